@@ -0,0 +1,4 @@
+-- Track when a transcript segment was soft-deleted so it can be purged later
+ALTER TABLE transcript_segments ADD COLUMN deleted_at TIMESTAMP;
+
+UPDATE transcript_segments SET deleted_at = CURRENT_TIMESTAMP WHERE is_deleted = 1;
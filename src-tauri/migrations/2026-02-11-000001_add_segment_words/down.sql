@@ -0,0 +1 @@
+ALTER TABLE transcript_segments DROP COLUMN words;
@@ -0,0 +1,3 @@
+-- Per-word timing and confidence, stored compactly as a JSON array of
+-- [word, start, end, confidence] tuples
+ALTER TABLE transcript_segments ADD COLUMN words TEXT;
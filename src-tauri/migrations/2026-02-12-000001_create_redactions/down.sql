@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS audio_derivatives;
+DROP TABLE IF EXISTS redactions;
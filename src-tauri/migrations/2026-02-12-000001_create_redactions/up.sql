@@ -0,0 +1,27 @@
+-- Create redactions table (time ranges to silence or bleep before disclosure)
+CREATE TABLE redactions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    audio_file_id INTEGER NOT NULL,
+    start_time REAL NOT NULL,
+    end_time REAL NOT NULL,
+    mode TEXT NOT NULL DEFAULT 'silence',
+    reason TEXT NOT NULL,
+    created_by INTEGER,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (audio_file_id) REFERENCES audio_files(id) ON DELETE CASCADE,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+-- Create audio_derivatives table (generated files; the original evidence is never modified)
+CREATE TABLE audio_derivatives (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    audio_file_id INTEGER NOT NULL,
+    kind TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    sha256 TEXT NOT NULL,
+    params TEXT,
+    created_by INTEGER,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (audio_file_id) REFERENCES audio_files(id) ON DELETE CASCADE,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL
+);
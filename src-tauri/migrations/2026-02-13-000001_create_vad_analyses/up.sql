@@ -0,0 +1,14 @@
+-- Create vad_analyses table (voice activity detection result per audio file)
+-- regions and long_gaps are JSON arrays of [start, end] pairs in seconds
+CREATE TABLE vad_analyses (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    audio_file_id INTEGER NOT NULL UNIQUE,
+    duration REAL NOT NULL,
+    speech_duration REAL NOT NULL,
+    speech_ratio REAL NOT NULL,
+    regions TEXT NOT NULL,
+    long_gaps TEXT NOT NULL,
+    params TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (audio_file_id) REFERENCES audio_files(id) ON DELETE CASCADE
+);
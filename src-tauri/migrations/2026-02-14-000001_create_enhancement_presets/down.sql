@@ -0,0 +1 @@
+DROP TABLE IF EXISTS enhancement_presets;
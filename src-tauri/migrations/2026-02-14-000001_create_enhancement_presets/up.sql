@@ -0,0 +1,10 @@
+-- Create enhancement_presets table (audio enhancement parameters per audio file, JSON)
+CREATE TABLE enhancement_presets (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    audio_file_id INTEGER NOT NULL UNIQUE,
+    params TEXT NOT NULL,
+    updated_by INTEGER,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (audio_file_id) REFERENCES audio_files(id) ON DELETE CASCADE,
+    FOREIGN KEY (updated_by) REFERENCES users(id) ON DELETE SET NULL
+);
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS channel_speakers;
+ALTER TABLE transcript_segments DROP COLUMN channel;
+ALTER TABLE audio_files DROP COLUMN channels;
@@ -0,0 +1,15 @@
+-- Channel count of each recording and the channel each segment was transcribed from
+ALTER TABLE audio_files ADD COLUMN channels INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE transcript_segments ADD COLUMN channel INTEGER;
+
+-- Create channel_speakers table (which speaker is on which channel of a recording)
+CREATE TABLE channel_speakers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    audio_file_id INTEGER NOT NULL,
+    channel INTEGER NOT NULL,
+    speaker_id INTEGER NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (audio_file_id, channel),
+    FOREIGN KEY (audio_file_id) REFERENCES audio_files(id) ON DELETE CASCADE,
+    FOREIGN KEY (speaker_id) REFERENCES speakers(id) ON DELETE CASCADE
+);
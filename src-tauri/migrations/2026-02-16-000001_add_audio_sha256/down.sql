@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_audio_files_sha256;
+ALTER TABLE audio_files DROP COLUMN sha256;
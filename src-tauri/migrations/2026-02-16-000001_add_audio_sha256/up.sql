@@ -0,0 +1,4 @@
+-- Content hash of each audio file for integrity checks and duplicate detection
+ALTER TABLE audio_files ADD COLUMN sha256 TEXT;
+
+CREATE INDEX idx_audio_files_sha256 ON audio_files(sha256);
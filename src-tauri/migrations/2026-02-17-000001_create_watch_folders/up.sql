@@ -0,0 +1,15 @@
+-- Create watch_folders table (drop folders ingested automatically into a case)
+CREATE TABLE watch_folders (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    path TEXT NOT NULL UNIQUE,
+    case_id INTEGER NOT NULL,
+    file_pattern TEXT NOT NULL DEFAULT '*',
+    post_action TEXT NOT NULL DEFAULT 'leave',
+    move_to TEXT,
+    is_active INTEGER NOT NULL DEFAULT 1,
+    last_scan_at TIMESTAMP,
+    created_by INTEGER,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (case_id) REFERENCES cases(id) ON DELETE CASCADE,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL
+);
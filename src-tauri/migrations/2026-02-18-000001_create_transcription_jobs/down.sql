@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS transcription_chunks;
+DROP TABLE IF EXISTS transcription_jobs;
@@ -0,0 +1,30 @@
+-- Create transcription_jobs table (chunked transcription of long recordings)
+CREATE TABLE transcription_jobs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    audio_file_id INTEGER NOT NULL,
+    status TEXT NOT NULL DEFAULT 'running',
+    params TEXT NOT NULL,
+    total_chunks INTEGER NOT NULL,
+    segment_count INTEGER NOT NULL DEFAULT 0,
+    error TEXT,
+    created_by INTEGER,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    completed_at TIMESTAMP,
+    FOREIGN KEY (audio_file_id) REFERENCES audio_files(id) ON DELETE CASCADE,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+-- Create transcription_chunks table (one overlapping window of a job; output kept for resuming)
+CREATE TABLE transcription_chunks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    job_id INTEGER NOT NULL,
+    chunk_index INTEGER NOT NULL,
+    start_time REAL NOT NULL,
+    end_time REAL NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    segments TEXT,
+    error TEXT,
+    completed_at TIMESTAMP,
+    UNIQUE (job_id, chunk_index),
+    FOREIGN KEY (job_id) REFERENCES transcription_jobs(id) ON DELETE CASCADE
+);
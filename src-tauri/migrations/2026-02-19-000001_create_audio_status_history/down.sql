@@ -0,0 +1,9 @@
+DROP TABLE IF EXISTS audio_status_history;
+
+UPDATE audio_files SET status = CASE status
+    WHEN 'uploaded' THEN 'pending'
+    WHEN 'queued' THEN 'pending'
+    WHEN 'processing' THEN 'processing'
+    WHEN 'failed' THEN 'error'
+    ELSE 'completed'
+END;
@@ -0,0 +1,23 @@
+-- Map free-form statuses onto the audio file lifecycle
+UPDATE audio_files SET status = CASE status
+    WHEN 'pending' THEN 'uploaded'
+    WHEN 'processing' THEN 'processing'
+    WHEN 'completed' THEN 'transcribed'
+    WHEN 'error' THEN 'failed'
+    ELSE 'uploaded'
+END;
+
+-- Create audio_status_history table (who moved an audio file to which status, and when)
+CREATE TABLE audio_status_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    audio_file_id INTEGER NOT NULL,
+    from_status TEXT NOT NULL,
+    to_status TEXT NOT NULL,
+    reason TEXT,
+    changed_by INTEGER,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (audio_file_id) REFERENCES audio_files(id) ON DELETE CASCADE,
+    FOREIGN KEY (changed_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_audio_status_history_audio_file_id ON audio_status_history(audio_file_id);
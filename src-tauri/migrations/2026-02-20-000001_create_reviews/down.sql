@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS review_comments;
+DROP TABLE IF EXISTS review_assignments;
+ALTER TABLE notifications DROP COLUMN user_id;
@@ -0,0 +1,33 @@
+-- Notifications addressed to one user (NULL = everyone)
+ALTER TABLE notifications ADD COLUMN user_id INTEGER REFERENCES users(id) ON DELETE CASCADE;
+
+-- Create review_assignments table (second-analyst review of an audio file's transcript)
+CREATE TABLE review_assignments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    audio_file_id INTEGER NOT NULL,
+    reviewer_id INTEGER NOT NULL,
+    assigned_by INTEGER,
+    status TEXT NOT NULL DEFAULT 'pending',
+    decision_reason TEXT,
+    decided_at TIMESTAMP,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (audio_file_id) REFERENCES audio_files(id) ON DELETE CASCADE,
+    FOREIGN KEY (reviewer_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (assigned_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+-- Create review_comments table (reviewer remarks anchored to a transcript segment)
+CREATE TABLE review_comments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    review_id INTEGER NOT NULL,
+    segment_id INTEGER NOT NULL,
+    author_id INTEGER,
+    body TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (review_id) REFERENCES review_assignments(id) ON DELETE CASCADE,
+    FOREIGN KEY (segment_id) REFERENCES transcript_segments(id) ON DELETE CASCADE,
+    FOREIGN KEY (author_id) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_review_assignments_audio_file_id ON review_assignments(audio_file_id);
+CREATE INDEX idx_review_assignments_reviewer_id ON review_assignments(reviewer_id);
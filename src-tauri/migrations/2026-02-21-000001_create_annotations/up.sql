@@ -0,0 +1,21 @@
+-- Create annotations table (analyst notes on a transcript segment or a free time range of an audio file)
+CREATE TABLE annotations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    audio_file_id INTEGER NOT NULL,
+    segment_id INTEGER,
+    start_time REAL NOT NULL,
+    end_time REAL NOT NULL,
+    author_id INTEGER,
+    body TEXT NOT NULL,
+    tags TEXT NOT NULL DEFAULT '[]',
+    color TEXT,
+    is_resolved INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (audio_file_id) REFERENCES audio_files(id) ON DELETE CASCADE,
+    FOREIGN KEY (segment_id) REFERENCES transcript_segments(id) ON DELETE SET NULL,
+    FOREIGN KEY (author_id) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_annotations_audio_file_id ON annotations(audio_file_id);
+CREATE INDEX idx_annotations_segment_id ON annotations(segment_id);
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS bookmark_segments;
+DROP TABLE IF EXISTS bookmarks;
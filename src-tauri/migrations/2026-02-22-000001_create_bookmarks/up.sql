@@ -0,0 +1,25 @@
+-- Create bookmarks table (important moments of a recording, curated by investigators)
+CREATE TABLE bookmarks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    audio_file_id INTEGER NOT NULL,
+    start_time REAL NOT NULL,
+    end_time REAL NOT NULL,
+    title TEXT NOT NULL,
+    description TEXT,
+    severity TEXT NOT NULL DEFAULT 'medium',
+    created_by INTEGER,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (audio_file_id) REFERENCES audio_files(id) ON DELETE CASCADE,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+-- Create bookmark_segments table (transcript segments linked to a bookmark)
+CREATE TABLE bookmark_segments (
+    bookmark_id INTEGER NOT NULL,
+    segment_id INTEGER NOT NULL,
+    PRIMARY KEY (bookmark_id, segment_id),
+    FOREIGN KEY (bookmark_id) REFERENCES bookmarks(id) ON DELETE CASCADE,
+    FOREIGN KEY (segment_id) REFERENCES transcript_segments(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_bookmarks_audio_file_id ON bookmarks(audio_file_id);
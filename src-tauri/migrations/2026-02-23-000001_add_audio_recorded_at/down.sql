@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS recording_time_patterns;
+ALTER TABLE audio_files DROP COLUMN recorded_at_source;
+ALTER TABLE audio_files DROP COLUMN recorded_tz;
+ALTER TABLE audio_files DROP COLUMN recorded_at;
@@ -0,0 +1,19 @@
+-- Real-world start of the recording (UTC), the UTC offset it was made in and where it came from
+ALTER TABLE audio_files ADD COLUMN recorded_at TIMESTAMP;
+ALTER TABLE audio_files ADD COLUMN recorded_tz TEXT;
+ALTER TABLE audio_files ADD COLUMN recorded_at_source TEXT;
+
+-- Create recording_time_patterns table (chrono formats searched for in file names)
+CREATE TABLE recording_time_patterns (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    pattern TEXT NOT NULL UNIQUE,
+    timezone TEXT NOT NULL DEFAULT '+07:00',
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Common recorder and phone naming schemes, e.g. 20260103_142210.amr
+INSERT INTO recording_time_patterns (pattern) VALUES
+    ('%Y%m%d_%H%M%S'),
+    ('%Y%m%d-%H%M%S'),
+    ('%Y-%m-%d_%H-%M-%S'),
+    ('%Y-%m-%d %H-%M-%S');
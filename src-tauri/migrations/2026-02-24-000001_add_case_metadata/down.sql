@@ -0,0 +1,25 @@
+DROP TRIGGER IF EXISTS trg_cases_touch;
+DROP TRIGGER IF EXISTS trg_audio_files_insert_touch_case;
+DROP TRIGGER IF EXISTS trg_audio_files_update_touch_case;
+DROP TRIGGER IF EXISTS trg_audio_files_delete_touch_case;
+DROP TRIGGER IF EXISTS trg_case_assignments_insert_touch_case;
+DROP TRIGGER IF EXISTS trg_case_assignments_update_touch_case;
+DROP TRIGGER IF EXISTS trg_case_assignments_delete_touch_case;
+DROP TRIGGER IF EXISTS trg_transcript_segments_insert_touch_case;
+DROP TRIGGER IF EXISTS trg_transcript_segments_update_touch_case;
+DROP TRIGGER IF EXISTS trg_transcript_segments_delete_touch_case;
+DROP TRIGGER IF EXISTS trg_annotations_insert_touch_case;
+DROP TRIGGER IF EXISTS trg_annotations_update_touch_case;
+DROP TRIGGER IF EXISTS trg_annotations_delete_touch_case;
+DROP TRIGGER IF EXISTS trg_bookmarks_insert_touch_case;
+DROP TRIGGER IF EXISTS trg_bookmarks_update_touch_case;
+DROP TRIGGER IF EXISTS trg_bookmarks_delete_touch_case;
+DROP TRIGGER IF EXISTS trg_redactions_insert_touch_case;
+DROP TRIGGER IF EXISTS trg_redactions_update_touch_case;
+DROP TRIGGER IF EXISTS trg_redactions_delete_touch_case;
+DROP TABLE IF EXISTS case_assignments;
+DROP INDEX IF EXISTS idx_cases_status;
+ALTER TABLE cases DROP COLUMN due_date;
+ALTER TABLE cases DROP COLUMN tags;
+ALTER TABLE cases DROP COLUMN priority;
+ALTER TABLE cases DROP COLUMN status;
@@ -0,0 +1,117 @@
+-- Case workflow metadata
+ALTER TABLE cases ADD COLUMN status TEXT NOT NULL DEFAULT 'open';
+ALTER TABLE cases ADD COLUMN priority TEXT NOT NULL DEFAULT 'medium';
+ALTER TABLE cases ADD COLUMN tags TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE cases ADD COLUMN due_date DATE;
+
+-- Create case_assignments table (users assigned to work on a case)
+CREATE TABLE case_assignments (
+    case_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    assigned_by INTEGER,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (case_id, user_id),
+    FOREIGN KEY (case_id) REFERENCES cases(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (assigned_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_case_assignments_user_id ON case_assignments(user_id);
+CREATE INDEX idx_cases_status ON cases(status);
+
+-- Keep cases.updated_at current on every change to the case or its children
+CREATE TRIGGER trg_cases_touch AFTER UPDATE ON cases
+FOR EACH ROW WHEN NEW.updated_at = OLD.updated_at
+BEGIN
+    UPDATE cases SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER trg_audio_files_insert_touch_case AFTER INSERT ON audio_files
+BEGIN
+    UPDATE cases SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.case_id;
+END;
+
+CREATE TRIGGER trg_audio_files_update_touch_case AFTER UPDATE ON audio_files
+BEGIN
+    UPDATE cases SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.case_id;
+END;
+
+CREATE TRIGGER trg_audio_files_delete_touch_case AFTER DELETE ON audio_files
+BEGIN
+    UPDATE cases SET updated_at = CURRENT_TIMESTAMP WHERE id = OLD.case_id;
+END;
+
+CREATE TRIGGER trg_case_assignments_insert_touch_case AFTER INSERT ON case_assignments
+BEGIN
+    UPDATE cases SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.case_id;
+END;
+
+CREATE TRIGGER trg_case_assignments_update_touch_case AFTER UPDATE ON case_assignments
+BEGIN
+    UPDATE cases SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.case_id;
+END;
+
+CREATE TRIGGER trg_case_assignments_delete_touch_case AFTER DELETE ON case_assignments
+BEGIN
+    UPDATE cases SET updated_at = CURRENT_TIMESTAMP WHERE id = OLD.case_id;
+END;
+
+CREATE TRIGGER trg_transcript_segments_insert_touch_case AFTER INSERT ON transcript_segments
+BEGIN
+    UPDATE cases SET updated_at = CURRENT_TIMESTAMP WHERE id = (SELECT case_id FROM audio_files WHERE id = NEW.audio_file_id);
+END;
+
+CREATE TRIGGER trg_transcript_segments_update_touch_case AFTER UPDATE ON transcript_segments
+BEGIN
+    UPDATE cases SET updated_at = CURRENT_TIMESTAMP WHERE id = (SELECT case_id FROM audio_files WHERE id = NEW.audio_file_id);
+END;
+
+CREATE TRIGGER trg_transcript_segments_delete_touch_case AFTER DELETE ON transcript_segments
+BEGIN
+    UPDATE cases SET updated_at = CURRENT_TIMESTAMP WHERE id = (SELECT case_id FROM audio_files WHERE id = OLD.audio_file_id);
+END;
+
+CREATE TRIGGER trg_annotations_insert_touch_case AFTER INSERT ON annotations
+BEGIN
+    UPDATE cases SET updated_at = CURRENT_TIMESTAMP WHERE id = (SELECT case_id FROM audio_files WHERE id = NEW.audio_file_id);
+END;
+
+CREATE TRIGGER trg_annotations_update_touch_case AFTER UPDATE ON annotations
+BEGIN
+    UPDATE cases SET updated_at = CURRENT_TIMESTAMP WHERE id = (SELECT case_id FROM audio_files WHERE id = NEW.audio_file_id);
+END;
+
+CREATE TRIGGER trg_annotations_delete_touch_case AFTER DELETE ON annotations
+BEGIN
+    UPDATE cases SET updated_at = CURRENT_TIMESTAMP WHERE id = (SELECT case_id FROM audio_files WHERE id = OLD.audio_file_id);
+END;
+
+CREATE TRIGGER trg_bookmarks_insert_touch_case AFTER INSERT ON bookmarks
+BEGIN
+    UPDATE cases SET updated_at = CURRENT_TIMESTAMP WHERE id = (SELECT case_id FROM audio_files WHERE id = NEW.audio_file_id);
+END;
+
+CREATE TRIGGER trg_bookmarks_update_touch_case AFTER UPDATE ON bookmarks
+BEGIN
+    UPDATE cases SET updated_at = CURRENT_TIMESTAMP WHERE id = (SELECT case_id FROM audio_files WHERE id = NEW.audio_file_id);
+END;
+
+CREATE TRIGGER trg_bookmarks_delete_touch_case AFTER DELETE ON bookmarks
+BEGIN
+    UPDATE cases SET updated_at = CURRENT_TIMESTAMP WHERE id = (SELECT case_id FROM audio_files WHERE id = OLD.audio_file_id);
+END;
+
+CREATE TRIGGER trg_redactions_insert_touch_case AFTER INSERT ON redactions
+BEGIN
+    UPDATE cases SET updated_at = CURRENT_TIMESTAMP WHERE id = (SELECT case_id FROM audio_files WHERE id = NEW.audio_file_id);
+END;
+
+CREATE TRIGGER trg_redactions_update_touch_case AFTER UPDATE ON redactions
+BEGIN
+    UPDATE cases SET updated_at = CURRENT_TIMESTAMP WHERE id = (SELECT case_id FROM audio_files WHERE id = NEW.audio_file_id);
+END;
+
+CREATE TRIGGER trg_redactions_delete_touch_case AFTER DELETE ON redactions
+BEGIN
+    UPDATE cases SET updated_at = CURRENT_TIMESTAMP WHERE id = (SELECT case_id FROM audio_files WHERE id = OLD.audio_file_id);
+END;
@@ -0,0 +1,21 @@
+-- Create case_members table (who may access a case, and how)
+CREATE TABLE case_members (
+    case_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    role TEXT NOT NULL DEFAULT 'reader',
+    added_by INTEGER,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (case_id, user_id),
+    FOREIGN KEY (case_id) REFERENCES cases(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (added_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX idx_case_members_user_id ON case_members(user_id);
+
+-- Existing cases were visible to everyone: keep that access until owners trim it
+-- (admins see every case without membership)
+INSERT INTO case_members (case_id, user_id, role)
+SELECT cases.id, users.id, CASE WHEN users.role = 'viewer' THEN 'reader' ELSE 'editor' END
+FROM cases CROSS JOIN users
+WHERE users.role != 'admin';
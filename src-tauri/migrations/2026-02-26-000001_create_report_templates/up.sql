@@ -0,0 +1,38 @@
+-- Create report_templates table (user-editable layouts for case reports)
+CREATE TABLE report_templates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+    name TEXT NOT NULL UNIQUE,
+    body TEXT NOT NULL,
+    is_default INTEGER NOT NULL DEFAULT 0,
+    created_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Lines starting with #, ## or ### are headings; other lines are paragraphs.
+-- {{case.code}} and similar fields can appear anywhere in a line; section fields
+-- such as {{recordings}} must stand on a line of their own.
+INSERT INTO report_templates (name, body, is_default) VALUES (
+    'Mặc định',
+    '# BÁO CÁO VỤ VIỆC {{case.code}}
+{{case.title}}
+Trạng thái: {{case.status}} - Mức ưu tiên: {{case.priority}}
+Nhãn: {{case.tags}}
+Hạn xử lý: {{case.due_date}}
+{{case.description}}
+Lập lúc {{report.generated_at}} bởi {{report.generated_by}}
+## 1. Danh sách ghi âm
+Tổng số: {{report.recording_count}} tệp, {{report.total_duration}}
+{{recordings}}
+## 2. Nội dung ghi âm
+{{transcripts}}
+## 3. Từ ngữ cảnh báo
+{{alert_hits}}
+## 4. Đánh dấu
+{{bookmarks}}
+## 5. Ghi chú
+{{annotations}}
+## Phụ lục: Lịch sử lưu giữ chứng cứ
+{{custody}}',
+    1
+);
@@ -0,0 +1 @@
+ALTER TABLE transcription_chunks DROP COLUMN channel;
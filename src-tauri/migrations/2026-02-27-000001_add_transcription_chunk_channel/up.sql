@@ -0,0 +1,2 @@
+-- Channel a chunk transcribes (NULL for the mixed-down recording)
+ALTER TABLE transcription_chunks ADD COLUMN channel INTEGER;
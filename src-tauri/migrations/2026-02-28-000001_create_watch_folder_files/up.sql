@@ -0,0 +1,11 @@
+-- Files a watch folder already handled, so files left in place are not
+-- ingested again after a restart
+CREATE TABLE watch_folder_files (
+    watch_folder_id INTEGER NOT NULL,
+    file_path TEXT NOT NULL,
+    file_size BIGINT NOT NULL,
+    modified_ms BIGINT,
+    handled_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (watch_folder_id, file_path),
+    FOREIGN KEY (watch_folder_id) REFERENCES watch_folders(id) ON DELETE CASCADE
+);
@@ -0,0 +1,6 @@
+DROP TRIGGER IF EXISTS trg_audio_files_update_touch_case;
+
+CREATE TRIGGER trg_audio_files_update_touch_case AFTER UPDATE ON audio_files
+BEGIN
+    UPDATE cases SET updated_at = CURRENT_TIMESTAMP WHERE id = NEW.case_id;
+END;
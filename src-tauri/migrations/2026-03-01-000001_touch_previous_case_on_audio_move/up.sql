@@ -0,0 +1,7 @@
+-- Moving an audio file to another case changes both cases
+DROP TRIGGER IF EXISTS trg_audio_files_update_touch_case;
+
+CREATE TRIGGER trg_audio_files_update_touch_case AFTER UPDATE ON audio_files
+BEGIN
+    UPDATE cases SET updated_at = CURRENT_TIMESTAMP WHERE id IN (OLD.case_id, NEW.case_id);
+END;
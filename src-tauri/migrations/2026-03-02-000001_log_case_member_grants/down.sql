@@ -0,0 +1,3 @@
+DELETE FROM activity_logs
+WHERE target_type = 'case_access'
+  AND json_extract(details, '$.via') = 'migration';
@@ -0,0 +1,23 @@
+-- Memberships granted without an audit entry (the case_members backfill, case
+-- creation and assignment) get a case_access grant so the access log is complete
+INSERT INTO activity_logs (user_id, action, target_type, target_id, details, created_at)
+SELECT
+    case_members.added_by,
+    'create',
+    'case_access',
+    case_members.case_id,
+    json_object(
+        'operation', 'grant_access',
+        'member_id', case_members.user_id,
+        'from', NULL,
+        'to', case_members.role,
+        'via', 'migration'
+    ),
+    case_members.created_at
+FROM case_members
+WHERE NOT EXISTS (
+    SELECT 1 FROM activity_logs
+    WHERE activity_logs.target_type = 'case_access'
+      AND activity_logs.target_id = case_members.case_id
+      AND json_extract(activity_logs.details, '$.member_id') = case_members.user_id
+);
@@ -1,5 +1,6 @@
 //! Activity log commands
-use crate::models::{ActivityLog, NewActivityLog};
+use crate::models::pagination::invalid_sort;
+use crate::models::{ActivityLog, ListQuery, NewActivityLog, Page, SortDirection};
 use crate::schema::activity_logs;
 use crate::services::get_pool;
 use diesel::prelude::*;
@@ -14,35 +15,91 @@ pub struct CreateActivityLogInput {
     pub details: Option<String>,
 }
 
-/// Get all activity logs (newest first)
+/// Activity logs matching an optional action and the query's search and date range
+fn log_query(
+    action: Option<&str>,
+    query: &ListQuery,
+) -> activity_logs::BoxedQuery<'static, diesel::sqlite::Sqlite> {
+    let mut boxed = activity_logs::table.into_boxed();
+    if let Some(action) = action {
+        boxed = boxed.filter(activity_logs::action.eq(action.to_string()));
+    }
+    if let Some(pattern) = query.search_pattern() {
+        boxed = boxed.filter(
+            activity_logs::target_type
+                .like(pattern.clone())
+                .escape('\\')
+                .or(activity_logs::details.like(pattern).escape('\\')),
+        );
+    }
+    if let Some(from) = query.date_from {
+        boxed = boxed.filter(activity_logs::created_at.ge(from));
+    }
+    if let Some(to) = query.date_to {
+        boxed = boxed.filter(activity_logs::created_at.le(to));
+    }
+    boxed
+}
+
+fn load_log_page(
+    conn: &mut SqliteConnection,
+    action: Option<&str>,
+    query: ListQuery,
+    default_page_size: i64,
+) -> Result<Page<ActivityLog>, String> {
+    let page_size = query.page_size(default_page_size);
+    let offset = query.offset(page_size);
+
+    let total = log_query(action, &query)
+        .count()
+        .get_result::<i64>(conn)
+        .map_err(|e| e.to_string())?;
+
+    let desc = query.descending(SortDirection::Desc);
+    let sorted = match query.sort_field("created_at") {
+        "created_at" if desc => log_query(action, &query).order(activity_logs::created_at.desc()),
+        "created_at" => log_query(action, &query).order(activity_logs::created_at.asc()),
+        "action" if desc => log_query(action, &query).order(activity_logs::action.desc()),
+        "action" => log_query(action, &query).order(activity_logs::action.asc()),
+        "target_type" if desc => log_query(action, &query).order(activity_logs::target_type.desc()),
+        "target_type" => log_query(action, &query).order(activity_logs::target_type.asc()),
+        field => return Err(invalid_sort(field)),
+    };
+
+    let items = sorted
+        .then_order_by(activity_logs::id.desc())
+        .limit(page_size)
+        .offset(offset)
+        .load::<ActivityLog>(conn)
+        .map_err(|e| e.to_string())?;
+
+    Ok(Page::new(items, total, offset, page_size))
+}
+
+/// Get a page of activity logs (newest first, 100 per page by default).
+/// Sortable by created_at, action or target_type.
 #[tauri::command]
-pub fn get_activity_logs() -> Result<Vec<ActivityLog>, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+pub fn get_activity_logs(query: Option<ListQuery>) -> Result<Page<ActivityLog>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
-    activity_logs::table
-        .order(activity_logs::created_at.desc())
-        .limit(100) // Limit to last 100 entries
-        .load::<ActivityLog>(&mut conn)
-        .map_err(|e| e.to_string())
+    load_log_page(&mut conn, None, query.unwrap_or_default(), 100)
 }
 
-/// Get activity logs by action type
+/// Get a page of activity logs of one action type (50 per page by default)
 #[tauri::command]
-pub fn get_activity_logs_by_action(action: String) -> Result<Vec<ActivityLog>, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+pub fn get_activity_logs_by_action(
+    action: String,
+    query: Option<ListQuery>,
+) -> Result<Page<ActivityLog>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
-    activity_logs::table
-        .filter(activity_logs::action.eq(action))
-        .order(activity_logs::created_at.desc())
-        .limit(50)
-        .load::<ActivityLog>(&mut conn)
-        .map_err(|e| e.to_string())
+    load_log_page(&mut conn, Some(&action), query.unwrap_or_default(), 50)
 }
 
 /// Create a new activity log entry
 #[tauri::command]
 pub fn create_activity_log(input: CreateActivityLogInput) -> Result<ActivityLog, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
     let new_log = NewActivityLog {
         user_id: input.user_id,
@@ -66,7 +123,7 @@ pub fn create_activity_log(input: CreateActivityLogInput) -> Result<ActivityLog,
 /// Delete old activity logs (older than specified days)
 #[tauri::command]
 pub fn cleanup_old_logs(days: i32) -> Result<usize, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
     let cutoff_date = chrono::Utc::now().naive_utc() - chrono::Duration::days(days as i64);
 
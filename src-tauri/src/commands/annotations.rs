@@ -0,0 +1,239 @@
+//! Annotation commands (notes anchored to transcript segments or time ranges)
+use crate::models::tags::encode_tags;
+use crate::models::{Annotation, NewAnnotation, TranscriptSegment};
+use crate::schema::{annotations, transcript_segments};
+use crate::services::access_service::{require_audio_role, CaseRole};
+use crate::services::activity_service::log_activity;
+use crate::services::get_pool;
+use diesel::prelude::*;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateAnnotationInput {
+    pub audio_file_id: i32,
+    /// Anchor to a segment (its time range is used); otherwise start/end are required
+    pub segment_id: Option<i32>,
+    pub start_time: Option<f32>,
+    pub end_time: Option<f32>,
+    pub body: String,
+    pub tags: Option<Vec<String>>,
+    pub color: Option<String>,
+    pub user_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateAnnotationInput {
+    pub body: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub color: Option<String>,
+    pub is_resolved: Option<bool>,
+    pub user_id: Option<i32>,
+}
+
+/// Accept "#rgb" or "#rrggbb" colours
+fn validate_color(color: &str) -> Result<(), String> {
+    let hex = color.strip_prefix('#').unwrap_or("");
+    if (hex.len() == 3 || hex.len() == 6) && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(())
+    } else {
+        Err(format!("Invalid annotation colour: {}", color))
+    }
+}
+
+/// Get the annotations of an audio file ordered by time, optionally hiding resolved ones
+#[tauri::command]
+pub fn get_annotations(
+    audio_file_id: i32,
+    include_resolved: Option<bool>,
+    user_id: Option<i32>,
+) -> Result<Vec<Annotation>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Reader)?;
+
+    let mut query = annotations::table
+        .filter(annotations::audio_file_id.eq(audio_file_id))
+        .into_boxed();
+    if !include_resolved.unwrap_or(true) {
+        query = query.filter(annotations::is_resolved.eq(0));
+    }
+
+    query
+        .order((annotations::start_time.asc(), annotations::id.asc()))
+        .load::<Annotation>(&mut conn)
+        .map_err(|e| e.to_string())
+}
+
+/// Annotate a transcript segment or a free time range of an audio file
+#[tauri::command]
+pub fn create_annotation(input: CreateAnnotationInput) -> Result<Annotation, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    if input.body.trim().is_empty() {
+        return Err("Annotation is empty".to_string());
+    }
+    if let Some(color) = &input.color {
+        validate_color(color)?;
+    }
+
+    require_audio_role(
+        &mut conn,
+        input.user_id,
+        input.audio_file_id,
+        CaseRole::Editor,
+    )?;
+
+    let (start_time, end_time) = match input.segment_id {
+        Some(segment_id) => {
+            let segment = transcript_segments::table
+                .find(segment_id)
+                .first::<TranscriptSegment>(&mut conn)
+                .map_err(|e| format!("Segment not found: {}", e))?;
+            if segment.audio_file_id != input.audio_file_id {
+                return Err("Segment does not belong to the audio file".to_string());
+            }
+            (segment.start_time, segment.end_time)
+        }
+        None => match (input.start_time, input.end_time) {
+            (Some(start), Some(end)) if start >= 0.0 && end >= start => (start, end),
+            (Some(_), Some(_)) => return Err("Invalid annotation time range".to_string()),
+            _ => return Err("A segment or a time range is required".to_string()),
+        },
+    };
+
+    let new_annotation = NewAnnotation {
+        audio_file_id: input.audio_file_id,
+        segment_id: input.segment_id,
+        start_time,
+        end_time,
+        author_id: input.user_id,
+        body: input.body,
+        tags: encode_tags(&input.tags.unwrap_or_default()),
+        color: input.color,
+    };
+
+    conn.transaction::<Annotation, diesel::result::Error, _>(|conn| {
+        diesel::insert_into(annotations::table)
+            .values(&new_annotation)
+            .execute(conn)?;
+
+        let annotation = annotations::table
+            .order(annotations::id.desc())
+            .first::<Annotation>(conn)?;
+
+        log_activity(
+            conn,
+            input.user_id,
+            "create",
+            "annotation",
+            Some(annotation.id),
+            serde_json::to_string(&annotation).ok(),
+        )?;
+
+        Ok(annotation)
+    })
+    .map_err(|e| format!("Failed to create annotation: {}", e))
+}
+
+/// Update an annotation's body, tags, colour or resolved flag
+#[tauri::command]
+pub fn update_annotation(id: i32, input: UpdateAnnotationInput) -> Result<Annotation, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+    let target = annotations::table.find(id);
+
+    let annotation = target
+        .first::<Annotation>(&mut conn)
+        .map_err(|e| format!("Annotation not found: {}", e))?;
+    require_audio_role(
+        &mut conn,
+        input.user_id,
+        annotation.audio_file_id,
+        CaseRole::Editor,
+    )?;
+
+    if input
+        .body
+        .as_deref()
+        .is_some_and(|body| body.trim().is_empty())
+    {
+        return Err("Annotation is empty".to_string());
+    }
+    if let Some(color) = &input.color {
+        validate_color(color)?;
+    }
+
+    conn.transaction::<Annotation, diesel::result::Error, _>(|conn| {
+        if let Some(body) = &input.body {
+            diesel::update(target)
+                .set(annotations::body.eq(body))
+                .execute(conn)?;
+        }
+
+        if let Some(tags) = &input.tags {
+            diesel::update(target)
+                .set(annotations::tags.eq(encode_tags(tags)))
+                .execute(conn)?;
+        }
+
+        if let Some(color) = &input.color {
+            diesel::update(target)
+                .set(annotations::color.eq(color))
+                .execute(conn)?;
+        }
+
+        if let Some(is_resolved) = input.is_resolved {
+            diesel::update(target)
+                .set(annotations::is_resolved.eq(if is_resolved { 1 } else { 0 }))
+                .execute(conn)?;
+        }
+
+        diesel::update(target)
+            .set(annotations::updated_at.eq(chrono::Utc::now().naive_utc()))
+            .execute(conn)?;
+
+        let annotation = target.first::<Annotation>(conn)?;
+
+        log_activity(
+            conn,
+            input.user_id,
+            "edit",
+            "annotation",
+            Some(annotation.id),
+            serde_json::to_string(&annotation).ok(),
+        )?;
+
+        Ok(annotation)
+    })
+    .map_err(|e| format!("Failed to update annotation: {}", e))
+}
+
+/// Delete an annotation (kept in the activity log)
+#[tauri::command]
+pub fn delete_annotation(id: i32, user_id: Option<i32>) -> Result<(), String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    let annotation = annotations::table
+        .find(id)
+        .first::<Annotation>(&mut conn)
+        .map_err(|e| format!("Annotation not found: {}", e))?;
+    require_audio_role(
+        &mut conn,
+        user_id,
+        annotation.audio_file_id,
+        CaseRole::Editor,
+    )?;
+
+    conn.transaction::<(), diesel::result::Error, _>(|conn| {
+        diesel::delete(annotations::table.find(id)).execute(conn)?;
+
+        log_activity(
+            conn,
+            user_id,
+            "delete",
+            "annotation",
+            Some(annotation.id),
+            serde_json::to_string(&annotation).ok(),
+        )
+    })
+    .map_err(|e| format!("Failed to delete annotation: {}", e))
+}
@@ -1,7 +1,17 @@
 //! Audio file commands
-use crate::models::{AudioFile, NewAudioFile};
-use crate::schema::audio_files;
+use crate::models::{AudioDerivative, AudioFile, AudioStatusChange, NewAudioFile};
+use crate::schema::{audio_derivatives, audio_files, audio_status_history};
+use crate::services::access_service::{require_audio_role, require_case_role, CaseRole};
+use crate::services::ffmpeg::probe_channels;
 use crate::services::get_pool;
+use crate::services::recording_time_service::refresh_recorded_at;
+use crate::services::status_service::{
+    check_manual_transition, record_transition, transition_error, AudioStatus,
+};
+use crate::services::storage::sha256_file;
+use crate::services::working_copy_service::{
+    ensure_working_copies, playback_path, queue_working_copies,
+};
 use diesel::prelude::*;
 use serde::Deserialize;
 
@@ -12,6 +22,7 @@ pub struct CreateAudioInput {
     pub file_path: String,
     pub duration: Option<f32>,
     pub status: Option<String>,
+    pub user_id: Option<i32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -19,12 +30,15 @@ pub struct UpdateAudioInput {
     pub file_name: Option<String>,
     pub duration: Option<f32>,
     pub status: Option<String>,
+    pub user_id: Option<i32>,
 }
 
 /// Get all audio files for a case
 #[tauri::command]
-pub fn get_audio_files(case_id: i32) -> Result<Vec<AudioFile>, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+pub fn get_audio_files(case_id: i32, user_id: Option<i32>) -> Result<Vec<AudioFile>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_case_role(&mut conn, user_id, case_id, CaseRole::Reader)?;
 
     audio_files::table
         .filter(audio_files::case_id.eq(case_id))
@@ -35,8 +49,10 @@ pub fn get_audio_files(case_id: i32) -> Result<Vec<AudioFile>, String> {
 
 /// Get a single audio file by ID
 #[tauri::command]
-pub fn get_audio_file(id: i32) -> Result<AudioFile, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+pub fn get_audio_file(id: i32, user_id: Option<i32>) -> Result<AudioFile, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, id, CaseRole::Reader)?;
 
     audio_files::table
         .find(id)
@@ -47,61 +63,154 @@ pub fn get_audio_file(id: i32) -> Result<AudioFile, String> {
 /// Upload/register a new audio file
 #[tauri::command]
 pub fn upload_audio(input: CreateAudioInput) -> Result<AudioFile, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_case_role(&mut conn, input.user_id, input.case_id, CaseRole::Editor)?;
+
+    // New files start as uploaded and may only be queued right away; later statuses
+    // are reached through the state machine
+    let status = match input.status {
+        Some(status) => status.parse::<AudioStatus>()?,
+        None => AudioStatus::Uploaded,
+    };
+    if !matches!(status, AudioStatus::Uploaded | AudioStatus::Queued) {
+        return Err(format!("A new audio file cannot start as {}", status));
+    }
+
+    // Channel count and hash are best effort; ffprobe may be unavailable or the file unreadable
+    let path = std::path::Path::new(&input.file_path);
+    let channels = probe_channels(path).unwrap_or(1);
+    let sha256 = sha256_file(path).ok();
 
     let new_audio = NewAudioFile {
         case_id: input.case_id,
         file_name: input.file_name,
         file_path: input.file_path,
         duration: input.duration.unwrap_or(0.0),
-        status: input.status.unwrap_or_else(|| "pending".to_string()),
+        status: AudioStatus::Uploaded.to_string(),
+        channels,
+        sha256,
     };
 
-    diesel::insert_into(audio_files::table)
-        .values(&new_audio)
-        .execute(&mut conn)
+    let audio = conn
+        .transaction::<AudioFile, diesel::result::Error, _>(|conn| {
+            diesel::insert_into(audio_files::table)
+                .values(&new_audio)
+                .execute(conn)?;
+
+            let audio = audio_files::table
+                .order(audio_files::id.desc())
+                .first::<AudioFile>(conn)?;
+
+            if status == AudioStatus::Uploaded {
+                return Ok(audio);
+            }
+            record_transition(
+                conn,
+                audio.id,
+                AudioStatus::Uploaded,
+                status,
+                input.user_id,
+                None,
+            )
+        })
         .map_err(|e| format!("Failed to create audio file: {}", e))?;
 
-    audio_files::table
-        .order(audio_files::id.desc())
-        .first::<AudioFile>(&mut conn)
-        .map_err(|e| e.to_string())
+    queue_working_copies(audio.id, input.user_id);
+    let audio = refresh_recorded_at(&mut conn, &audio, false).unwrap_or(audio);
+
+    Ok(audio)
 }
 
-/// Update audio file metadata
+/// Update audio file metadata. A status change is validated before anything is
+/// saved, and all changes are applied together.
 #[tauri::command]
 pub fn update_audio_file(id: i32, input: UpdateAudioInput) -> Result<AudioFile, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
-    let target = audio_files::table.find(id);
-
-    if let Some(file_name) = input.file_name {
-        diesel::update(target)
-            .set(audio_files::file_name.eq(file_name))
-            .execute(&mut conn)
-            .map_err(|e| e.to_string())?;
-    }
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
-    if let Some(duration) = input.duration {
-        diesel::update(target)
-            .set(audio_files::duration.eq(duration))
-            .execute(&mut conn)
-            .map_err(|e| e.to_string())?;
-    }
+    require_audio_role(&mut conn, input.user_id, id, CaseRole::Editor)?;
 
-    if let Some(status) = input.status {
-        diesel::update(target)
-            .set(audio_files::status.eq(status))
-            .execute(&mut conn)
-            .map_err(|e| e.to_string())?;
-    }
+    let audio = audio_files::table
+        .find(id)
+        .first::<AudioFile>(&mut conn)
+        .map_err(|e| format!("Audio file not found: {}", e))?;
+    let transition = match &input.status {
+        Some(status) => {
+            let to: AudioStatus = status.parse()?;
+            Some((check_manual_transition(&audio, to)?, to))
+        }
+        None => None,
+    };
+
+    conn.transaction::<AudioFile, diesel::result::Error, _>(|conn| {
+        let target = audio_files::table.find(id);
+
+        if let Some(file_name) = &input.file_name {
+            diesel::update(target)
+                .set(audio_files::file_name.eq(file_name))
+                .execute(conn)?;
+        }
+
+        if let Some(duration) = input.duration {
+            diesel::update(target)
+                .set(audio_files::duration.eq(duration))
+                .execute(conn)?;
+        }
+
+        match transition {
+            Some((from, to)) => record_transition(conn, id, from, to, input.user_id, None),
+            None => target.first::<AudioFile>(conn),
+        }
+    })
+    .map_err(transition_error)
+}
+
+/// Move an audio file to another status (illegal transitions are rejected;
+/// approving and reopening have their own commands)
+#[tauri::command]
+pub fn set_audio_status(
+    audio_file_id: i32,
+    status: AudioStatus,
+    user_id: Option<i32>,
+    reason: Option<String>,
+) -> Result<AudioFile, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Editor)?;
+
+    let audio = audio_files::table
+        .find(audio_file_id)
+        .first::<AudioFile>(&mut conn)
+        .map_err(|e| format!("Audio file not found: {}", e))?;
+    let from = check_manual_transition(&audio, status)?;
+
+    conn.transaction(|conn| record_transition(conn, audio_file_id, from, status, user_id, reason))
+        .map_err(transition_error)
+}
+
+/// Get the status transitions of an audio file (oldest first)
+#[tauri::command]
+pub fn get_audio_status_history(
+    audio_file_id: i32,
+    user_id: Option<i32>,
+) -> Result<Vec<AudioStatusChange>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Reader)?;
 
-    get_audio_file(id)
+    audio_status_history::table
+        .filter(audio_status_history::audio_file_id.eq(audio_file_id))
+        .order(audio_status_history::id.asc())
+        .load::<AudioStatusChange>(&mut conn)
+        .map_err(|e| e.to_string())
 }
 
 /// Delete an audio file
 #[tauri::command]
-pub fn delete_audio_file(id: i32) -> Result<(), String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+pub fn delete_audio_file(id: i32, user_id: Option<i32>) -> Result<(), String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, id, CaseRole::Editor)?;
 
     diesel::delete(audio_files::table.find(id))
         .execute(&mut conn)
@@ -109,3 +218,72 @@ pub fn delete_audio_file(id: i32) -> Result<(), String> {
 
     Ok(())
 }
+
+/// Get generated derivatives of an audio file, optionally of one kind
+#[tauri::command]
+pub fn get_audio_derivatives(
+    audio_file_id: i32,
+    kind: Option<String>,
+    user_id: Option<i32>,
+) -> Result<Vec<AudioDerivative>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Reader)?;
+
+    let mut query = audio_derivatives::table
+        .filter(audio_derivatives::audio_file_id.eq(audio_file_id))
+        .into_boxed();
+
+    if let Some(kind) = kind {
+        query = query.filter(audio_derivatives::kind.eq(kind));
+    }
+
+    query
+        .order(audio_derivatives::created_at.desc())
+        .load::<AudioDerivative>(&mut conn)
+        .map_err(|e| e.to_string())
+}
+
+/// Create (or reuse) the working and playback copies of an audio file.
+/// The original file is left untouched.
+#[tauri::command]
+pub async fn normalize_audio(
+    audio_file_id: i32,
+    user_id: Option<i32>,
+) -> Result<Vec<AudioDerivative>, String> {
+    // ffmpeg transcodes the working and playback copies
+    tauri::async_runtime::spawn_blocking(move || run_normalize(audio_file_id, user_id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn run_normalize(audio_file_id: i32, user_id: Option<i32>) -> Result<Vec<AudioDerivative>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Editor)?;
+
+    let audio = audio_files::table
+        .find(audio_file_id)
+        .first::<AudioFile>(&mut conn)
+        .map_err(|e| format!("Audio file not found: {}", e))?;
+
+    ensure_working_copies(&mut conn, &audio, user_id)
+}
+
+/// Get the path the player should load (a transcoded copy for formats the
+/// webview cannot play, otherwise the original)
+#[tauri::command]
+pub fn get_playback_path(audio_file_id: i32, user_id: Option<i32>) -> Result<String, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Reader)?;
+
+    let audio = audio_files::table
+        .find(audio_file_id)
+        .first::<AudioFile>(&mut conn)
+        .map_err(|e| format!("Audio file not found: {}", e))?;
+
+    Ok(playback_path(&mut conn, &audio)
+        .to_string_lossy()
+        .to_string())
+}
@@ -0,0 +1,98 @@
+//! Batch audio import commands (folder scan with duplicate detection)
+use crate::services::access_service::{require_case_role, CaseRole};
+use crate::services::activity_service::log_activity;
+use crate::services::get_pool;
+use crate::services::import_service::{
+    import_audio_file, scan_files, DuplicatePolicy, ImportFileResult, ImportReport,
+};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+#[derive(Debug, Deserialize)]
+pub struct ImportFolderInput {
+    pub case_id: i32,
+    pub folder_path: String,
+    pub recursive: Option<bool>,
+    pub duplicate_policy: Option<DuplicatePolicy>,
+    pub user_id: Option<i32>,
+}
+
+/// Payload of the `audio-import-progress` event, emitted after each file
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportProgress {
+    pub case_id: i32,
+    pub processed: usize,
+    pub total: usize,
+    pub result: ImportFileResult,
+}
+
+/// Scan a folder and register every supported audio file in a case
+#[tauri::command]
+pub async fn import_audio_folder(
+    app: AppHandle,
+    input: ImportFolderInput,
+) -> Result<ImportReport, String> {
+    // Hashing, probing and conversion are blocking; keep them off the async runtime
+    tauri::async_runtime::spawn_blocking(move || run_folder_import(&app, input))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn run_folder_import(app: &AppHandle, input: ImportFolderInput) -> Result<ImportReport, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_case_role(&mut conn, input.user_id, input.case_id, CaseRole::Editor)?;
+
+    let folder = std::path::Path::new(&input.folder_path);
+    let scan = scan_files(folder, input.recursive.unwrap_or(true))
+        .map_err(|e| format!("Failed to scan folder: {}", e))?;
+    let policy = input.duplicate_policy.unwrap_or_default();
+
+    // Unreadable subdirectories are reported alongside the files, not fatal
+    let mut results: Vec<ImportFileResult> = scan
+        .errors
+        .iter()
+        .map(|(path, e)| ImportFileResult::failed(path, format!("Failed to read folder: {}", e)))
+        .collect();
+    let total = scan.files.len();
+    for (index, path) in scan.files.iter().enumerate() {
+        let result = import_audio_file(&mut conn, input.case_id, path, policy);
+
+        let _ = app.emit(
+            "audio-import-progress",
+            ImportProgress {
+                case_id: input.case_id,
+                processed: index + 1,
+                total,
+                result: result.clone(),
+            },
+        );
+        results.push(result);
+    }
+
+    let report = ImportReport::from_results(results);
+
+    log_activity(
+        &mut conn,
+        input.user_id,
+        "import",
+        "case",
+        Some(input.case_id),
+        Some(
+            serde_json::json!({
+                "operation": "import_folder",
+                "folder": input.folder_path,
+                "total": report.total,
+                "imported": report.imported,
+                "linked": report.linked,
+                "duplicates": report.duplicates,
+                "failed": report.failed,
+            })
+            .to_string(),
+        ),
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(report)
+}
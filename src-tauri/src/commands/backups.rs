@@ -0,0 +1,159 @@
+//! Database backup commands: online backups, scheduled snapshots and restore (admin only)
+use crate::services::activity_service::log_activity;
+use crate::services::auth_service::require_admin;
+use crate::services::backup_service::{self, Backup, BackupSchedule, Restore, Snapshot};
+use crate::services::get_pool;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Deserialize)]
+pub struct BackupDatabaseInput {
+    /// Destination file; defaults to a new snapshot in the snapshot folder
+    pub output_path: Option<String>,
+    pub user_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateBackupScheduleInput {
+    pub enabled: bool,
+    pub interval_hours: u32,
+    pub keep: usize,
+    pub user_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RestoreDatabaseInput {
+    pub backup_path: String,
+    pub user_id: Option<i32>,
+}
+
+/// Back up the live database without stopping the app
+#[tauri::command]
+pub async fn backup_database(input: BackupDatabaseInput) -> Result<Backup, String> {
+    // Copying and checking a large database takes a while
+    tauri::async_runtime::spawn_blocking(move || run_backup(input))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn run_backup(input: BackupDatabaseInput) -> Result<Backup, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_admin(&mut conn, input.user_id).map_err(|e| e.to_string())?;
+
+    let backup = match &input.output_path {
+        Some(path) => backup_service::backup_database(&PathBuf::from(path))?,
+        None => backup_service::take_snapshot()?,
+    };
+
+    log_activity(
+        &mut conn,
+        input.user_id,
+        "export",
+        "database",
+        None,
+        Some(
+            serde_json::json!({
+                "operation": "backup",
+                "file_path": backup.path,
+                "sha256": backup.sha256,
+                "schema_version": backup.schema_version,
+                "pruned": backup.pruned.len(),
+            })
+            .to_string(),
+        ),
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(backup)
+}
+
+/// Snapshots available for restore, newest first
+#[tauri::command]
+pub fn list_database_snapshots(user_id: Option<i32>) -> Result<Vec<Snapshot>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_admin(&mut conn, user_id).map_err(|e| e.to_string())?;
+
+    backup_service::list_snapshots()
+}
+
+#[tauri::command]
+pub fn get_backup_schedule() -> BackupSchedule {
+    backup_service::load_schedule()
+}
+
+#[tauri::command]
+pub fn update_backup_schedule(input: UpdateBackupScheduleInput) -> Result<BackupSchedule, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_admin(&mut conn, input.user_id).map_err(|e| e.to_string())?;
+
+    let schedule = BackupSchedule {
+        enabled: input.enabled,
+        interval_hours: input.interval_hours,
+        keep: input.keep,
+    };
+    backup_service::save_schedule(&schedule)?;
+
+    log_activity(
+        &mut conn,
+        input.user_id,
+        "edit",
+        "database",
+        None,
+        Some(
+            serde_json::json!({
+                "operation": "backup_schedule",
+                "enabled": schedule.enabled,
+                "interval_hours": schedule.interval_hours,
+                "keep": schedule.keep,
+            })
+            .to_string(),
+        ),
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(schedule)
+}
+
+/// Replace the live database with a backup. The current database is snapshotted
+/// first so the restore itself can be undone.
+#[tauri::command]
+pub async fn restore_database(input: RestoreDatabaseInput) -> Result<Restore, String> {
+    // Snapshots the current database, then copies the backup over it
+    tauri::async_runtime::spawn_blocking(move || run_restore(input))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn run_restore(input: RestoreDatabaseInput) -> Result<Restore, String> {
+    {
+        let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+        require_admin(&mut conn, input.user_id).map_err(|e| e.to_string())?;
+    }
+
+    let restore = backup_service::restore_database(Path::new(&input.backup_path))?;
+
+    // Logged into the restored database; the user may not exist there
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+    let _ = log_activity(
+        &mut conn,
+        input.user_id,
+        "import",
+        "database",
+        None,
+        Some(
+            serde_json::json!({
+                "operation": "restore",
+                "file_path": restore.restored_from,
+                "schema_version": restore.schema_version,
+                "migrations_applied": restore.migrations_applied,
+                "safety_snapshot": restore.safety_snapshot,
+            })
+            .to_string(),
+        ),
+    );
+
+    Ok(restore)
+}
@@ -0,0 +1,323 @@
+//! Evidence bookmark commands (important moments of a recording)
+use crate::models::{Bookmark, NewBookmark, NewBookmarkSegment};
+use crate::schema::{audio_files, bookmark_segments, bookmarks, transcript_segments};
+use crate::services::access_service::{require_audio_role, require_case_role, CaseRole};
+use crate::services::activity_service::log_activity;
+use crate::services::get_pool;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Severities accepted for bookmarks, from least to most important
+pub const BOOKMARK_SEVERITIES: [&str; 4] = ["low", "medium", "high", "critical"];
+
+#[derive(Debug, Deserialize)]
+pub struct CreateBookmarkInput {
+    pub audio_file_id: i32,
+    pub start_time: f32,
+    pub end_time: f32,
+    pub title: String,
+    pub description: Option<String>,
+    pub severity: Option<String>,
+    pub segment_ids: Option<Vec<i32>>,
+    pub user_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateBookmarkInput {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub severity: Option<String>,
+    /// Replaces the linked segments
+    pub segment_ids: Option<Vec<i32>>,
+    pub user_id: Option<i32>,
+}
+
+/// A bookmark with the ids of its linked transcript segments
+#[derive(Debug, Serialize)]
+pub struct BookmarkDetail {
+    #[serde(flatten)]
+    pub bookmark: Bookmark,
+    pub segment_ids: Vec<i32>,
+}
+
+fn validate_severity(severity: &str) -> Result<(), String> {
+    if BOOKMARK_SEVERITIES.contains(&severity) {
+        Ok(())
+    } else {
+        Err(format!("Invalid bookmark severity: {}", severity))
+    }
+}
+
+/// Segments may only be linked to bookmarks of their own audio file, and not once deleted
+fn check_segments(
+    conn: &mut SqliteConnection,
+    audio_file_id: i32,
+    segment_ids: &[i32],
+) -> Result<(), String> {
+    let found = transcript_segments::table
+        .filter(transcript_segments::id.eq_any(segment_ids))
+        .filter(transcript_segments::audio_file_id.eq(audio_file_id))
+        .filter(transcript_segments::is_deleted.eq(0))
+        .count()
+        .get_result::<i64>(conn)
+        .map_err(|e| e.to_string())?;
+
+    let mut unique = segment_ids.to_vec();
+    unique.sort_unstable();
+    unique.dedup();
+    if found != unique.len() as i64 {
+        return Err(
+            "Linked segments must belong to the bookmarked audio file and not be deleted"
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+fn link_segments(
+    conn: &mut SqliteConnection,
+    bookmark_id: i32,
+    segment_ids: &[i32],
+) -> QueryResult<()> {
+    let mut unique = segment_ids.to_vec();
+    unique.sort_unstable();
+    unique.dedup();
+
+    let links: Vec<NewBookmarkSegment> = unique
+        .into_iter()
+        .map(|segment_id| NewBookmarkSegment {
+            bookmark_id,
+            segment_id,
+        })
+        .collect();
+
+    diesel::insert_into(bookmark_segments::table)
+        .values(&links)
+        .execute(conn)?;
+    Ok(())
+}
+
+fn with_segments(
+    conn: &mut SqliteConnection,
+    list: Vec<Bookmark>,
+) -> QueryResult<Vec<BookmarkDetail>> {
+    let ids: Vec<i32> = list.iter().map(|bookmark| bookmark.id).collect();
+    let links = bookmark_segments::table
+        .filter(bookmark_segments::bookmark_id.eq_any(&ids))
+        .order(bookmark_segments::segment_id.asc())
+        .select((
+            bookmark_segments::bookmark_id,
+            bookmark_segments::segment_id,
+        ))
+        .load::<(i32, i32)>(conn)?;
+
+    Ok(list
+        .into_iter()
+        .map(|bookmark| BookmarkDetail {
+            segment_ids: links
+                .iter()
+                .filter(|(bookmark_id, _)| *bookmark_id == bookmark.id)
+                .map(|(_, segment_id)| *segment_id)
+                .collect(),
+            bookmark,
+        })
+        .collect())
+}
+
+/// Get the bookmarks of an audio file ordered by start time
+#[tauri::command]
+pub fn get_bookmarks(
+    audio_file_id: i32,
+    user_id: Option<i32>,
+) -> Result<Vec<BookmarkDetail>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Reader)?;
+
+    let list = bookmarks::table
+        .filter(bookmarks::audio_file_id.eq(audio_file_id))
+        .order(bookmarks::start_time.asc())
+        .load::<Bookmark>(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+    with_segments(&mut conn, list).map_err(|e| e.to_string())
+}
+
+/// Get the bookmarks of every audio file of a case
+#[tauri::command]
+pub fn get_case_bookmarks(
+    case_id: i32,
+    user_id: Option<i32>,
+) -> Result<Vec<BookmarkDetail>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_case_role(&mut conn, user_id, case_id, CaseRole::Reader)?;
+
+    let list = bookmarks::table
+        .inner_join(audio_files::table)
+        .filter(audio_files::case_id.eq(case_id))
+        .order((bookmarks::audio_file_id.asc(), bookmarks::start_time.asc()))
+        .select(Bookmark::as_select())
+        .load::<Bookmark>(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+    with_segments(&mut conn, list).map_err(|e| e.to_string())
+}
+
+/// Bookmark an important moment of a recording
+#[tauri::command]
+pub fn create_bookmark(input: CreateBookmarkInput) -> Result<BookmarkDetail, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    let severity = input.severity.unwrap_or_else(|| "medium".to_string());
+    validate_severity(&severity)?;
+    if input.title.trim().is_empty() {
+        return Err("A title is required for every bookmark".to_string());
+    }
+    if input.start_time < 0.0 || input.end_time < input.start_time {
+        return Err("Invalid bookmark time range".to_string());
+    }
+
+    require_audio_role(
+        &mut conn,
+        input.user_id,
+        input.audio_file_id,
+        CaseRole::Editor,
+    )?;
+
+    let segment_ids = input.segment_ids.unwrap_or_default();
+    check_segments(&mut conn, input.audio_file_id, &segment_ids)?;
+
+    let new_bookmark = NewBookmark {
+        audio_file_id: input.audio_file_id,
+        start_time: input.start_time,
+        end_time: input.end_time,
+        title: input.title,
+        description: input.description,
+        severity,
+        created_by: input.user_id,
+    };
+
+    conn.transaction::<BookmarkDetail, diesel::result::Error, _>(|conn| {
+        diesel::insert_into(bookmarks::table)
+            .values(&new_bookmark)
+            .execute(conn)?;
+
+        let bookmark = bookmarks::table
+            .order(bookmarks::id.desc())
+            .first::<Bookmark>(conn)?;
+        link_segments(conn, bookmark.id, &segment_ids)?;
+
+        let detail = with_segments(conn, vec![bookmark])?.remove(0);
+
+        log_activity(
+            conn,
+            input.user_id,
+            "create",
+            "bookmark",
+            Some(detail.bookmark.id),
+            serde_json::to_string(&detail).ok(),
+        )?;
+
+        Ok(detail)
+    })
+    .map_err(|e| format!("Failed to create bookmark: {}", e))
+}
+
+/// Update a bookmark's title, description, severity or linked segments
+#[tauri::command]
+pub fn update_bookmark(id: i32, input: UpdateBookmarkInput) -> Result<BookmarkDetail, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+    let target = bookmarks::table.find(id);
+
+    let bookmark = target
+        .first::<Bookmark>(&mut conn)
+        .map_err(|e| format!("Bookmark not found: {}", e))?;
+    require_audio_role(
+        &mut conn,
+        input.user_id,
+        bookmark.audio_file_id,
+        CaseRole::Editor,
+    )?;
+
+    if let Some(severity) = &input.severity {
+        validate_severity(severity)?;
+    }
+    if input
+        .title
+        .as_deref()
+        .is_some_and(|title| title.trim().is_empty())
+    {
+        return Err("A title is required for every bookmark".to_string());
+    }
+    if let Some(segment_ids) = &input.segment_ids {
+        check_segments(&mut conn, bookmark.audio_file_id, segment_ids)?;
+    }
+
+    conn.transaction::<BookmarkDetail, diesel::result::Error, _>(|conn| {
+        if let Some(title) = &input.title {
+            diesel::update(target)
+                .set(bookmarks::title.eq(title))
+                .execute(conn)?;
+        }
+
+        if let Some(description) = &input.description {
+            diesel::update(target)
+                .set(bookmarks::description.eq(description))
+                .execute(conn)?;
+        }
+
+        if let Some(severity) = &input.severity {
+            diesel::update(target)
+                .set(bookmarks::severity.eq(severity))
+                .execute(conn)?;
+        }
+
+        if let Some(segment_ids) = &input.segment_ids {
+            diesel::delete(bookmark_segments::table.filter(bookmark_segments::bookmark_id.eq(id)))
+                .execute(conn)?;
+            link_segments(conn, id, segment_ids)?;
+        }
+
+        let bookmark = target.first::<Bookmark>(conn)?;
+        let detail = with_segments(conn, vec![bookmark])?.remove(0);
+
+        log_activity(
+            conn,
+            input.user_id,
+            "edit",
+            "bookmark",
+            Some(id),
+            serde_json::to_string(&detail).ok(),
+        )?;
+
+        Ok(detail)
+    })
+    .map_err(|e| format!("Failed to update bookmark: {}", e))
+}
+
+/// Delete a bookmark (kept in the activity log)
+#[tauri::command]
+pub fn delete_bookmark(id: i32, user_id: Option<i32>) -> Result<(), String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    let bookmark = bookmarks::table
+        .find(id)
+        .first::<Bookmark>(&mut conn)
+        .map_err(|e| format!("Bookmark not found: {}", e))?;
+    require_audio_role(&mut conn, user_id, bookmark.audio_file_id, CaseRole::Editor)?;
+
+    conn.transaction::<(), diesel::result::Error, _>(|conn| {
+        diesel::delete(bookmarks::table.find(id)).execute(conn)?;
+
+        log_activity(
+            conn,
+            user_id,
+            "delete",
+            "bookmark",
+            Some(bookmark.id),
+            serde_json::to_string(&bookmark).ok(),
+        )
+    })
+    .map_err(|e| format!("Failed to delete bookmark: {}", e))
+}
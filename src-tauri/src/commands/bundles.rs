@@ -0,0 +1,138 @@
+//! Case bundle commands: signed export and import of whole cases between workstations
+use crate::services::access_service::{require_case_role, CaseRole};
+use crate::services::activity_service::log_activity;
+use crate::services::bundle_service::{self, BundleExport, BundleImport, BundleImportOptions};
+use crate::services::get_pool;
+use crate::services::storage::{sha256_file, storage_dir};
+use crate::services::working_copy_service::queue_working_copies;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Deserialize)]
+pub struct ExportCaseBundleInput {
+    pub case_id: i32,
+    /// Destination file; defaults to the app's bundles directory
+    pub output_path: Option<String>,
+    pub user_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportCaseBundleInput {
+    pub bundle_path: String,
+    #[serde(flatten)]
+    pub options: BundleImportOptions,
+    pub user_id: Option<i32>,
+}
+
+/// Public key this workstation signs bundles with, for the receiving side to trust
+#[tauri::command]
+pub fn get_bundle_public_key() -> Result<String, String> {
+    let key = bundle_service::signing_key()?;
+    Ok(bundle_service::public_key_hex(&key))
+}
+
+/// Export a case with its recordings, transcripts, annotations, redactions, bookmarks
+/// and speakers into a signed bundle (case owners only)
+#[tauri::command]
+pub async fn export_case_bundle(input: ExportCaseBundleInput) -> Result<BundleExport, String> {
+    // Copying and hashing every recording is blocking
+    tauri::async_runtime::spawn_blocking(move || run_bundle_export(input))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn run_bundle_export(input: ExportCaseBundleInput) -> Result<BundleExport, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_case_role(&mut conn, input.user_id, input.case_id, CaseRole::Owner)?;
+
+    let output_path = match &input.output_path {
+        Some(path) => PathBuf::from(path),
+        None => storage_dir("bundles")
+            .map_err(|e| e.to_string())?
+            .join(format!(
+                "case{}_{}.spexor",
+                input.case_id,
+                chrono::Utc::now().format("%Y%m%d%H%M%S")
+            )),
+    };
+
+    let export =
+        bundle_service::export_case_bundle(&mut conn, input.case_id, input.user_id, &output_path);
+    if export.is_err() {
+        let _ = std::fs::remove_file(&output_path);
+    }
+    let export = export?;
+
+    log_activity(
+        &mut conn,
+        input.user_id,
+        "export",
+        "case",
+        Some(input.case_id),
+        Some(
+            serde_json::json!({
+                "operation": "case_bundle",
+                "file_path": export.file_path,
+                "sha256": export.sha256,
+                "public_key": export.public_key,
+                "audio_files": export.audio_files,
+            })
+            .to_string(),
+        ),
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(export)
+}
+
+/// Import a case bundle signed by a trusted workstation. The signature and every file
+/// hash are verified first; code and speaker collisions are resolved per the chosen
+/// policies and reported back.
+#[tauri::command]
+pub async fn import_case_bundle(input: ImportCaseBundleInput) -> Result<BundleImport, String> {
+    // Extracting, hashing and sealing every recording is blocking
+    tauri::async_runtime::spawn_blocking(move || run_bundle_import(input))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn run_bundle_import(input: ImportCaseBundleInput) -> Result<BundleImport, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    if input.user_id.is_none() {
+        return Err("Authentication required".to_string());
+    }
+
+    let bundle_path = Path::new(&input.bundle_path);
+    let import =
+        bundle_service::import_case_bundle(&mut conn, bundle_path, input.user_id, &input.options)?;
+
+    for audio_file_id in &import.new_audio_ids {
+        queue_working_copies(*audio_file_id, input.user_id);
+    }
+
+    log_activity(
+        &mut conn,
+        input.user_id,
+        "import",
+        "case",
+        Some(import.case.id),
+        Some(
+            serde_json::json!({
+                "operation": "case_bundle",
+                "file_path": input.bundle_path,
+                "sha256": sha256_file(bundle_path).ok(),
+                "public_key": import.public_key,
+                "exported_by": import.exported_by,
+                "merged": import.merged,
+                "audio_files": import.audio_files,
+                "conflicts": import.conflicts.len(),
+            })
+            .to_string(),
+        ),
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(import)
+}
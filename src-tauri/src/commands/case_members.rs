@@ -0,0 +1,187 @@
+//! Case membership commands (per-case access control and its audit)
+use crate::models::{ActivityLog, CaseMember, NewCaseMember};
+use crate::schema::{activity_logs, case_members, users};
+use crate::services::access_service::{require_case_role, CaseRole, ACCESS_TARGET};
+use crate::services::activity_service::log_activity;
+use crate::services::auth_service::require_admin;
+use crate::services::get_pool;
+use diesel::prelude::*;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct SetCaseMemberInput {
+    pub case_id: i32,
+    pub member_id: i32,
+    pub role: CaseRole,
+    pub user_id: Option<i32>,
+}
+
+fn owner_count(conn: &mut SqliteConnection, case_id: i32) -> QueryResult<i64> {
+    case_members::table
+        .filter(case_members::case_id.eq(case_id))
+        .filter(case_members::role.eq(CaseRole::Owner.as_str()))
+        .count()
+        .get_result(conn)
+}
+
+/// Get the members of a case
+#[tauri::command]
+pub fn get_case_members(case_id: i32, user_id: Option<i32>) -> Result<Vec<CaseMember>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_case_role(&mut conn, user_id, case_id, CaseRole::Reader)?;
+
+    case_members::table
+        .filter(case_members::case_id.eq(case_id))
+        .order(case_members::created_at.asc())
+        .load::<CaseMember>(&mut conn)
+        .map_err(|e| e.to_string())
+}
+
+/// Grant a user access to a case, or change their role (owners only)
+#[tauri::command]
+pub fn set_case_member(input: SetCaseMemberInput) -> Result<CaseMember, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_case_role(&mut conn, input.user_id, input.case_id, CaseRole::Owner)?;
+
+    users::table
+        .find(input.member_id)
+        .select(users::id)
+        .first::<i32>(&mut conn)
+        .map_err(|e| format!("User not found: {}", e))?;
+
+    let target = case_members::table.find((input.case_id, input.member_id));
+
+    conn.transaction::<CaseMember, diesel::result::Error, _>(|conn| {
+        let previous = target.first::<CaseMember>(conn).optional()?;
+
+        match &previous {
+            Some(member) => {
+                if member.role == CaseRole::Owner.as_str()
+                    && input.role != CaseRole::Owner
+                    && owner_count(conn, input.case_id)? <= 1
+                {
+                    return Err(diesel::result::Error::RollbackTransaction);
+                }
+                diesel::update(target)
+                    .set(case_members::role.eq(input.role.as_str()))
+                    .execute(conn)?;
+            }
+            None => {
+                diesel::insert_into(case_members::table)
+                    .values(&NewCaseMember {
+                        case_id: input.case_id,
+                        user_id: input.member_id,
+                        role: input.role.to_string(),
+                        added_by: input.user_id,
+                    })
+                    .execute(conn)?;
+            }
+        }
+
+        log_activity(
+            conn,
+            input.user_id,
+            if previous.is_some() { "edit" } else { "create" },
+            ACCESS_TARGET,
+            Some(input.case_id),
+            Some(
+                serde_json::json!({
+                    "operation": if previous.is_some() { "change_access" } else { "grant_access" },
+                    "member_id": input.member_id,
+                    "from": previous.as_ref().map(|member| member.role.clone()),
+                    "to": input.role,
+                })
+                .to_string(),
+            ),
+        )?;
+
+        target.first::<CaseMember>(conn)
+    })
+    .map_err(|e| match e {
+        diesel::result::Error::RollbackTransaction => {
+            "A case must keep at least one owner".to_string()
+        }
+        e => format!("Failed to update case access: {}", e),
+    })
+}
+
+/// Revoke a user's access to a case (owners only; the last owner cannot be removed)
+#[tauri::command]
+pub fn remove_case_member(
+    case_id: i32,
+    member_id: i32,
+    user_id: Option<i32>,
+) -> Result<(), String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_case_role(&mut conn, user_id, case_id, CaseRole::Owner)?;
+
+    let member = case_members::table
+        .find((case_id, member_id))
+        .first::<CaseMember>(&mut conn)
+        .map_err(|e| format!("Case member not found: {}", e))?;
+
+    conn.transaction::<(), diesel::result::Error, _>(|conn| {
+        if member.role == CaseRole::Owner.as_str() && owner_count(conn, case_id)? <= 1 {
+            return Err(diesel::result::Error::RollbackTransaction);
+        }
+
+        diesel::delete(case_members::table.find((case_id, member_id))).execute(conn)?;
+
+        log_activity(
+            conn,
+            user_id,
+            "delete",
+            ACCESS_TARGET,
+            Some(case_id),
+            Some(
+                serde_json::json!({
+                    "operation": "revoke_access",
+                    "member_id": member_id,
+                    "from": member.role,
+                })
+                .to_string(),
+            ),
+        )
+    })
+    .map_err(|e| match e {
+        diesel::result::Error::RollbackTransaction => {
+            "A case must keep at least one owner".to_string()
+        }
+        e => format!("Failed to update case access: {}", e),
+    })
+}
+
+/// Get the history of access grants and revocations on a case (admin only)
+#[tauri::command]
+pub fn get_case_access_log(case_id: i32, user_id: Option<i32>) -> Result<Vec<ActivityLog>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_admin(&mut conn, user_id).map_err(|e| e.to_string())?;
+
+    activity_logs::table
+        .filter(activity_logs::target_type.eq(ACCESS_TARGET))
+        .filter(activity_logs::target_id.eq(case_id))
+        .order(activity_logs::created_at.desc())
+        .load::<ActivityLog>(&mut conn)
+        .map_err(|e| e.to_string())
+}
+
+/// Get every case a user is a member of, with their role (admin only)
+#[tauri::command]
+pub fn get_user_case_access(
+    member_id: i32,
+    user_id: Option<i32>,
+) -> Result<Vec<CaseMember>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_admin(&mut conn, user_id).map_err(|e| e.to_string())?;
+
+    case_members::table
+        .filter(case_members::user_id.eq(member_id))
+        .order(case_members::case_id.asc())
+        .load::<CaseMember>(&mut conn)
+        .map_err(|e| e.to_string())
+}
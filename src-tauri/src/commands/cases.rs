@@ -1,15 +1,39 @@
 //! Case CRUD commands
-use crate::models::{Case, NewCase};
-use crate::schema::cases;
+use crate::models::pagination::invalid_sort;
+use crate::models::tags::encode_tags;
+use crate::models::{
+    Case, ListQuery, NewCase, NewCaseAssignment, NewCaseMember, Page, SortDirection, User,
+};
+use crate::schema::{audio_files, case_assignments, case_members, cases, users};
+use crate::services::access_service::{
+    accessible_case_ids, log_access_grant, require_case_role, CaseRole,
+};
+use crate::services::activity_service::log_activity;
 use crate::services::get_pool;
+use crate::services::notification_service::notify;
+use crate::services::status_service::AudioStatus;
+use crate::services::timeline_service::{build_case_timeline, TimelineEvent};
 use diesel::prelude::*;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Case workflow statuses
+pub const CASE_STATUSES: [&str; 4] = ["open", "in-progress", "closed", "archived"];
+
+/// Case priorities, from least to most pressing
+pub const CASE_PRIORITIES: [&str; 4] = ["low", "medium", "high", "urgent"];
 
 #[derive(Debug, Deserialize)]
 pub struct CreateCaseInput {
     pub code: String,
     pub title: String,
     pub description: Option<String>,
+    pub status: Option<String>,
+    pub priority: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub due_date: Option<chrono::NaiveDate>,
+    pub assignee_ids: Option<Vec<i32>>,
+    pub user_id: Option<i32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -17,57 +41,204 @@ pub struct UpdateCaseInput {
     pub code: Option<String>,
     pub title: Option<String>,
     pub description: Option<String>,
+    pub status: Option<String>,
+    pub priority: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub due_date: Option<chrono::NaiveDate>,
+    /// Remove the due date (ignored when `due_date` is set)
+    pub clear_due_date: Option<bool>,
+    pub user_id: Option<i32>,
 }
 
-/// Get all cases ordered by creation date (newest first)
-#[tauri::command]
-pub fn get_cases() -> Result<Vec<Case>, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+/// Filters for `filter_cases`; every field is optional and they combine with AND
+#[derive(Debug, Default, Deserialize)]
+pub struct CaseFilter {
+    pub status: Option<String>,
+    pub priority: Option<String>,
+    pub tag: Option<String>,
+    pub assignee_id: Option<i32>,
+    pub due_before: Option<chrono::NaiveDate>,
+    pub due_after: Option<chrono::NaiveDate>,
+    /// Only open or in-progress cases past their due date
+    pub overdue: Option<bool>,
+}
+
+/// Processing progress of a case, aggregated from its audio files
+#[derive(Debug, Serialize)]
+pub struct CaseProgress {
+    pub case_id: i32,
+    pub total_files: i64,
+    /// Number of files per status (every status is present)
+    pub by_status: BTreeMap<String, i64>,
+    /// Share of files that are approved or archived, 0-100
+    pub percent_complete: f32,
+}
+
+fn validate_status(status: &str) -> Result<(), String> {
+    if CASE_STATUSES.contains(&status) {
+        Ok(())
+    } else {
+        Err(format!("Invalid case status: {}", status))
+    }
+}
+
+fn validate_priority(priority: &str) -> Result<(), String> {
+    if CASE_PRIORITIES.contains(&priority) {
+        Ok(())
+    } else {
+        Err(format!("Invalid case priority: {}", priority))
+    }
+}
 
+fn find_case(conn: &mut SqliteConnection, id: i32) -> Result<Case, String> {
     cases::table
-        .order(cases::created_at.desc())
+        .find(id)
+        .first::<Case>(conn)
+        .map_err(|e| format!("Case not found: {}", e))
+}
+
+/// Cases visible to the caller that match the query's search and date range
+fn case_query(
+    visible: &Option<Vec<i32>>,
+    query: &ListQuery,
+) -> cases::BoxedQuery<'static, diesel::sqlite::Sqlite> {
+    let mut boxed = cases::table.into_boxed();
+    if let Some(ids) = visible {
+        boxed = boxed.filter(cases::id.eq_any(ids.clone()));
+    }
+    if let Some(pattern) = query.search_pattern() {
+        boxed = boxed.filter(
+            cases::code
+                .like(pattern.clone())
+                .escape('\\')
+                .or(cases::title.like(pattern.clone()).escape('\\'))
+                .or(cases::description.like(pattern).escape('\\')),
+        );
+    }
+    if let Some(from) = query.date_from {
+        boxed = boxed.filter(cases::created_at.ge(from));
+    }
+    if let Some(to) = query.date_to {
+        boxed = boxed.filter(cases::created_at.le(to));
+    }
+    boxed
+}
+
+/// Get a page of the cases the caller may access (newest first by default).
+/// Sortable by created_at, updated_at, code, title or due_date.
+#[tauri::command]
+pub fn get_cases(user_id: Option<i32>, query: Option<ListQuery>) -> Result<Page<Case>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    let query = query.unwrap_or_default();
+    let visible = accessible_case_ids(&mut conn, user_id)?;
+    let page_size = query.page_size(50);
+    let offset = query.offset(page_size);
+
+    let total = case_query(&visible, &query)
+        .count()
+        .get_result::<i64>(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+    let desc = query.descending(SortDirection::Desc);
+    let sorted = match query.sort_field("created_at") {
+        "created_at" if desc => case_query(&visible, &query).order(cases::created_at.desc()),
+        "created_at" => case_query(&visible, &query).order(cases::created_at.asc()),
+        "updated_at" if desc => case_query(&visible, &query).order(cases::updated_at.desc()),
+        "updated_at" => case_query(&visible, &query).order(cases::updated_at.asc()),
+        "code" if desc => case_query(&visible, &query).order(cases::code.desc()),
+        "code" => case_query(&visible, &query).order(cases::code.asc()),
+        "title" if desc => case_query(&visible, &query).order(cases::title.desc()),
+        "title" => case_query(&visible, &query).order(cases::title.asc()),
+        "due_date" if desc => case_query(&visible, &query).order(cases::due_date.desc()),
+        "due_date" => case_query(&visible, &query).order(cases::due_date.asc()),
+        field => return Err(invalid_sort(field)),
+    };
+
+    let items = sorted
+        .then_order_by(cases::id.desc())
+        .limit(page_size)
+        .offset(offset)
         .load::<Case>(&mut conn)
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    Ok(Page::new(items, total, offset, page_size))
 }
 
 /// Get a single case by ID
 #[tauri::command]
-pub fn get_case(id: i32) -> Result<Case, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+pub fn get_case(id: i32, user_id: Option<i32>) -> Result<Case, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
-    cases::table
-        .find(id)
-        .first::<Case>(&mut conn)
-        .map_err(|e| format!("Case not found: {}", e))
+    require_case_role(&mut conn, user_id, id, CaseRole::Reader)?;
+    find_case(&mut conn, id)
 }
 
-/// Create a new case
+/// Create a new case (the creator becomes its owner), optionally with its assignees
 #[tauri::command]
 pub fn create_case(input: CreateCaseInput) -> Result<Case, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    let creator = input
+        .user_id
+        .ok_or_else(|| "Authentication required".to_string())?;
+
+    let status = input.status.unwrap_or_else(|| "open".to_string());
+    validate_status(&status)?;
+    let priority = input.priority.unwrap_or_else(|| "medium".to_string());
+    validate_priority(&priority)?;
 
     let new_case = NewCase {
         code: input.code,
         title: input.title,
         description: input.description,
+        status,
+        priority,
+        tags: encode_tags(&input.tags.unwrap_or_default()),
+        due_date: input.due_date,
     };
 
-    diesel::insert_into(cases::table)
-        .values(&new_case)
-        .execute(&mut conn)
+    let assignee_ids = input
+        .assignee_ids
+        .map(|ids| check_assignees(&mut conn, ids))
+        .transpose()?;
+
+    // The case, its owner and its assignees are created together or not at all
+    let case = conn
+        .transaction::<Case, diesel::result::Error, _>(|conn| {
+            diesel::insert_into(cases::table)
+                .values(&new_case)
+                .execute(conn)?;
+
+            let case = cases::table.order(cases::id.desc()).first::<Case>(conn)?;
+
+            diesel::insert_into(case_members::table)
+                .values(&NewCaseMember {
+                    case_id: case.id,
+                    user_id: creator,
+                    role: CaseRole::Owner.to_string(),
+                    added_by: Some(creator),
+                })
+                .execute(conn)?;
+            log_access_grant(conn, input.user_id, case.id, creator, CaseRole::Owner)?;
+
+            if let Some(assignee_ids) = &assignee_ids {
+                apply_assignees(conn, &case, assignee_ids, input.user_id)?;
+            }
+
+            Ok(case)
+        })
         .map_err(|e| format!("Failed to create case: {}", e))?;
 
-    // Return the newly created case
-    cases::table
-        .order(cases::id.desc())
-        .first::<Case>(&mut conn)
-        .map_err(|e| e.to_string())
+    find_case(&mut conn, case.id)
 }
 
 /// Update an existing case
 #[tauri::command]
 pub fn update_case(id: i32, input: UpdateCaseInput) -> Result<Case, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_case_role(&mut conn, input.user_id, id, CaseRole::Editor)?;
 
     // Build update query dynamically
     let target = cases::table.find(id);
@@ -93,19 +264,45 @@ pub fn update_case(id: i32, input: UpdateCaseInput) -> Result<Case, String> {
             .map_err(|e| e.to_string())?;
     }
 
-    // Update timestamp
-    diesel::update(target)
-        .set(cases::updated_at.eq(chrono::Utc::now().naive_utc()))
-        .execute(&mut conn)
-        .map_err(|e| e.to_string())?;
+    if let Some(status) = input.status {
+        validate_status(&status)?;
+        diesel::update(target)
+            .set(cases::status.eq(status))
+            .execute(&mut conn)
+            .map_err(|e| e.to_string())?;
+    }
 
-    get_case(id)
+    if let Some(priority) = input.priority {
+        validate_priority(&priority)?;
+        diesel::update(target)
+            .set(cases::priority.eq(priority))
+            .execute(&mut conn)
+            .map_err(|e| e.to_string())?;
+    }
+
+    if let Some(tags) = input.tags {
+        diesel::update(target)
+            .set(cases::tags.eq(encode_tags(&tags)))
+            .execute(&mut conn)
+            .map_err(|e| e.to_string())?;
+    }
+
+    if input.due_date.is_some() || input.clear_due_date == Some(true) {
+        diesel::update(target)
+            .set(cases::due_date.eq(input.due_date))
+            .execute(&mut conn)
+            .map_err(|e| e.to_string())?;
+    }
+
+    find_case(&mut conn, id)
 }
 
-/// Delete a case by ID (cascades to audio_files, transcript_segments)
+/// Delete a case by ID (cascades to audio_files, transcript_segments; owners only)
 #[tauri::command]
-pub fn delete_case(id: i32) -> Result<(), String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+pub fn delete_case(id: i32, user_id: Option<i32>) -> Result<(), String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_case_role(&mut conn, user_id, id, CaseRole::Owner)?;
 
     diesel::delete(cases::table.find(id))
         .execute(&mut conn)
@@ -113,3 +310,224 @@ pub fn delete_case(id: i32) -> Result<(), String> {
 
     Ok(())
 }
+
+/// Get cases matching status, priority, tag, assignee and due date filters
+/// (most recently updated first)
+#[tauri::command]
+pub fn filter_cases(filter: CaseFilter, user_id: Option<i32>) -> Result<Vec<Case>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    let mut query = cases::table.into_boxed();
+    if let Some(ids) = accessible_case_ids(&mut conn, user_id)? {
+        query = query.filter(cases::id.eq_any(ids));
+    }
+    if let Some(status) = filter.status {
+        query = query.filter(cases::status.eq(status));
+    }
+    if let Some(priority) = filter.priority {
+        query = query.filter(cases::priority.eq(priority));
+    }
+    if let Some(assignee_id) = filter.assignee_id {
+        query = query.filter(
+            cases::id.eq_any(
+                case_assignments::table
+                    .filter(case_assignments::user_id.eq(assignee_id))
+                    .select(case_assignments::case_id),
+            ),
+        );
+    }
+    if let Some(due_before) = filter.due_before {
+        query = query.filter(cases::due_date.lt(due_before));
+    }
+    if let Some(due_after) = filter.due_after {
+        query = query.filter(cases::due_date.ge(due_after));
+    }
+    if filter.overdue == Some(true) {
+        query = query
+            .filter(cases::due_date.lt(chrono::Local::now().date_naive()))
+            .filter(cases::status.eq_any(["open", "in-progress"]));
+    }
+
+    let mut found = query
+        .order(cases::updated_at.desc())
+        .load::<Case>(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+    // Tags are a JSON list, so match them here rather than with LIKE
+    if let Some(tag) = filter.tag {
+        let tag = tag.trim().to_lowercase();
+        found.retain(|case| case.tag_list().iter().any(|t| t.to_lowercase() == tag));
+    }
+
+    Ok(found)
+}
+
+/// Get the users assigned to a case
+#[tauri::command]
+pub fn get_case_assignees(case_id: i32, user_id: Option<i32>) -> Result<Vec<User>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_case_role(&mut conn, user_id, case_id, CaseRole::Reader)?;
+
+    users::table
+        .inner_join(case_assignments::table.on(case_assignments::user_id.eq(users::id)))
+        .filter(case_assignments::case_id.eq(case_id))
+        .order(users::name.asc())
+        .select(User::as_select())
+        .load::<User>(&mut conn)
+        .map_err(|e| e.to_string())
+}
+
+/// Replace the assignees of a case; newly assigned users are notified and
+/// made editors of the case if they could not access it yet
+#[tauri::command]
+pub fn set_case_assignees(
+    case_id: i32,
+    user_ids: Vec<i32>,
+    user_id: Option<i32>,
+) -> Result<Vec<User>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_case_role(&mut conn, user_id, case_id, CaseRole::Editor)?;
+    let case = find_case(&mut conn, case_id)?;
+    let user_ids = check_assignees(&mut conn, user_ids)?;
+
+    conn.transaction(|conn| apply_assignees(conn, &case, &user_ids, user_id))
+        .map_err(|e| format!("Failed to update assignees: {}", e))?;
+
+    get_case_assignees(case_id, user_id)
+}
+
+/// Sorted, deduplicated assignee ids, all of existing users
+fn check_assignees(conn: &mut SqliteConnection, user_ids: Vec<i32>) -> Result<Vec<i32>, String> {
+    let mut user_ids = user_ids;
+    user_ids.sort_unstable();
+    user_ids.dedup();
+
+    let known = users::table
+        .filter(users::id.eq_any(&user_ids))
+        .count()
+        .get_result::<i64>(conn)
+        .map_err(|e| e.to_string())?;
+    if known != user_ids.len() as i64 {
+        return Err("Unknown user in assignees".to_string());
+    }
+    Ok(user_ids)
+}
+
+/// Replace the assignees of a case inside the caller's transaction
+fn apply_assignees(
+    conn: &mut SqliteConnection,
+    case: &Case,
+    user_ids: &[i32],
+    user_id: Option<i32>,
+) -> QueryResult<()> {
+    let case_id = case.id;
+    let previous = case_assignments::table
+        .filter(case_assignments::case_id.eq(case_id))
+        .select(case_assignments::user_id)
+        .load::<i32>(conn)?;
+
+    diesel::delete(
+        case_assignments::table
+            .filter(case_assignments::case_id.eq(case_id))
+            .filter(case_assignments::user_id.ne_all(user_ids)),
+    )
+    .execute(conn)?;
+
+    for assignee in user_ids.iter().filter(|id| !previous.contains(id)) {
+        diesel::insert_into(case_assignments::table)
+            .values(&NewCaseAssignment {
+                case_id,
+                user_id: *assignee,
+                assigned_by: user_id,
+            })
+            .execute(conn)?;
+
+        let granted = diesel::insert_or_ignore_into(case_members::table)
+            .values(&NewCaseMember {
+                case_id,
+                user_id: *assignee,
+                role: CaseRole::Editor.to_string(),
+                added_by: user_id,
+            })
+            .execute(conn)?;
+        if granted > 0 {
+            log_access_grant(conn, user_id, case_id, *assignee, CaseRole::Editor)?;
+        }
+
+        notify(
+            conn,
+            Some(*assignee),
+            "crud",
+            "case_assignment",
+            format!("Assigned to case {}", case.code),
+            Some(case.title.clone()),
+            Some(("case", case_id)),
+        )?;
+    }
+
+    log_activity(
+        conn,
+        user_id,
+        "edit",
+        "case",
+        Some(case_id),
+        Some(
+            serde_json::json!({
+                "operation": "set_assignees",
+                "from": previous,
+                "to": user_ids,
+            })
+            .to_string(),
+        ),
+    )
+}
+
+/// Get the processing progress of a case from the statuses of its audio files
+#[tauri::command]
+pub fn get_case_progress(case_id: i32, user_id: Option<i32>) -> Result<CaseProgress, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_case_role(&mut conn, user_id, case_id, CaseRole::Reader)?;
+
+    let counts = audio_files::table
+        .filter(audio_files::case_id.eq(case_id))
+        .group_by(audio_files::status)
+        .select((audio_files::status, diesel::dsl::count_star()))
+        .load::<(String, i64)>(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+    let mut by_status: BTreeMap<String, i64> = AudioStatus::ALL
+        .iter()
+        .map(|status| (status.to_string(), 0))
+        .collect();
+    for (status, count) in counts {
+        *by_status.entry(status).or_insert(0) += count;
+    }
+
+    let total_files: i64 = by_status.values().sum();
+    let done =
+        by_status[AudioStatus::Approved.as_str()] + by_status[AudioStatus::Archived.as_str()];
+
+    Ok(CaseProgress {
+        case_id,
+        total_files,
+        by_status,
+        percent_complete: if total_files > 0 {
+            done as f32 * 100.0 / total_files as f32
+        } else {
+            0.0
+        },
+    })
+}
+
+/// Get the key moments of a case (bookmarks, alert hits and annotations) in chronological order
+#[tauri::command]
+pub fn get_case_timeline(case_id: i32, user_id: Option<i32>) -> Result<Vec<TimelineEvent>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_case_role(&mut conn, user_id, case_id, CaseRole::Reader)?;
+
+    build_case_timeline(&mut conn, case_id).map_err(|e| e.to_string())
+}
@@ -0,0 +1,174 @@
+//! Multi-channel recording commands (per-channel tracks and speaker mapping)
+use crate::models::{AudioDerivative, AudioFile, ChannelSpeaker, NewChannelSpeaker};
+use crate::schema::{audio_files, channel_speakers, transcript_segments};
+use crate::services::access_service::{require_audio_role, CaseRole};
+use crate::services::activity_service::log_activity;
+use crate::services::ffmpeg::probe_channels;
+use crate::services::get_pool;
+use crate::services::review_service::ensure_transcript_unlocked;
+use crate::services::working_copy_service::ensure_channel_tracks;
+use diesel::prelude::*;
+
+/// Re-read the channel count of an audio file from the file itself
+#[tauri::command]
+pub async fn detect_audio_channels(
+    audio_file_id: i32,
+    user_id: Option<i32>,
+) -> Result<AudioFile, String> {
+    // ffprobe reads the file
+    tauri::async_runtime::spawn_blocking(move || run_channel_detection(audio_file_id, user_id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn run_channel_detection(audio_file_id: i32, user_id: Option<i32>) -> Result<AudioFile, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Editor)?;
+
+    let audio = audio_files::table
+        .find(audio_file_id)
+        .first::<AudioFile>(&mut conn)
+        .map_err(|e| format!("Audio file not found: {}", e))?;
+
+    let channels =
+        probe_channels(std::path::Path::new(&audio.file_path)).map_err(|e| e.to_string())?;
+
+    diesel::update(audio_files::table.find(audio_file_id))
+        .set(audio_files::channels.eq(channels))
+        .execute(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+    audio_files::table
+        .find(audio_file_id)
+        .first::<AudioFile>(&mut conn)
+        .map_err(|e| e.to_string())
+}
+
+/// Split a multi-channel recording into one mono track per channel (0-based),
+/// reusing tracks that were already generated
+#[tauri::command]
+pub async fn split_audio_channels(
+    audio_file_id: i32,
+    user_id: Option<i32>,
+) -> Result<Vec<AudioDerivative>, String> {
+    // ffmpeg extracts every channel in turn
+    tauri::async_runtime::spawn_blocking(move || run_channel_split(audio_file_id, user_id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn run_channel_split(
+    audio_file_id: i32,
+    user_id: Option<i32>,
+) -> Result<Vec<AudioDerivative>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Editor)?;
+
+    let audio = audio_files::table
+        .find(audio_file_id)
+        .first::<AudioFile>(&mut conn)
+        .map_err(|e| format!("Audio file not found: {}", e))?;
+
+    ensure_channel_tracks(&mut conn, &audio, user_id)
+}
+
+/// Get the channel to speaker mapping of an audio file
+#[tauri::command]
+pub fn get_channel_speakers(
+    audio_file_id: i32,
+    user_id: Option<i32>,
+) -> Result<Vec<ChannelSpeaker>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Reader)?;
+
+    channel_speakers::table
+        .filter(channel_speakers::audio_file_id.eq(audio_file_id))
+        .order(channel_speakers::channel.asc())
+        .load::<ChannelSpeaker>(&mut conn)
+        .map_err(|e| e.to_string())
+}
+
+/// Assign a speaker to a channel and apply it to the channel's existing segments.
+/// Segments that already have a speaker keep it unless `overwrite` is set.
+#[tauri::command]
+pub fn assign_channel_speaker(
+    audio_file_id: i32,
+    channel: i32,
+    speaker_id: i32,
+    overwrite: Option<bool>,
+    user_id: Option<i32>,
+) -> Result<ChannelSpeaker, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Editor)?;
+    // Relabels the channel's segments, so an approved transcript must be reopened first
+    ensure_transcript_unlocked(&mut conn, audio_file_id)?;
+
+    let audio = audio_files::table
+        .find(audio_file_id)
+        .first::<AudioFile>(&mut conn)
+        .map_err(|e| format!("Audio file not found: {}", e))?;
+    if !(0..audio.channels).contains(&channel) {
+        return Err(format!(
+            "Invalid channel {} (the file has {} channels)",
+            channel, audio.channels
+        ));
+    }
+
+    let new_mapping = NewChannelSpeaker {
+        audio_file_id,
+        channel,
+        speaker_id,
+    };
+
+    conn.transaction::<ChannelSpeaker, diesel::result::Error, _>(|conn| {
+        diesel::insert_into(channel_speakers::table)
+            .values(&new_mapping)
+            .on_conflict((channel_speakers::audio_file_id, channel_speakers::channel))
+            .do_update()
+            .set(channel_speakers::speaker_id.eq(speaker_id))
+            .execute(conn)?;
+
+        let segments = transcript_segments::table
+            .filter(transcript_segments::audio_file_id.eq(audio_file_id))
+            .filter(transcript_segments::channel.eq(channel))
+            .into_boxed();
+        let segments = if overwrite.unwrap_or(false) {
+            segments
+        } else {
+            segments.filter(transcript_segments::speaker_id.is_null())
+        };
+        let ids: Vec<i32> = segments.select(transcript_segments::id).load(conn)?;
+
+        diesel::update(transcript_segments::table.filter(transcript_segments::id.eq_any(&ids)))
+            .set(transcript_segments::speaker_id.eq(speaker_id))
+            .execute(conn)?;
+
+        log_activity(
+            conn,
+            user_id,
+            "edit",
+            "audio_file",
+            Some(audio_file_id),
+            Some(
+                serde_json::json!({
+                    "operation": "assign_channel_speaker",
+                    "channel": channel,
+                    "speaker_id": speaker_id,
+                    "overwrite": overwrite.unwrap_or(false),
+                    "segment_ids": ids,
+                })
+                .to_string(),
+            ),
+        )?;
+
+        channel_speakers::table
+            .filter(channel_speakers::audio_file_id.eq(audio_file_id))
+            .filter(channel_speakers::channel.eq(channel))
+            .first::<ChannelSpeaker>(conn)
+    })
+    .map_err(|e| format!("Failed to assign channel speaker: {}", e))
+}
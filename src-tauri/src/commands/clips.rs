@@ -0,0 +1,267 @@
+//! Audio clip extraction commands (evidence excerpts)
+use crate::models::{AudioFile, NewVoiceSample, TranscriptSegment, User, VoiceSample};
+use crate::schema::{audio_files, transcript_segments, users, voice_samples};
+use crate::services::access_service::{require_audio_role, CaseRole};
+use crate::services::activity_service::log_activity;
+use crate::services::encryption_service::seal_stored_file;
+use crate::services::ffmpeg::{run_ffmpeg, OutputFormat};
+use crate::services::get_pool;
+use crate::services::storage::{sha256_file, storage_dir};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Debug, Deserialize)]
+pub struct ExtractClipInput {
+    pub audio_file_id: i32,
+    /// Explicit range in seconds (ignored when `segment_ids` is given)
+    pub start_time: Option<f32>,
+    pub end_time: Option<f32>,
+    /// Clip the span covering these segments instead of an explicit range
+    pub segment_ids: Option<Vec<i32>>,
+    pub format: OutputFormat,
+    pub padding: Option<f32>,
+    pub fade_in: Option<f32>,
+    pub fade_out: Option<f32>,
+    /// Destination file; defaults to the app's clips directory
+    pub output_path: Option<String>,
+    pub user_id: Option<i32>,
+    /// Register the clip as a voice sample of this speaker
+    pub speaker_id: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ClipResult {
+    pub file_path: String,
+    pub sidecar_path: String,
+    pub sha256: String,
+    pub start_time: f32,
+    pub end_time: f32,
+    pub voice_sample: Option<VoiceSample>,
+}
+
+/// Resolve the clip range from explicit times or the span of the given segments
+fn resolve_range(
+    conn: &mut SqliteConnection,
+    input: &ExtractClipInput,
+) -> Result<(f32, f32), String> {
+    if let Some(ids) = &input.segment_ids {
+        let mut ids = ids.clone();
+        ids.sort_unstable();
+        ids.dedup();
+        if ids.is_empty() {
+            return Err("No segments given".to_string());
+        }
+
+        let segments = transcript_segments::table
+            .filter(transcript_segments::audio_file_id.eq(input.audio_file_id))
+            .filter(transcript_segments::id.eq_any(&ids))
+            .filter(transcript_segments::is_deleted.eq(0))
+            .load::<TranscriptSegment>(conn)
+            .map_err(|e| e.to_string())?;
+
+        if segments.len() != ids.len() {
+            return Err(
+                "Some segments do not belong to this audio file or were deleted".to_string(),
+            );
+        }
+
+        let start = segments
+            .iter()
+            .map(|s| s.start_time)
+            .fold(f32::MAX, f32::min);
+        let end = segments.iter().map(|s| s.end_time).fold(0.0, f32::max);
+        return Ok((start, end));
+    }
+
+    match (input.start_time, input.end_time) {
+        (Some(start), Some(end)) => Ok((start, end)),
+        _ => Err("Either a time range or segment ids are required".to_string()),
+    }
+}
+
+/// Extract a time range (or a set of segments) of an audio file into a new clip,
+/// with a JSON sidecar describing its provenance. The clip is written unencrypted
+/// unless it is registered as a voice sample.
+#[tauri::command]
+pub async fn extract_audio_clip(input: ExtractClipInput) -> Result<ClipResult, String> {
+    // ffmpeg encodes the clip, then the source and clip are hashed
+    tauri::async_runtime::spawn_blocking(move || run_clip_extraction(input))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn run_clip_extraction(input: ExtractClipInput) -> Result<ClipResult, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(
+        &mut conn,
+        input.user_id,
+        input.audio_file_id,
+        CaseRole::Editor,
+    )?;
+
+    let audio = audio_files::table
+        .find(input.audio_file_id)
+        .first::<AudioFile>(&mut conn)
+        .map_err(|e| format!("Audio file not found: {}", e))?;
+
+    let (start, end) = resolve_range(&mut conn, &input)?;
+    let padding = input.padding.unwrap_or(0.0).max(0.0);
+    let start = (start - padding).max(0.0);
+    let end = if audio.duration > 0.0 {
+        (end + padding).min(audio.duration)
+    } else {
+        end + padding
+    };
+    if end <= start {
+        return Err("Clip range is empty".to_string());
+    }
+    let length = end - start;
+
+    let output_path = match &input.output_path {
+        Some(path) => PathBuf::from(path),
+        None => storage_dir("clips")
+            .map_err(|e| e.to_string())?
+            .join(format!(
+                "audio{}_{:.0}-{:.0}_{}.{}",
+                audio.id,
+                start * 1000.0,
+                end * 1000.0,
+                chrono::Utc::now().format("%Y%m%d%H%M%S"),
+                input.format.extension()
+            )),
+    };
+
+    let mut filters = Vec::new();
+    if let Some(fade_in) = input.fade_in.filter(|d| *d > 0.0) {
+        filters.push(format!("afade=t=in:st=0:d={:.3}", fade_in.min(length)));
+    }
+    if let Some(fade_out) = input.fade_out.filter(|d| *d > 0.0) {
+        let fade_out = fade_out.min(length);
+        filters.push(format!(
+            "afade=t=out:st={:.3}:d={:.3}",
+            length - fade_out,
+            fade_out
+        ));
+    }
+
+    let mut args = vec![
+        "-ss".to_string(),
+        format!("{:.3}", start),
+        "-i".to_string(),
+        audio.file_path.clone(),
+        "-t".to_string(),
+        format!("{:.3}", length),
+    ];
+    if !filters.is_empty() {
+        args.push("-af".to_string());
+        args.push(filters.join(","));
+    }
+    args.extend(input.format.codec_args());
+    args.push(output_path.to_string_lossy().to_string());
+
+    run_ffmpeg(&args).map_err(|e| e.to_string())?;
+    // Clips are shareable excerpts and stay readable outside the app; only a clip
+    // kept as a voice sample is stored audio and sealed like the other samples
+    if input.speaker_id.is_some() {
+        seal_stored_file(&output_path)?;
+    }
+
+    let source_hash = sha256_file(std::path::Path::new(&audio.file_path))
+        .map_err(|e| format!("Failed to hash source file: {}", e))?;
+    let clip_hash = sha256_file(&output_path).map_err(|e| format!("Failed to hash clip: {}", e))?;
+
+    let operator = match input.user_id {
+        Some(user_id) => users::table
+            .find(user_id)
+            .first::<User>(&mut conn)
+            .optional()
+            .map_err(|e| e.to_string())?,
+        None => None,
+    };
+
+    let sidecar = serde_json::json!({
+        "source": {
+            "audio_file_id": audio.id,
+            "case_id": audio.case_id,
+            "file_name": audio.file_name,
+            "file_path": audio.file_path,
+            "sha256": source_hash,
+        },
+        "clip": {
+            "file_path": output_path.to_string_lossy(),
+            "format": input.format,
+            "sha256": clip_hash,
+            "start_time": start,
+            "end_time": end,
+            "padding": padding,
+            "fade_in": input.fade_in,
+            "fade_out": input.fade_out,
+        },
+        "segment_ids": input.segment_ids,
+        "operator": {
+            "user_id": input.user_id,
+            "username": operator.as_ref().map(|u| u.username.clone()),
+            "name": operator.as_ref().map(|u| u.name.clone()),
+        },
+        "created_at": chrono::Utc::now().to_rfc3339(),
+    });
+
+    let sidecar_path = output_path.with_extension(format!("{}.json", input.format.extension()));
+    let sidecar_json = serde_json::to_string_pretty(&sidecar).map_err(|e| e.to_string())?;
+    std::fs::write(&sidecar_path, sidecar_json)
+        .map_err(|e| format!("Failed to write sidecar: {}", e))?;
+
+    let voice_sample = match input.speaker_id {
+        Some(speaker_id) => {
+            let new_sample = NewVoiceSample {
+                speaker_id,
+                file_name: output_path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                file_path: output_path.to_string_lossy().to_string(),
+                duration: length,
+            };
+
+            diesel::insert_into(voice_samples::table)
+                .values(&new_sample)
+                .execute(&mut conn)
+                .map_err(|e| format!("Failed to create voice sample: {}", e))?;
+
+            Some(
+                voice_samples::table
+                    .order(voice_samples::id.desc())
+                    .first::<VoiceSample>(&mut conn)
+                    .map_err(|e| e.to_string())?,
+            )
+        }
+        None => None,
+    };
+
+    log_activity(
+        &mut conn,
+        input.user_id,
+        "export",
+        "audio_file",
+        Some(audio.id),
+        Some(
+            serde_json::json!({
+                "operation": "extract_clip",
+                "clip": sidecar["clip"],
+            })
+            .to_string(),
+        ),
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(ClipResult {
+        file_path: output_path.to_string_lossy().to_string(),
+        sidecar_path: sidecar_path.to_string_lossy().to_string(),
+        sha256: clip_hash,
+        start_time: start,
+        end_time: end,
+        voice_sample,
+    })
+}
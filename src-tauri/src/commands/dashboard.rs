@@ -0,0 +1,345 @@
+//! Dashboard statistics commands
+use crate::commands::cases::CASE_STATUSES;
+use crate::models::ActivityLog;
+use crate::schema::{
+    activity_logs, alert_words, audio_files, cases, speakers, transcript_segments, users,
+};
+use crate::services::access_service::accessible_case_ids;
+use crate::services::alert_service::{matches_keyword, register_keyword_match};
+use crate::services::get_pool;
+use crate::services::status_service::AudioStatus;
+use chrono::{NaiveDate, NaiveDateTime};
+use diesel::dsl::{count_star, sql, sum};
+use diesel::prelude::*;
+use diesel::sql_types::{Date, Integer, Text};
+use diesel::sqlite::Sqlite;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Speakers listed in `top_speakers`
+const TOP_SPEAKERS: i64 = 10;
+
+/// Activity log entries listed in `recent_activity`
+const RECENT_ACTIVITY: i64 = 10;
+
+/// Audio statuses that still wait for a transcript
+const BACKLOG_STATUSES: [AudioStatus; 3] = [
+    AudioStatus::Uploaded,
+    AudioStatus::Queued,
+    AudioStatus::Processing,
+];
+
+/// Monday of the week an audio file was ingested
+fn ingest_week() -> diesel::expression::SqlLiteral<Date> {
+    sql::<Date>("date(audio_files.created_at, 'weekday 0', '-6 days')")
+}
+
+/// Ingest week and alert category of an alert hit
+fn hit_week_category() -> diesel::expression::SqlLiteral<(Date, Text)> {
+    sql::<(Date, Text)>(
+        "date(audio_files.created_at, 'weekday 0', '-6 days'), alert_words.category",
+    )
+}
+
+#[derive(Debug, Serialize)]
+pub struct DashboardTotals {
+    pub cases: i64,
+    pub audio_files: i64,
+    pub audio_hours: f64,
+    pub speakers: i64,
+    pub alert_words: i64,
+    pub users: i64,
+}
+
+/// Audio ingested in one week
+#[derive(Debug, Serialize)]
+pub struct WeeklyAudio {
+    /// Monday of the week
+    pub week: NaiveDate,
+    pub files: i64,
+    pub hours: f64,
+}
+
+/// Audio files still waiting for a transcript
+#[derive(Debug, Serialize)]
+pub struct TranscriptionBacklog {
+    pub files: i64,
+    pub hours: f64,
+    /// Waiting files per status (uploaded, queued, processing)
+    pub by_status: BTreeMap<String, i64>,
+    /// Files whose transcription failed
+    pub failed: i64,
+}
+
+/// Alert hits of one category in one week
+#[derive(Debug, Serialize)]
+pub struct WeeklyAlertHits {
+    pub week: NaiveDate,
+    pub category: String,
+    pub hits: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SpeakerTalkTime {
+    pub speaker_id: i32,
+    pub name: String,
+    pub segments: i64,
+    pub seconds: f64,
+}
+
+/// Aggregates for the dashboard. Series are bucketed by the week the audio was ingested.
+#[derive(Debug, Serialize)]
+pub struct DashboardStats {
+    pub date_from: Option<NaiveDateTime>,
+    pub date_to: Option<NaiveDateTime>,
+    pub totals: DashboardTotals,
+    /// Number of cases per status (every status is present)
+    pub cases_by_status: BTreeMap<String, i64>,
+    pub audio_per_week: Vec<WeeklyAudio>,
+    pub transcription_backlog: TranscriptionBacklog,
+    pub alert_hits_per_week: Vec<WeeklyAlertHits>,
+    pub top_speakers: Vec<SpeakerTalkTime>,
+    pub recent_activity: Vec<ActivityLog>,
+}
+
+fn hours(seconds: Option<f32>) -> f64 {
+    seconds.unwrap_or(0.0) as f64 / 3600.0
+}
+
+/// Ids of the visible cases created within the range
+fn case_id_query(
+    case_ids: &Option<Vec<i32>>,
+    date_from: Option<NaiveDateTime>,
+    date_to: Option<NaiveDateTime>,
+) -> cases::BoxedQuery<'static, Sqlite, Integer> {
+    let mut boxed = cases::table.select(cases::id).into_boxed();
+    if let Some(ids) = case_ids {
+        boxed = boxed.filter(cases::id.eq_any(ids.clone()));
+    }
+    if let Some(from) = date_from {
+        boxed = boxed.filter(cases::created_at.ge(from));
+    }
+    if let Some(to) = date_to {
+        boxed = boxed.filter(cases::created_at.le(to));
+    }
+    boxed
+}
+
+/// Ids of the audio files of the visible cases, ingested within the range
+fn audio_id_query(
+    case_ids: &Option<Vec<i32>>,
+    date_from: Option<NaiveDateTime>,
+    date_to: Option<NaiveDateTime>,
+) -> audio_files::BoxedQuery<'static, Sqlite, Integer> {
+    let mut boxed = audio_files::table.select(audio_files::id).into_boxed();
+    if let Some(ids) = case_ids {
+        boxed = boxed.filter(audio_files::case_id.eq_any(ids.clone()));
+    }
+    if let Some(from) = date_from {
+        boxed = boxed.filter(audio_files::created_at.ge(from));
+    }
+    if let Some(to) = date_to {
+        boxed = boxed.filter(audio_files::created_at.le(to));
+    }
+    boxed
+}
+
+/// Alert hits per category and ingest week, matched in SQL with `matches_keyword`
+fn alert_hits_per_week(
+    conn: &mut SqliteConnection,
+    case_ids: &Option<Vec<i32>>,
+    date_from: Option<NaiveDateTime>,
+    date_to: Option<NaiveDateTime>,
+) -> QueryResult<Vec<WeeklyAlertHits>> {
+    register_keyword_match(conn)?;
+    Ok(transcript_segments::table
+        .inner_join(audio_files::table)
+        .inner_join(alert_words::table.on(matches_keyword(
+            transcript_segments::text,
+            alert_words::keyword,
+        )))
+        .filter(
+            transcript_segments::audio_file_id.eq_any(audio_id_query(case_ids, date_from, date_to)),
+        )
+        .filter(transcript_segments::is_deleted.eq(0))
+        .group_by(hit_week_category())
+        .select((hit_week_category(), count_star()))
+        .order(hit_week_category())
+        .load::<((NaiveDate, String), i64)>(conn)?
+        .into_iter()
+        .map(|((week, category), hits)| WeeklyAlertHits {
+            week,
+            category,
+            hits,
+        })
+        .collect())
+}
+
+/// Get dashboard statistics for the cases the user can see.
+/// The optional range limits cases, audio, alert hits, speakers, users and activity by
+/// creation time; the transcription backlog is always the current one.
+#[tauri::command]
+pub fn get_dashboard_stats(
+    user_id: Option<i32>,
+    date_from: Option<NaiveDateTime>,
+    date_to: Option<NaiveDateTime>,
+) -> Result<DashboardStats, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    let case_ids = accessible_case_ids(&mut conn, user_id)?;
+    let is_admin = case_ids.is_none();
+
+    // Cases by status
+    let case_counts = cases::table
+        .filter(cases::id.eq_any(case_id_query(&case_ids, date_from, date_to)))
+        .group_by(cases::status)
+        .select((cases::status, count_star()))
+        .load::<(String, i64)>(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+    let mut cases_by_status: BTreeMap<String, i64> = CASE_STATUSES
+        .iter()
+        .map(|status| (status.to_string(), 0))
+        .collect();
+    for (status, count) in case_counts {
+        *cases_by_status.entry(status).or_insert(0) += count;
+    }
+
+    // Audio ingested per week
+    let audio_per_week = audio_files::table
+        .filter(audio_files::id.eq_any(audio_id_query(&case_ids, date_from, date_to)))
+        .group_by(ingest_week())
+        .select((ingest_week(), count_star(), sum(audio_files::duration)))
+        .order(ingest_week().asc())
+        .load::<(NaiveDate, i64, Option<f32>)>(&mut conn)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|(week, files, seconds)| WeeklyAudio {
+            week,
+            files,
+            hours: hours(seconds),
+        })
+        .collect();
+
+    // Transcription backlog
+    let status_counts = audio_files::table
+        .filter(audio_files::id.eq_any(audio_id_query(&case_ids, None, None)))
+        .filter(
+            audio_files::status.eq_any(
+                BACKLOG_STATUSES
+                    .iter()
+                    .chain([&AudioStatus::Failed])
+                    .map(|status| status.as_str()),
+            ),
+        )
+        .group_by(audio_files::status)
+        .select((
+            audio_files::status,
+            count_star(),
+            sum(audio_files::duration),
+        ))
+        .load::<(String, i64, Option<f32>)>(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+    let mut transcription_backlog = TranscriptionBacklog {
+        files: 0,
+        hours: 0.0,
+        by_status: BACKLOG_STATUSES
+            .iter()
+            .map(|status| (status.to_string(), 0))
+            .collect(),
+        failed: 0,
+    };
+    for (status, count, seconds) in status_counts {
+        if status == AudioStatus::Failed.as_str() {
+            transcription_backlog.failed = count;
+        } else {
+            transcription_backlog.files += count;
+            transcription_backlog.hours += hours(seconds);
+            transcription_backlog.by_status.insert(status, count);
+        }
+    }
+
+    // Alert hits per category and week
+    let alert_hits_per_week =
+        alert_hits_per_week(&mut conn, &case_ids, date_from, date_to).map_err(|e| e.to_string())?;
+
+    // Top speakers by talk time
+    let talk_time = sum(transcript_segments::end_time - transcript_segments::start_time);
+    let top_speakers = transcript_segments::table
+        .inner_join(speakers::table)
+        .filter(
+            transcript_segments::audio_file_id
+                .eq_any(audio_id_query(&case_ids, date_from, date_to)),
+        )
+        .filter(transcript_segments::is_deleted.eq(0))
+        .group_by((speakers::id, speakers::name))
+        .select((speakers::id, speakers::name, count_star(), talk_time))
+        .order(talk_time.desc())
+        .limit(TOP_SPEAKERS)
+        .load::<(i32, String, i64, Option<f32>)>(&mut conn)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|(speaker_id, name, segments, seconds)| SpeakerTalkTime {
+            speaker_id,
+            name,
+            segments,
+            seconds: seconds.unwrap_or(0.0) as f64,
+        })
+        .collect();
+
+    // Recent activity; users other than admins only see their own
+    let mut activity_query = activity_logs::table.into_boxed();
+    if !is_admin {
+        activity_query = activity_query.filter(activity_logs::user_id.eq(user_id));
+    }
+    if let Some(from) = date_from {
+        activity_query = activity_query.filter(activity_logs::created_at.ge(from));
+    }
+    if let Some(to) = date_to {
+        activity_query = activity_query.filter(activity_logs::created_at.le(to));
+    }
+    let recent_activity = activity_query
+        .order((activity_logs::created_at.desc(), activity_logs::id.desc()))
+        .limit(RECENT_ACTIVITY)
+        .load::<ActivityLog>(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+    // Totals
+    let (audio_count, audio_seconds) = audio_files::table
+        .filter(audio_files::id.eq_any(audio_id_query(&case_ids, date_from, date_to)))
+        .select((count_star(), sum(audio_files::duration)))
+        .first::<(i64, Option<f32>)>(&mut conn)
+        .map_err(|e| e.to_string())?;
+    let mut speaker_count = speakers::table.into_boxed();
+    let mut user_count = users::table.into_boxed();
+    if let Some(from) = date_from {
+        speaker_count = speaker_count.filter(speakers::created_at.ge(from));
+        user_count = user_count.filter(users::created_at.ge(from));
+    }
+    if let Some(to) = date_to {
+        speaker_count = speaker_count.filter(speakers::created_at.le(to));
+        user_count = user_count.filter(users::created_at.le(to));
+    }
+    let count = |result: QueryResult<i64>| result.map_err(|e| e.to_string());
+    let totals = DashboardTotals {
+        cases: cases_by_status.values().sum(),
+        audio_files: audio_count,
+        audio_hours: hours(audio_seconds),
+        speakers: count(speaker_count.count().get_result(&mut conn))?,
+        alert_words: count(alert_words::table.count().get_result(&mut conn))?,
+        users: count(user_count.count().get_result(&mut conn))?,
+    };
+
+    Ok(DashboardStats {
+        date_from,
+        date_to,
+        totals,
+        cases_by_status,
+        audio_per_week,
+        transcription_backlog,
+        alert_hits_per_week,
+        top_speakers,
+        recent_activity,
+    })
+}
@@ -0,0 +1,135 @@
+//! Encryption commands: master passphrase unlock, enabling at-rest encryption and key rotation
+use crate::services::activity_service::log_activity;
+use crate::services::auth_service::require_admin;
+use crate::services::backup_service::list_snapshots;
+use crate::services::database::db_path;
+use crate::services::encryption_service::{self, EncryptionStatus, KeyRotation};
+use crate::services::storage::app_dir;
+use crate::services::{get_pool, init_db, start_snapshots, start_watch_folders};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+#[derive(Debug, Deserialize)]
+pub struct EnableEncryptionInput {
+    pub passphrase: String,
+    pub user_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RotateEncryptionKeysInput {
+    pub passphrase: String,
+    /// Also change the master passphrase
+    pub new_passphrase: Option<String>,
+    pub user_id: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EncryptionSetup {
+    pub status: EncryptionStatus,
+    /// Stored audio files encrypted right away
+    pub files_sealed: usize,
+    pub failed: Vec<String>,
+}
+
+/// Encryption state; available before the database is open, to decide whether to ask
+/// for the master passphrase
+#[tauri::command]
+pub fn get_encryption_status() -> EncryptionStatus {
+    encryption_service::status()
+}
+
+/// Unlock the encrypted store with the master passphrase and open the database
+#[tauri::command]
+pub fn unlock_encryption(app: AppHandle, passphrase: String) -> Result<EncryptionStatus, String> {
+    if encryption_service::is_unlocked() {
+        return Err("Already unlocked".to_string());
+    }
+    encryption_service::unlock(&passphrase)?;
+
+    let snapshots: Vec<PathBuf> = list_snapshots()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|snapshot| PathBuf::from(snapshot.path))
+        .collect();
+    let opened = encryption_service::prepare_database(&db_path(app_dir()), &snapshots)
+        .and_then(|_| init_db(app_dir()).map_err(|e| format!("Failed to open database: {}", e)));
+    if let Err(e) = opened {
+        encryption_service::lock();
+        return Err(e);
+    }
+
+    app.manage(get_pool()?.clone());
+    start_watch_folders();
+    start_snapshots();
+
+    Ok(encryption_service::status())
+}
+
+/// Turn on at-rest encryption (admin only). Stored audio is encrypted now; the
+/// database is converted to SQLCipher at the next start.
+#[tauri::command]
+pub fn enable_encryption(input: EnableEncryptionInput) -> Result<EncryptionSetup, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_admin(&mut conn, input.user_id).map_err(|e| e.to_string())?;
+
+    encryption_service::enable(&mut conn, &input.passphrase)?;
+    let (files_sealed, failed) = encryption_service::seal_stored_audio(&mut conn)?;
+
+    log_activity(
+        &mut conn,
+        input.user_id,
+        "edit",
+        "encryption",
+        None,
+        Some(
+            serde_json::json!({
+                "operation": "enable_encryption",
+                "files_sealed": files_sealed,
+                "failed": failed.len(),
+            })
+            .to_string(),
+        ),
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(EncryptionSetup {
+        status: encryption_service::status(),
+        files_sealed,
+        failed,
+    })
+}
+
+/// Rotate the database and audio keys, optionally changing the passphrase (admin only).
+/// Stored audio is re-encrypted now; the database is rekeyed at the next start.
+#[tauri::command]
+pub fn rotate_encryption_keys(input: RotateEncryptionKeysInput) -> Result<KeyRotation, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_admin(&mut conn, input.user_id).map_err(|e| e.to_string())?;
+
+    let rotation =
+        encryption_service::rotate_keys(&input.passphrase, input.new_passphrase.as_deref())?;
+
+    log_activity(
+        &mut conn,
+        input.user_id,
+        "edit",
+        "encryption",
+        None,
+        Some(
+            serde_json::json!({
+                "operation": "rotate_keys",
+                "audio_key_id": rotation.audio_key_id,
+                "files_resealed": rotation.files_resealed,
+                "failed": rotation.failed.len(),
+                "passphrase_changed": rotation.passphrase_changed,
+            })
+            .to_string(),
+        ),
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(rotation)
+}
@@ -0,0 +1,79 @@
+//! Audio enhancement commands (non-destructive, cached per preset)
+use crate::models::{AudioDerivative, EnhancementPreset, NewEnhancementPreset};
+use crate::schema::enhancement_presets;
+use crate::services::access_service::{require_audio_role, CaseRole};
+use crate::services::enhancement_service::{ensure_enhanced_copy, load_preset, EnhancementParams};
+use crate::services::get_pool;
+use diesel::prelude::*;
+
+/// Get the enhancement preset of an audio file (defaults when none was saved)
+#[tauri::command]
+pub fn get_enhancement_preset(
+    audio_file_id: i32,
+    user_id: Option<i32>,
+) -> Result<EnhancementParams, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Reader)?;
+
+    load_preset(&mut conn, audio_file_id)
+}
+
+/// Save the enhancement preset of an audio file
+#[tauri::command]
+pub fn save_enhancement_preset(
+    audio_file_id: i32,
+    params: EnhancementParams,
+    user_id: Option<i32>,
+) -> Result<EnhancementPreset, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Editor)?;
+
+    let new_preset = NewEnhancementPreset {
+        audio_file_id,
+        params: serde_json::to_string(&params).map_err(|e| e.to_string())?,
+        updated_by: user_id,
+    };
+
+    diesel::insert_into(enhancement_presets::table)
+        .values(&new_preset)
+        .on_conflict(enhancement_presets::audio_file_id)
+        .do_update()
+        .set((
+            enhancement_presets::params.eq(&new_preset.params),
+            enhancement_presets::updated_by.eq(user_id),
+            enhancement_presets::updated_at.eq(chrono::Utc::now().naive_utc()),
+        ))
+        .execute(&mut conn)
+        .map_err(|e| format!("Failed to save enhancement preset: {}", e))?;
+
+    enhancement_presets::table
+        .filter(enhancement_presets::audio_file_id.eq(audio_file_id))
+        .first::<EnhancementPreset>(&mut conn)
+        .map_err(|e| e.to_string())
+}
+
+/// Get the enhanced copy of an audio file for its current preset, rendering it
+/// only when no cached copy exists for these exact parameters
+#[tauri::command]
+pub async fn render_enhanced_audio(
+    audio_file_id: i32,
+    user_id: Option<i32>,
+) -> Result<AudioDerivative, String> {
+    // ffmpeg runs the full filter chain over the recording
+    tauri::async_runtime::spawn_blocking(move || run_enhancement_render(audio_file_id, user_id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn run_enhancement_render(
+    audio_file_id: i32,
+    user_id: Option<i32>,
+) -> Result<AudioDerivative, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Editor)?;
+
+    ensure_enhanced_copy(&mut conn, audio_file_id, user_id)
+}
@@ -3,22 +3,60 @@
 //! All commands are organized by domain and re-exported here for lib.rs registration.
 
 pub mod activity_logs;
+pub mod annotations;
 pub mod audio;
+pub mod audio_import;
 pub mod auth;
+pub mod backups;
+pub mod bookmarks;
+pub mod bundles;
+pub mod case_members;
 pub mod cases;
+pub mod channels;
+pub mod clips;
+pub mod dashboard;
+pub mod encryption;
+pub mod enhancement;
 pub mod greet;
 pub mod notifications;
+pub mod recording_time;
+pub mod redactions;
+pub mod reports;
+pub mod reviews;
 pub mod speakers;
+pub mod spectrogram;
 pub mod transcript;
+pub mod transcription;
 pub mod users;
 pub mod vocabulary;
+pub mod voice_activity;
+pub mod watch_folders;
 
 // Re-export all commands for easy registration in lib.rs
 pub use activity_logs::*;
+pub use annotations::*;
 pub use audio::*;
+pub use audio_import::*;
+pub use backups::*;
+pub use bookmarks::*;
+pub use bundles::*;
+pub use case_members::*;
 pub use cases::*;
+pub use channels::*;
+pub use clips::*;
+pub use dashboard::*;
+pub use encryption::*;
+pub use enhancement::*;
 pub use notifications::*;
+pub use recording_time::*;
+pub use redactions::*;
+pub use reports::*;
+pub use reviews::*;
 pub use speakers::*;
+pub use spectrogram::*;
 pub use transcript::*;
+pub use transcription::*;
 pub use users::*;
 pub use vocabulary::*;
+pub use voice_activity::*;
+pub use watch_folders::*;
@@ -1,5 +1,6 @@
 //! Notification commands
-use crate::models::{NewNotification, Notification};
+use crate::models::pagination::invalid_sort;
+use crate::models::{ListQuery, NewNotification, Notification, Page, SortDirection};
 use crate::schema::notifications;
 use crate::services::get_pool;
 use diesel::prelude::*;
@@ -13,6 +14,7 @@ pub struct CreateNotificationInput {
     pub message: Option<String>,
     pub entity_type: Option<String>,
     pub entity_id: Option<i32>,
+    pub user_id: Option<i32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -21,23 +23,94 @@ pub struct UpdateNotificationInput {
     pub is_important: Option<bool>,
 }
 
-/// Get all notifications (newest first)
+/// Notifications for the user (or everyone) matching the query's search and date range
+fn notification_query(
+    user_id: Option<i32>,
+    query: &ListQuery,
+) -> notifications::BoxedQuery<'static, diesel::sqlite::Sqlite> {
+    let mut boxed = notifications::table.into_boxed();
+    if let Some(user_id) = user_id {
+        boxed = boxed.filter(
+            notifications::user_id
+                .is_null()
+                .or(notifications::user_id.eq(user_id)),
+        );
+    }
+    if let Some(pattern) = query.search_pattern() {
+        boxed = boxed.filter(
+            notifications::title
+                .like(pattern.clone())
+                .escape('\\')
+                .or(notifications::message.like(pattern).escape('\\')),
+        );
+    }
+    if let Some(from) = query.date_from {
+        boxed = boxed.filter(notifications::created_at.ge(from));
+    }
+    if let Some(to) = query.date_to {
+        boxed = boxed.filter(notifications::created_at.le(to));
+    }
+    boxed
+}
+
+/// Get a page of notifications for a user, including those sent to everyone
+/// (newest first by default). Sortable by created_at or notification_type.
 #[tauri::command]
-pub fn get_notifications() -> Result<Vec<Notification>, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+pub fn get_notifications(
+    user_id: Option<i32>,
+    query: Option<ListQuery>,
+) -> Result<Page<Notification>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
-    notifications::table
-        .order(notifications::created_at.desc())
+    let query = query.unwrap_or_default();
+    let page_size = query.page_size(50);
+    let offset = query.offset(page_size);
+
+    let total = notification_query(user_id, &query)
+        .count()
+        .get_result::<i64>(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+    let desc = query.descending(SortDirection::Desc);
+    let sorted = match query.sort_field("created_at") {
+        "created_at" if desc => {
+            notification_query(user_id, &query).order(notifications::created_at.desc())
+        }
+        "created_at" => notification_query(user_id, &query).order(notifications::created_at.asc()),
+        "notification_type" if desc => {
+            notification_query(user_id, &query).order(notifications::notification_type.desc())
+        }
+        "notification_type" => {
+            notification_query(user_id, &query).order(notifications::notification_type.asc())
+        }
+        field => return Err(invalid_sort(field)),
+    };
+
+    let items = sorted
+        .then_order_by(notifications::id.desc())
+        .limit(page_size)
+        .offset(offset)
         .load::<Notification>(&mut conn)
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    Ok(Page::new(items, total, offset, page_size))
 }
 
-/// Get unread notifications count
+/// Get unread notifications count (of the ones visible to a user, if given)
 #[tauri::command]
-pub fn get_unread_count() -> Result<i64, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+pub fn get_unread_count(user_id: Option<i32>) -> Result<i64, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    let mut query = notifications::table.into_boxed();
+    if let Some(user_id) = user_id {
+        query = query.filter(
+            notifications::user_id
+                .is_null()
+                .or(notifications::user_id.eq(user_id)),
+        );
+    }
 
-    notifications::table
+    query
         .filter(notifications::is_read.eq(0))
         .count()
         .get_result::<i64>(&mut conn)
@@ -47,7 +120,7 @@ pub fn get_unread_count() -> Result<i64, String> {
 /// Create a new notification
 #[tauri::command]
 pub fn create_notification(input: CreateNotificationInput) -> Result<Notification, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
     let new_notification = NewNotification {
         notification_type: input.notification_type,
@@ -56,6 +129,7 @@ pub fn create_notification(input: CreateNotificationInput) -> Result<Notificatio
         message: input.message,
         entity_type: input.entity_type,
         entity_id: input.entity_id,
+        user_id: input.user_id,
     };
 
     diesel::insert_into(notifications::table)
@@ -75,7 +149,7 @@ pub fn update_notification(
     id: i32,
     input: UpdateNotificationInput,
 ) -> Result<Notification, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
     let target = notifications::table.find(id);
 
     if let Some(is_read) = input.is_read {
@@ -101,7 +175,7 @@ pub fn update_notification(
 /// Mark all notifications as read
 #[tauri::command]
 pub fn mark_all_notifications_read() -> Result<usize, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
     diesel::update(notifications::table.filter(notifications::is_read.eq(0)))
         .set(notifications::is_read.eq(1))
@@ -112,7 +186,7 @@ pub fn mark_all_notifications_read() -> Result<usize, String> {
 /// Delete a notification
 #[tauri::command]
 pub fn delete_notification(id: i32) -> Result<(), String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
     diesel::delete(notifications::table.find(id))
         .execute(&mut conn)
@@ -0,0 +1,208 @@
+//! Recording time commands (real-world start of recordings and absolute segment times)
+use crate::models::{AudioFile, NewRecordingTimePattern, RecordingTimePattern};
+use crate::schema::{audio_files, recording_time_patterns, transcript_segments};
+use crate::services::access_service::{require_audio_role, require_segment_role, CaseRole};
+use crate::services::activity_service::log_activity;
+use crate::services::get_pool;
+use crate::services::recording_time_service::{
+    absolute_time, local_to_utc, normalize_offset, refresh_recorded_at, utc_to_local,
+    validate_pattern,
+};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+pub struct SetRecordedAtInput {
+    pub audio_file_id: i32,
+    /// Wall-clock start in `timezone`; None clears the recording time
+    pub recorded_at: Option<chrono::NaiveDateTime>,
+    pub timezone: Option<String>,
+    pub user_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateRecordingTimePatternInput {
+    pub pattern: String,
+    pub timezone: Option<String>,
+}
+
+/// When a moment of a recording happened, in UTC and in the recording's timezone
+#[derive(Debug, Serialize)]
+pub struct AbsoluteTime {
+    pub utc: chrono::NaiveDateTime,
+    pub local: chrono::NaiveDateTime,
+    pub timezone: String,
+}
+
+fn find_audio(conn: &mut SqliteConnection, id: i32) -> Result<AudioFile, String> {
+    audio_files::table
+        .find(id)
+        .first::<AudioFile>(conn)
+        .map_err(|e| format!("Audio file not found: {}", e))
+}
+
+/// Enter the recording start by hand (takes precedence over detected times)
+#[tauri::command]
+pub fn set_recorded_at(input: SetRecordedAtInput) -> Result<AudioFile, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(
+        &mut conn,
+        input.user_id,
+        input.audio_file_id,
+        CaseRole::Editor,
+    )?;
+
+    let audio = find_audio(&mut conn, input.audio_file_id)?;
+
+    let (recorded_at, timezone, source) = match input.recorded_at {
+        Some(local) => {
+            let timezone = normalize_offset(input.timezone.as_deref().unwrap_or("+07:00"))?;
+            let utc = local_to_utc(local, &timezone)?;
+            (Some(utc), Some(timezone), Some("manual"))
+        }
+        None => (None, None, None),
+    };
+
+    conn.transaction::<AudioFile, diesel::result::Error, _>(|conn| {
+        diesel::update(audio_files::table.find(audio.id))
+            .set((
+                audio_files::recorded_at.eq(recorded_at),
+                audio_files::recorded_tz.eq(&timezone),
+                audio_files::recorded_at_source.eq(source),
+            ))
+            .execute(conn)?;
+
+        log_activity(
+            conn,
+            input.user_id,
+            "edit",
+            "audio_file",
+            Some(audio.id),
+            Some(
+                serde_json::json!({
+                    "operation": "set_recorded_at",
+                    "from": audio.recorded_at,
+                    "to": recorded_at,
+                    "timezone": timezone,
+                })
+                .to_string(),
+            ),
+        )?;
+
+        audio_files::table.find(audio.id).first::<AudioFile>(conn)
+    })
+    .map_err(|e| format!("Failed to save recording time: {}", e))
+}
+
+/// Detect the recording start again from container tags and file name patterns,
+/// replacing any manually entered time
+#[tauri::command]
+pub fn detect_recorded_at(audio_file_id: i32, user_id: Option<i32>) -> Result<AudioFile, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Editor)?;
+
+    let audio = find_audio(&mut conn, audio_file_id)?;
+    let updated = refresh_recorded_at(&mut conn, &audio, true)?;
+
+    if updated.recorded_at != audio.recorded_at {
+        log_activity(
+            &mut conn,
+            user_id,
+            "edit",
+            "audio_file",
+            Some(audio.id),
+            Some(
+                serde_json::json!({
+                    "operation": "detect_recorded_at",
+                    "from": audio.recorded_at,
+                    "to": updated.recorded_at,
+                    "source": updated.recorded_at_source,
+                })
+                .to_string(),
+            ),
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    Ok(updated)
+}
+
+/// Get the real-world time of a transcript segment's start (None when the recording time is unknown)
+#[tauri::command]
+pub fn get_segment_absolute_time(
+    segment_id: i32,
+    user_id: Option<i32>,
+) -> Result<Option<AbsoluteTime>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_segment_role(&mut conn, user_id, segment_id, CaseRole::Reader)?;
+
+    let (audio_file_id, start_time) = transcript_segments::table
+        .find(segment_id)
+        .select((
+            transcript_segments::audio_file_id,
+            transcript_segments::start_time,
+        ))
+        .first::<(i32, f32)>(&mut conn)
+        .map_err(|e| format!("Segment not found: {}", e))?;
+    let audio = find_audio(&mut conn, audio_file_id)?;
+
+    let (Some(utc), Some(timezone)) = (absolute_time(&audio, start_time), audio.recorded_tz) else {
+        return Ok(None);
+    };
+
+    Ok(Some(AbsoluteTime {
+        utc,
+        local: utc_to_local(utc, &timezone)?,
+        timezone,
+    }))
+}
+
+/// Get the file name patterns used to detect recording times
+#[tauri::command]
+pub fn get_recording_time_patterns() -> Result<Vec<RecordingTimePattern>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    recording_time_patterns::table
+        .order(recording_time_patterns::id.asc())
+        .load::<RecordingTimePattern>(&mut conn)
+        .map_err(|e| e.to_string())
+}
+
+/// Add a file name pattern (chrono format such as "%Y%m%d_%H%M%S")
+#[tauri::command]
+pub fn create_recording_time_pattern(
+    input: CreateRecordingTimePatternInput,
+) -> Result<RecordingTimePattern, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    validate_pattern(&input.pattern)?;
+    let new_pattern = NewRecordingTimePattern {
+        pattern: input.pattern,
+        timezone: normalize_offset(input.timezone.as_deref().unwrap_or("+07:00"))?,
+    };
+
+    diesel::insert_into(recording_time_patterns::table)
+        .values(&new_pattern)
+        .execute(&mut conn)
+        .map_err(|e| format!("Failed to create time pattern: {}", e))?;
+
+    recording_time_patterns::table
+        .order(recording_time_patterns::id.desc())
+        .first::<RecordingTimePattern>(&mut conn)
+        .map_err(|e| e.to_string())
+}
+
+/// Delete a file name pattern
+#[tauri::command]
+pub fn delete_recording_time_pattern(id: i32) -> Result<(), String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    diesel::delete(recording_time_patterns::table.find(id))
+        .execute(&mut conn)
+        .map_err(|e| format!("Failed to delete time pattern: {}", e))?;
+
+    Ok(())
+}
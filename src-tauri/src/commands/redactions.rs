@@ -0,0 +1,263 @@
+//! Redaction commands (silence or bleep sensitive ranges before disclosure)
+use crate::models::{
+    AudioDerivative, AudioFile, NewAudioDerivative, NewRedaction, Redaction, TranscriptSegment,
+};
+use crate::schema::{audio_derivatives, audio_files, redactions, transcript_segments};
+use crate::services::access_service::{require_audio_role, CaseRole};
+use crate::services::activity_service::log_activity;
+use crate::services::encryption_service::seal_stored_file;
+use crate::services::ffmpeg::{run_ffmpeg, OutputFormat};
+use crate::services::get_pool;
+use crate::services::redaction_service::{mask_segment, redaction_filter, REDACTION_MODES};
+use crate::services::storage::{sha256_file, storage_dir};
+use diesel::prelude::*;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateRedactionInput {
+    pub audio_file_id: i32,
+    pub start_time: f32,
+    pub end_time: f32,
+    pub mode: Option<String>,
+    pub reason: String,
+    pub user_id: Option<i32>,
+}
+
+/// Get redactions of an audio file ordered by start time
+#[tauri::command]
+pub fn get_redactions(audio_file_id: i32, user_id: Option<i32>) -> Result<Vec<Redaction>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Reader)?;
+
+    redactions::table
+        .filter(redactions::audio_file_id.eq(audio_file_id))
+        .order(redactions::start_time.asc())
+        .load::<Redaction>(&mut conn)
+        .map_err(|e| e.to_string())
+}
+
+/// Mark a time range of an audio file for redaction
+#[tauri::command]
+pub fn create_redaction(input: CreateRedactionInput) -> Result<Redaction, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(
+        &mut conn,
+        input.user_id,
+        input.audio_file_id,
+        CaseRole::Editor,
+    )?;
+
+    let mode = input.mode.unwrap_or_else(|| "silence".to_string());
+    if !REDACTION_MODES.contains(&mode.as_str()) {
+        return Err(format!("Invalid redaction mode: {}", mode));
+    }
+    if input.end_time <= input.start_time {
+        return Err("Redaction range is empty".to_string());
+    }
+    if input.reason.trim().is_empty() {
+        return Err("A reason is required for every redaction".to_string());
+    }
+
+    let new_redaction = NewRedaction {
+        audio_file_id: input.audio_file_id,
+        start_time: input.start_time,
+        end_time: input.end_time,
+        mode,
+        reason: input.reason,
+        created_by: input.user_id,
+    };
+
+    conn.transaction::<Redaction, diesel::result::Error, _>(|conn| {
+        diesel::insert_into(redactions::table)
+            .values(&new_redaction)
+            .execute(conn)?;
+
+        let redaction = redactions::table
+            .order(redactions::id.desc())
+            .first::<Redaction>(conn)?;
+
+        log_activity(
+            conn,
+            input.user_id,
+            "create",
+            "redaction",
+            Some(redaction.id),
+            serde_json::to_string(&redaction).ok(),
+        )?;
+
+        Ok(redaction)
+    })
+    .map_err(|e| format!("Failed to create redaction: {}", e))
+}
+
+/// Remove a redaction (kept in the activity log)
+#[tauri::command]
+pub fn delete_redaction(id: i32, user_id: Option<i32>) -> Result<(), String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    let redaction = redactions::table
+        .find(id)
+        .first::<Redaction>(&mut conn)
+        .map_err(|e| format!("Redaction not found: {}", e))?;
+    require_audio_role(
+        &mut conn,
+        user_id,
+        redaction.audio_file_id,
+        CaseRole::Editor,
+    )?;
+
+    conn.transaction::<(), diesel::result::Error, _>(|conn| {
+        diesel::delete(redactions::table.find(id)).execute(conn)?;
+
+        log_activity(
+            conn,
+            user_id,
+            "delete",
+            "redaction",
+            Some(redaction.id),
+            serde_json::to_string(&redaction).ok(),
+        )
+    })
+    .map_err(|e| format!("Failed to delete redaction: {}", e))
+}
+
+/// Render a redacted copy of an audio file. The original evidence is left untouched.
+#[tauri::command]
+pub async fn render_redacted_audio(
+    audio_file_id: i32,
+    format: OutputFormat,
+    user_id: Option<i32>,
+) -> Result<AudioDerivative, String> {
+    // ffmpeg renders the whole recording
+    tauri::async_runtime::spawn_blocking(move || {
+        run_redaction_render(audio_file_id, format, user_id)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn run_redaction_render(
+    audio_file_id: i32,
+    format: OutputFormat,
+    user_id: Option<i32>,
+) -> Result<AudioDerivative, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Editor)?;
+
+    let audio = audio_files::table
+        .find(audio_file_id)
+        .first::<AudioFile>(&mut conn)
+        .map_err(|e| format!("Audio file not found: {}", e))?;
+
+    let ranges = redactions::table
+        .filter(redactions::audio_file_id.eq(audio_file_id))
+        .order(redactions::start_time.asc())
+        .load::<Redaction>(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+    if ranges.is_empty() {
+        return Err("Audio file has no redactions".to_string());
+    }
+
+    let output_path = storage_dir("redacted")
+        .map_err(|e| e.to_string())?
+        .join(format!(
+            "audio{}_redacted_{}.{}",
+            audio.id,
+            chrono::Utc::now().format("%Y%m%d%H%M%S"),
+            format.extension()
+        ));
+
+    let mut args = vec![
+        "-i".to_string(),
+        audio.file_path.clone(),
+        "-f".to_string(),
+        "lavfi".to_string(),
+        "-i".to_string(),
+        "sine=frequency=1000".to_string(),
+        "-filter_complex".to_string(),
+        redaction_filter(&ranges),
+        "-map".to_string(),
+        "[out]".to_string(),
+    ];
+    args.extend(format.codec_args());
+    args.push(output_path.to_string_lossy().to_string());
+
+    run_ffmpeg(&args).map_err(|e| e.to_string())?;
+    seal_stored_file(&output_path)?;
+
+    let sha256 = sha256_file(&output_path).map_err(|e| e.to_string())?;
+    let params = serde_json::json!({
+        "format": format,
+        "redaction_ids": ranges.iter().map(|r| r.id).collect::<Vec<_>>(),
+    });
+
+    let new_derivative = NewAudioDerivative {
+        audio_file_id,
+        kind: "redacted".to_string(),
+        file_path: output_path.to_string_lossy().to_string(),
+        sha256,
+        params: Some(params.to_string()),
+        created_by: user_id,
+    };
+
+    conn.transaction::<AudioDerivative, diesel::result::Error, _>(|conn| {
+        diesel::insert_into(audio_derivatives::table)
+            .values(&new_derivative)
+            .execute(conn)?;
+
+        let derivative = audio_derivatives::table
+            .order(audio_derivatives::id.desc())
+            .first::<AudioDerivative>(conn)?;
+
+        log_activity(
+            conn,
+            user_id,
+            "export",
+            "audio_file",
+            Some(audio_file_id),
+            Some(
+                serde_json::json!({
+                    "operation": "render_redacted_audio",
+                    "derivative_id": derivative.id,
+                    "params": params,
+                })
+                .to_string(),
+            ),
+        )?;
+
+        Ok(derivative)
+    })
+    .map_err(|e| format!("Failed to save redacted audio: {}", e))
+}
+
+/// Get transcript segments of an audio file with redacted speech masked (for export)
+#[tauri::command]
+pub fn get_redacted_transcript(
+    audio_file_id: i32,
+    user_id: Option<i32>,
+) -> Result<Vec<TranscriptSegment>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Reader)?;
+
+    let ranges = redactions::table
+        .filter(redactions::audio_file_id.eq(audio_file_id))
+        .load::<Redaction>(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+    let segments = transcript_segments::table
+        .filter(transcript_segments::audio_file_id.eq(audio_file_id))
+        .filter(transcript_segments::is_deleted.eq(0))
+        .order(transcript_segments::start_time.asc())
+        .load::<TranscriptSegment>(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+    Ok(segments
+        .iter()
+        .map(|segment| mask_segment(segment, &ranges))
+        .collect())
+}
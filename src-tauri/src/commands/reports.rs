@@ -0,0 +1,271 @@
+//! Case report commands: report templates and PDF/DOCX generation
+use crate::models::{NewReportTemplate, ReportTemplate};
+use crate::schema::report_templates;
+use crate::services::access_service::{require_case_role, CaseRole};
+use crate::services::activity_service::log_activity;
+use crate::services::auth_service::require_admin;
+use crate::services::document_service::{write_document, DocumentFormat};
+use crate::services::get_pool;
+use crate::services::report_service::{load_report_data, render_template, validate_template};
+use crate::services::storage::{sha256_file, storage_dir};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateReportTemplateInput {
+    pub name: String,
+    pub body: String,
+    /// Use this template when a report names none
+    pub is_default: Option<bool>,
+    pub user_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateReportTemplateInput {
+    pub name: Option<String>,
+    pub body: Option<String>,
+    /// Make this the default template (unsetting it is done by choosing another)
+    pub is_default: Option<bool>,
+    pub user_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GenerateCaseReportInput {
+    pub case_id: i32,
+    /// Defaults to the default template
+    pub template_id: Option<i32>,
+    pub format: DocumentFormat,
+    /// Destination file; defaults to the app's reports directory
+    pub output_path: Option<String>,
+    pub user_id: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CaseReportResult {
+    pub file_path: String,
+    pub format: DocumentFormat,
+    pub sha256: String,
+    pub template_id: i32,
+    pub recordings: usize,
+}
+
+/// Clear the default flag of every other template
+fn clear_other_defaults(conn: &mut SqliteConnection, id: i32) -> QueryResult<usize> {
+    diesel::update(report_templates::table.filter(report_templates::id.ne(id)))
+        .set(report_templates::is_default.eq(0))
+        .execute(conn)
+}
+
+/// Get all report templates, the default first
+#[tauri::command]
+pub fn get_report_templates() -> Result<Vec<ReportTemplate>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    report_templates::table
+        .order((
+            report_templates::is_default.desc(),
+            report_templates::name.asc(),
+        ))
+        .load::<ReportTemplate>(&mut conn)
+        .map_err(|e| e.to_string())
+}
+
+/// Create a report template (admin only)
+#[tauri::command]
+pub fn create_report_template(input: CreateReportTemplateInput) -> Result<ReportTemplate, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_admin(&mut conn, input.user_id).map_err(|e| e.to_string())?;
+    validate_template(&input.body)?;
+
+    let new_template = NewReportTemplate {
+        name: input.name.trim().to_string(),
+        body: input.body,
+        is_default: input.is_default.unwrap_or(false) as i32,
+        created_by: input.user_id,
+    };
+
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        diesel::insert_into(report_templates::table)
+            .values(&new_template)
+            .execute(conn)?;
+
+        let template = report_templates::table
+            .order(report_templates::id.desc())
+            .first::<ReportTemplate>(conn)?;
+        if template.is_default == 1 {
+            clear_other_defaults(conn, template.id)?;
+        }
+
+        log_activity(
+            conn,
+            input.user_id,
+            "create",
+            "report_template",
+            Some(template.id),
+            serde_json::to_string(&template).ok(),
+        )?;
+
+        Ok(template)
+    })
+    .map_err(|e| format!("Failed to create report template: {}", e))
+}
+
+/// Update a report template (admin only)
+#[tauri::command]
+pub fn update_report_template(
+    id: i32,
+    input: UpdateReportTemplateInput,
+) -> Result<ReportTemplate, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_admin(&mut conn, input.user_id).map_err(|e| e.to_string())?;
+    if let Some(body) = &input.body {
+        validate_template(body)?;
+    }
+
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        let target = report_templates::table.find(id);
+
+        if let Some(name) = &input.name {
+            diesel::update(target)
+                .set(report_templates::name.eq(name.trim()))
+                .execute(conn)?;
+        }
+        if let Some(body) = &input.body {
+            diesel::update(target)
+                .set(report_templates::body.eq(body))
+                .execute(conn)?;
+        }
+        if input.is_default == Some(true) {
+            diesel::update(target)
+                .set(report_templates::is_default.eq(1))
+                .execute(conn)?;
+            clear_other_defaults(conn, id)?;
+        }
+        diesel::update(target)
+            .set(report_templates::updated_at.eq(chrono::Utc::now().naive_utc()))
+            .execute(conn)?;
+
+        let template = target.first::<ReportTemplate>(conn)?;
+        log_activity(
+            conn,
+            input.user_id,
+            "edit",
+            "report_template",
+            Some(template.id),
+            serde_json::to_string(&template).ok(),
+        )?;
+
+        Ok(template)
+    })
+    .map_err(|e| format!("Failed to update report template: {}", e))
+}
+
+/// Delete a report template (admin only). The default template cannot be deleted.
+#[tauri::command]
+pub fn delete_report_template(id: i32, user_id: Option<i32>) -> Result<(), String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_admin(&mut conn, user_id).map_err(|e| e.to_string())?;
+
+    let template = report_templates::table
+        .find(id)
+        .first::<ReportTemplate>(&mut conn)
+        .map_err(|e| format!("Report template not found: {}", e))?;
+    if template.is_default == 1 {
+        return Err("Choose another default before deleting this template".to_string());
+    }
+
+    conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        diesel::delete(report_templates::table.find(id)).execute(conn)?;
+
+        log_activity(
+            conn,
+            user_id,
+            "delete",
+            "report_template",
+            Some(id),
+            serde_json::to_string(&template).ok(),
+        )
+    })
+    .map_err(|e| format!("Failed to delete report template: {}", e))
+}
+
+/// Generate a case report as PDF or DOCX: case metadata, recordings with hashes,
+/// redacted transcripts, alert hits, bookmarks, annotations and the chain of custody
+#[tauri::command]
+pub async fn generate_case_report(
+    input: GenerateCaseReportInput,
+) -> Result<CaseReportResult, String> {
+    // Loading every transcript and writing the document are blocking
+    tauri::async_runtime::spawn_blocking(move || run_case_report(input))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn run_case_report(input: GenerateCaseReportInput) -> Result<CaseReportResult, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_case_role(&mut conn, input.user_id, input.case_id, CaseRole::Reader)?;
+
+    let template = match input.template_id {
+        Some(template_id) => report_templates::table
+            .find(template_id)
+            .first::<ReportTemplate>(&mut conn),
+        None => report_templates::table
+            .order((
+                report_templates::is_default.desc(),
+                report_templates::id.asc(),
+            ))
+            .first::<ReportTemplate>(&mut conn),
+    }
+    .map_err(|e| format!("Report template not found: {}", e))?;
+
+    let data = load_report_data(&mut conn, input.case_id, input.user_id)?;
+    let blocks = render_template(&template.body, &data)?;
+
+    let output_path = match &input.output_path {
+        Some(path) => PathBuf::from(path),
+        None => storage_dir("reports")
+            .map_err(|e| e.to_string())?
+            .join(format!(
+                "case{}_{}.{}",
+                data.case.id,
+                chrono::Utc::now().format("%Y%m%d%H%M%S"),
+                input.format.extension()
+            )),
+    };
+
+    let title = format!("{} - {}", data.case.code, data.case.title);
+    write_document(input.format, &title, &blocks, &output_path)?;
+    let sha256 = sha256_file(&output_path).map_err(|e| format!("Failed to hash report: {}", e))?;
+
+    log_activity(
+        &mut conn,
+        input.user_id,
+        "export",
+        "case",
+        Some(input.case_id),
+        Some(
+            serde_json::json!({
+                "operation": "case_report",
+                "format": input.format,
+                "template_id": template.id,
+                "file_path": output_path.to_string_lossy(),
+                "sha256": sha256,
+            })
+            .to_string(),
+        ),
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(CaseReportResult {
+        file_path: output_path.to_string_lossy().to_string(),
+        format: input.format,
+        sha256,
+        template_id: template.id,
+        recordings: data.recordings.len(),
+    })
+}
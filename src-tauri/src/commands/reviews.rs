@@ -0,0 +1,443 @@
+//! Transcript review commands (second-analyst review, approval and reopening)
+use crate::models::{
+    AudioFile, NewReviewAssignment, NewReviewComment, ReviewAssignment, ReviewComment,
+};
+use crate::schema::{audio_files, review_assignments, review_comments, transcript_segments, users};
+use crate::services::access_service::{accessible_case_ids, require_audio_role, CaseRole};
+use crate::services::activity_service::log_activity;
+use crate::services::auth_service::require_admin;
+use crate::services::get_pool;
+use crate::services::notification_service::notify;
+use crate::services::status_service::{
+    check_transition, record_transition, transition_error, AudioStatus,
+};
+use diesel::prelude::*;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct AssignReviewInput {
+    pub audio_file_id: i32,
+    pub reviewer_id: i32,
+    pub user_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateReviewCommentInput {
+    pub review_id: i32,
+    pub segment_id: i32,
+    pub body: String,
+    pub user_id: Option<i32>,
+}
+
+fn find_review(conn: &mut SqliteConnection, id: i32) -> Result<ReviewAssignment, String> {
+    review_assignments::table
+        .find(id)
+        .first::<ReviewAssignment>(conn)
+        .map_err(|e| format!("Review not found: {}", e))
+}
+
+/// A pending review the caller is assigned to
+fn find_pending_review_for(
+    conn: &mut SqliteConnection,
+    id: i32,
+    user_id: Option<i32>,
+) -> Result<ReviewAssignment, String> {
+    let review = find_review(conn, id)?;
+    if review.status != "pending" {
+        return Err(format!("Review is already {}", review.status));
+    }
+    if user_id != Some(review.reviewer_id) {
+        return Err("Only the assigned reviewer can decide on this review".to_string());
+    }
+    require_audio_role(conn, user_id, review.audio_file_id, CaseRole::Reader)?;
+    Ok(review)
+}
+
+/// Record a review decision and tell whoever requested the review
+fn decide_review(
+    conn: &mut SqliteConnection,
+    review: &ReviewAssignment,
+    status: &str,
+    reason: Option<String>,
+    user_id: Option<i32>,
+) -> Result<ReviewAssignment, String> {
+    conn.transaction(|conn| record_decision(conn, review, status, reason, user_id))
+        .map_err(|e| format!("Failed to update review: {}", e))
+}
+
+/// `decide_review` inside the caller's transaction
+fn record_decision(
+    conn: &mut SqliteConnection,
+    review: &ReviewAssignment,
+    status: &str,
+    reason: Option<String>,
+    user_id: Option<i32>,
+) -> QueryResult<ReviewAssignment> {
+    let file_name = audio_files::table
+        .find(review.audio_file_id)
+        .select(audio_files::file_name)
+        .first::<String>(conn)?;
+
+    diesel::update(review_assignments::table.find(review.id))
+        .set((
+            review_assignments::status.eq(status),
+            review_assignments::decision_reason.eq(&reason),
+            review_assignments::decided_at.eq(chrono::Utc::now().naive_utc()),
+        ))
+        .execute(conn)?;
+
+    log_activity(
+        conn,
+        user_id,
+        "edit",
+        "review",
+        Some(review.id),
+        Some(
+            serde_json::json!({
+                "operation": "review_decision",
+                "audio_file_id": review.audio_file_id,
+                "status": status,
+                "reason": reason,
+            })
+            .to_string(),
+        ),
+    )?;
+
+    if let Some(assigned_by) = review.assigned_by {
+        notify(
+            conn,
+            Some(assigned_by),
+            "crud",
+            "review",
+            format!("Review {}: {}", status, file_name),
+            reason.clone(),
+            Some(("audio_file", review.audio_file_id)),
+        )?;
+    }
+
+    review_assignments::table
+        .find(review.id)
+        .first::<ReviewAssignment>(conn)
+}
+
+/// Get the reviews of an audio file (newest first)
+#[tauri::command]
+pub fn get_reviews(
+    audio_file_id: i32,
+    user_id: Option<i32>,
+) -> Result<Vec<ReviewAssignment>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Reader)?;
+
+    review_assignments::table
+        .filter(review_assignments::audio_file_id.eq(audio_file_id))
+        .order(review_assignments::id.desc())
+        .load::<ReviewAssignment>(&mut conn)
+        .map_err(|e| e.to_string())
+}
+
+/// Get the reviews assigned to a user, optionally only those in one status.
+/// Only admins may look at another reviewer's queue.
+#[tauri::command]
+pub fn get_assigned_reviews(
+    reviewer_id: i32,
+    status: Option<String>,
+    user_id: Option<i32>,
+) -> Result<Vec<ReviewAssignment>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    let visible = accessible_case_ids(&mut conn, user_id)?;
+    if visible.is_some() && user_id != Some(reviewer_id) {
+        return Err("Only admins can see another reviewer's reviews".to_string());
+    }
+
+    let mut query = review_assignments::table
+        .filter(review_assignments::reviewer_id.eq(reviewer_id))
+        .into_boxed();
+    // Reviews in cases the reviewer has since lost access to are left out
+    if let Some(ids) = visible {
+        query = query.filter(
+            review_assignments::audio_file_id.eq_any(
+                audio_files::table
+                    .filter(audio_files::case_id.eq_any(ids))
+                    .select(audio_files::id),
+            ),
+        );
+    }
+    if let Some(status) = status {
+        query = query.filter(review_assignments::status.eq(status));
+    }
+
+    query
+        .order(review_assignments::created_at.desc())
+        .load::<ReviewAssignment>(&mut conn)
+        .map_err(|e| e.to_string())
+}
+
+/// Assign a reviewer to a transcribed audio file (replaces any pending review)
+#[tauri::command]
+pub fn assign_reviewer(input: AssignReviewInput) -> Result<ReviewAssignment, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+    require_audio_role(
+        &mut conn,
+        input.user_id,
+        input.audio_file_id,
+        CaseRole::Editor,
+    )?;
+
+    let audio = audio_files::table
+        .find(input.audio_file_id)
+        .first::<AudioFile>(&mut conn)
+        .map_err(|e| format!("Audio file not found: {}", e))?;
+    if audio.status != AudioStatus::Transcribed.as_str() {
+        return Err("Only transcribed audio files can be sent to review".to_string());
+    }
+
+    let reviewer_active = users::table
+        .find(input.reviewer_id)
+        .select(users::is_active)
+        .first::<i32>(&mut conn)
+        .map_err(|e| format!("User not found: {}", e))?;
+    if reviewer_active != 1 {
+        return Err("Reviewer account is disabled".to_string());
+    }
+    require_audio_role(
+        &mut conn,
+        Some(input.reviewer_id),
+        audio.id,
+        CaseRole::Reader,
+    )
+    .map_err(|_| "Reviewer has no access to this case".to_string())?;
+
+    let new_review = NewReviewAssignment {
+        audio_file_id: audio.id,
+        reviewer_id: input.reviewer_id,
+        assigned_by: input.user_id,
+    };
+
+    conn.transaction::<ReviewAssignment, diesel::result::Error, _>(|conn| {
+        let replaced = review_assignments::table
+            .filter(review_assignments::audio_file_id.eq(audio.id))
+            .filter(review_assignments::status.eq("pending"))
+            .load::<ReviewAssignment>(conn)?;
+
+        for previous in &replaced {
+            diesel::update(review_assignments::table.find(previous.id))
+                .set((
+                    review_assignments::status.eq("cancelled"),
+                    review_assignments::decided_at.eq(chrono::Utc::now().naive_utc()),
+                ))
+                .execute(conn)?;
+
+            notify(
+                conn,
+                Some(previous.reviewer_id),
+                "crud",
+                "review",
+                format!("Review cancelled: {}", audio.file_name),
+                None,
+                Some(("audio_file", audio.id)),
+            )?;
+        }
+
+        diesel::insert_into(review_assignments::table)
+            .values(&new_review)
+            .execute(conn)?;
+
+        let review = review_assignments::table
+            .order(review_assignments::id.desc())
+            .first::<ReviewAssignment>(conn)?;
+
+        log_activity(
+            conn,
+            input.user_id,
+            "create",
+            "review",
+            Some(review.id),
+            serde_json::to_string(&review).ok(),
+        )?;
+
+        notify(
+            conn,
+            Some(review.reviewer_id),
+            "crud",
+            "review",
+            format!("Review assigned: {}", audio.file_name),
+            None,
+            Some(("audio_file", audio.id)),
+        )?;
+
+        Ok(review)
+    })
+    .map_err(|e| format!("Failed to assign reviewer: {}", e))
+}
+
+/// Get the comments of a review in the order they were written
+#[tauri::command]
+pub fn get_review_comments(
+    review_id: i32,
+    user_id: Option<i32>,
+) -> Result<Vec<ReviewComment>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+    let review = find_review(&mut conn, review_id)?;
+    require_audio_role(&mut conn, user_id, review.audio_file_id, CaseRole::Reader)?;
+
+    review_comments::table
+        .filter(review_comments::review_id.eq(review_id))
+        .order(review_comments::id.asc())
+        .load::<ReviewComment>(&mut conn)
+        .map_err(|e| e.to_string())
+}
+
+/// Comment on a segment of the transcript under review
+#[tauri::command]
+pub fn add_review_comment(input: CreateReviewCommentInput) -> Result<ReviewComment, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    if input.body.trim().is_empty() {
+        return Err("Comment is empty".to_string());
+    }
+
+    let review = find_review(&mut conn, input.review_id)?;
+    require_audio_role(
+        &mut conn,
+        input.user_id,
+        review.audio_file_id,
+        CaseRole::Reader,
+    )?;
+    let segment_audio_id = transcript_segments::table
+        .find(input.segment_id)
+        .select(transcript_segments::audio_file_id)
+        .first::<i32>(&mut conn)
+        .map_err(|e| format!("Segment not found: {}", e))?;
+    if segment_audio_id != review.audio_file_id {
+        return Err("Segment does not belong to the reviewed audio file".to_string());
+    }
+
+    let new_comment = NewReviewComment {
+        review_id: review.id,
+        segment_id: input.segment_id,
+        author_id: input.user_id,
+        body: input.body,
+    };
+
+    diesel::insert_into(review_comments::table)
+        .values(&new_comment)
+        .execute(&mut conn)
+        .map_err(|e| format!("Failed to create comment: {}", e))?;
+
+    review_comments::table
+        .order(review_comments::id.desc())
+        .first::<ReviewComment>(&mut conn)
+        .map_err(|e| e.to_string())
+}
+
+/// Approve the transcript under review; the transcript is locked afterwards
+#[tauri::command]
+pub fn approve_transcript(
+    review_id: i32,
+    user_id: Option<i32>,
+    reason: Option<String>,
+) -> Result<ReviewAssignment, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    let review = find_pending_review_for(&mut conn, review_id, user_id)?;
+    let note = Some(format!("Review {}", review.id));
+
+    let audio = audio_files::table
+        .find(review.audio_file_id)
+        .first::<AudioFile>(&mut conn)
+        .map_err(|e| format!("Audio file not found: {}", e))?;
+
+    // transcribed -> reviewed -> approved, so both steps appear in the status history
+    let to_reviewed = match audio.status.parse()? {
+        AudioStatus::Reviewed => None,
+        _ => Some(check_transition(&audio, AudioStatus::Reviewed)?),
+    };
+
+    // The transcript is only locked together with the approved review
+    conn.transaction::<ReviewAssignment, diesel::result::Error, _>(|conn| {
+        if let Some(from) = to_reviewed {
+            record_transition(
+                conn,
+                audio.id,
+                from,
+                AudioStatus::Reviewed,
+                user_id,
+                note.clone(),
+            )?;
+        }
+        record_transition(
+            conn,
+            audio.id,
+            AudioStatus::Reviewed,
+            AudioStatus::Approved,
+            user_id,
+            note,
+        )?;
+        record_decision(conn, &review, "approved", reason, user_id)
+    })
+    .map_err(transition_error)
+}
+
+/// Reject the transcript under review (a reason is required)
+#[tauri::command]
+pub fn reject_transcript(
+    review_id: i32,
+    user_id: Option<i32>,
+    reason: String,
+) -> Result<ReviewAssignment, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    if reason.trim().is_empty() {
+        return Err("A reason is required to reject a transcript".to_string());
+    }
+
+    let review = find_pending_review_for(&mut conn, review_id, user_id)?;
+    decide_review(&mut conn, &review, "rejected", Some(reason), user_id)
+}
+
+/// Unlock an approved transcript for editing (admin only)
+#[tauri::command]
+pub fn reopen_transcript(
+    audio_file_id: i32,
+    user_id: Option<i32>,
+    reason: String,
+) -> Result<AudioFile, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_admin(&mut conn, user_id).map_err(|e| e.to_string())?;
+    if reason.trim().is_empty() {
+        return Err("A reason is required to reopen a transcript".to_string());
+    }
+
+    let audio = audio_files::table
+        .find(audio_file_id)
+        .first::<AudioFile>(&mut conn)
+        .map_err(|e| format!("Audio file not found: {}", e))?;
+    let from = check_transition(&audio, AudioStatus::Transcribed)?;
+
+    let approved = review_assignments::table
+        .filter(review_assignments::audio_file_id.eq(audio_file_id))
+        .filter(review_assignments::status.eq("approved"))
+        .order(review_assignments::id.desc())
+        .first::<ReviewAssignment>(&mut conn)
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    conn.transaction::<AudioFile, diesel::result::Error, _>(|conn| {
+        let audio = record_transition(
+            conn,
+            audio_file_id,
+            from,
+            AudioStatus::Transcribed,
+            user_id,
+            Some(reason.clone()),
+        )?;
+        if let Some(review) = approved {
+            record_decision(conn, &review, "reopened", Some(reason), user_id)?;
+        }
+        Ok(audio)
+    })
+    .map_err(transition_error)
+}
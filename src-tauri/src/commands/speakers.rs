@@ -1,5 +1,8 @@
 //! Speaker CRUD commands
-use crate::models::{NewSpeaker, NewVoiceSample, Speaker, VoiceSample};
+use crate::models::pagination::invalid_sort;
+use crate::models::{
+    ListQuery, NewSpeaker, NewVoiceSample, Page, SortDirection, Speaker, VoiceSample,
+};
 use crate::schema::{speakers, voice_samples};
 use crate::services::get_pool;
 use diesel::prelude::*;
@@ -31,21 +34,64 @@ pub struct CreateVoiceSampleInput {
     pub duration: f32,
 }
 
-/// Get all speakers ordered by name
+/// Speakers matching the query's search (name, alias, notes) and date range
+fn speaker_query(query: &ListQuery) -> speakers::BoxedQuery<'static, diesel::sqlite::Sqlite> {
+    let mut boxed = speakers::table.into_boxed();
+    if let Some(pattern) = query.search_pattern() {
+        boxed = boxed.filter(
+            speakers::name
+                .like(pattern.clone())
+                .escape('\\')
+                .or(speakers::alias.like(pattern.clone()).escape('\\'))
+                .or(speakers::notes.like(pattern).escape('\\')),
+        );
+    }
+    if let Some(from) = query.date_from {
+        boxed = boxed.filter(speakers::created_at.ge(from));
+    }
+    if let Some(to) = query.date_to {
+        boxed = boxed.filter(speakers::created_at.le(to));
+    }
+    boxed
+}
+
+/// Get a page of speakers (by name by default). Sortable by name or created_at.
 #[tauri::command]
-pub fn get_speakers() -> Result<Vec<Speaker>, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+pub fn get_speakers(query: Option<ListQuery>) -> Result<Page<Speaker>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    let query = query.unwrap_or_default();
+    let page_size = query.page_size(50);
+    let offset = query.offset(page_size);
+
+    let total = speaker_query(&query)
+        .count()
+        .get_result::<i64>(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+    let desc = query.descending(SortDirection::Asc);
+    let sorted = match query.sort_field("name") {
+        "name" if desc => speaker_query(&query).order(speakers::name.desc()),
+        "name" => speaker_query(&query).order(speakers::name.asc()),
+        "created_at" if desc => speaker_query(&query).order(speakers::created_at.desc()),
+        "created_at" => speaker_query(&query).order(speakers::created_at.asc()),
+        field => return Err(invalid_sort(field)),
+    };
 
-    speakers::table
-        .order(speakers::name.asc())
+    let items = sorted
+        .then_order_by(speakers::id.asc())
+        .limit(page_size)
+        .offset(offset)
         .load::<Speaker>(&mut conn)
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    Ok(Page::new(items, total, offset, page_size))
 }
 
 /// Get a single speaker by ID
 #[tauri::command]
 pub fn get_speaker(id: i32) -> Result<Speaker, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
     speakers::table
         .find(id)
@@ -56,7 +102,7 @@ pub fn get_speaker(id: i32) -> Result<Speaker, String> {
 /// Create a new speaker
 #[tauri::command]
 pub fn create_speaker(input: CreateSpeakerInput) -> Result<Speaker, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
     let new_speaker = NewSpeaker {
         name: input.name,
@@ -80,7 +126,7 @@ pub fn create_speaker(input: CreateSpeakerInput) -> Result<Speaker, String> {
 /// Update a speaker
 #[tauri::command]
 pub fn update_speaker(id: i32, input: UpdateSpeakerInput) -> Result<Speaker, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
     let target = speakers::table.find(id);
 
     if let Some(name) = input.name {
@@ -124,7 +170,7 @@ pub fn update_speaker(id: i32, input: UpdateSpeakerInput) -> Result<Speaker, Str
 /// Delete a speaker (voice samples cascade)
 #[tauri::command]
 pub fn delete_speaker(id: i32) -> Result<(), String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
     diesel::delete(speakers::table.find(id))
         .execute(&mut conn)
@@ -140,7 +186,7 @@ pub fn delete_speaker(id: i32) -> Result<(), String> {
 /// Get voice samples for a speaker
 #[tauri::command]
 pub fn get_voice_samples(speaker_id: i32) -> Result<Vec<VoiceSample>, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
     voice_samples::table
         .filter(voice_samples::speaker_id.eq(speaker_id))
@@ -152,7 +198,7 @@ pub fn get_voice_samples(speaker_id: i32) -> Result<Vec<VoiceSample>, String> {
 /// Add a voice sample to a speaker
 #[tauri::command]
 pub fn create_voice_sample(input: CreateVoiceSampleInput) -> Result<VoiceSample, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
     let new_sample = NewVoiceSample {
         speaker_id: input.speaker_id,
@@ -175,7 +221,7 @@ pub fn create_voice_sample(input: CreateVoiceSampleInput) -> Result<VoiceSample,
 /// Delete a voice sample
 #[tauri::command]
 pub fn delete_voice_sample(id: i32) -> Result<(), String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
     diesel::delete(voice_samples::table.find(id))
         .execute(&mut conn)
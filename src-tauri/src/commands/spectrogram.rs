@@ -0,0 +1,141 @@
+//! Spectrogram commands (PNG tiles cached on disk for forensic inspection)
+use crate::models::AudioFile;
+use crate::schema::audio_files;
+use crate::services::access_service::{require_audio_role, CaseRole};
+use crate::services::ffmpeg::decode_pcm_range;
+use crate::services::get_pool;
+use crate::services::spectrogram_service::{
+    cache_key, render, tile_span, write_png, SpectrogramParams,
+};
+use crate::services::storage::storage_dir;
+use crate::services::working_copy_service::{analysis_path, WORKING_SAMPLE_RATE};
+use diesel::prelude::*;
+use serde::Serialize;
+
+/// Tiles rendered per call; longer ranges are fetched in several calls
+const MAX_TILES_PER_CALL: u32 = 30;
+
+#[derive(Debug, Serialize)]
+pub struct SpectrogramTile {
+    pub index: u32,
+    pub start_time: f32,
+    pub end_time: f32,
+    pub file_path: String,
+}
+
+/// Render (or reuse cached) spectrogram tiles covering a time range of an audio file.
+/// At most `MAX_TILES_PER_CALL` tiles are returned, starting at `start_time`.
+#[tauri::command]
+pub async fn render_spectrogram(
+    audio_file_id: i32,
+    start_time: f32,
+    end_time: f32,
+    params: Option<SpectrogramParams>,
+    user_id: Option<i32>,
+) -> Result<Vec<SpectrogramTile>, String> {
+    // Decoding and FFTs are CPU bound
+    tauri::async_runtime::spawn_blocking(move || {
+        run_spectrogram(audio_file_id, start_time, end_time, params, user_id)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+fn run_spectrogram(
+    audio_file_id: i32,
+    start_time: f32,
+    end_time: f32,
+    params: Option<SpectrogramParams>,
+    user_id: Option<i32>,
+) -> Result<Vec<SpectrogramTile>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+    let params = params.unwrap_or_default().normalized();
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Reader)?;
+
+    let audio = audio_files::table
+        .find(audio_file_id)
+        .first::<AudioFile>(&mut conn)
+        .map_err(|e| format!("Audio file not found: {}", e))?;
+
+    let end_time = if audio.duration > 0.0 {
+        end_time.min(audio.duration)
+    } else {
+        end_time
+    };
+    if end_time <= start_time {
+        return Err("Spectrogram range is empty".to_string());
+    }
+
+    // The working copy holds everything up to its Nyquist frequency
+    let source = if params.sample_rate <= WORKING_SAMPLE_RATE {
+        analysis_path(&mut conn, &audio)
+    } else {
+        std::path::PathBuf::from(&audio.file_path)
+    };
+
+    // Tiles are cached per parameter set and source version so nothing stale is reused
+    let cache_dir = storage_dir("spectrograms")
+        .map_err(|e| e.to_string())?
+        .join(format!("audio{}", audio.id))
+        .join(cache_key(&params, &source)?);
+    std::fs::create_dir_all(&cache_dir).map_err(|e| e.to_string())?;
+
+    let first = (start_time.max(0.0) / params.tile_seconds).floor() as u32;
+    let last = ((end_time / params.tile_seconds).ceil() as u32)
+        .max(first + 1)
+        .min(first + MAX_TILES_PER_CALL);
+
+    let mut tiles = Vec::new();
+    for index in first..last {
+        // The last tile only covers the rest of the recording
+        let Some((tile_start, tile_end)) = tile_span(index, params.tile_seconds, audio.duration)
+        else {
+            break;
+        };
+        let file_path = cache_dir.join(format!("tile_{:05}.png", index));
+
+        if !file_path.exists() {
+            let samples = decode_pcm_range(
+                &source,
+                params.sample_rate,
+                Some((tile_start, tile_end - tile_start)),
+            )
+            .map_err(|e| e.to_string())?;
+
+            if samples.is_empty() {
+                break;
+            }
+
+            let (width, height, pixels) = render(&samples, &params);
+            write_png(&file_path, width, height, &pixels)?;
+        }
+
+        tiles.push(SpectrogramTile {
+            index,
+            start_time: tile_start,
+            end_time: tile_end,
+            file_path: file_path.to_string_lossy().to_string(),
+        });
+    }
+
+    Ok(tiles)
+}
+
+/// Delete all cached spectrogram tiles of an audio file
+#[tauri::command]
+pub fn clear_spectrogram_cache(audio_file_id: i32, user_id: Option<i32>) -> Result<(), String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Editor)?;
+
+    let dir = storage_dir("spectrograms")
+        .map_err(|e| e.to_string())?
+        .join(format!("audio{}", audio_file_id));
+
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).map_err(|e| format!("Failed to clear cache: {}", e))?;
+    }
+
+    Ok(())
+}
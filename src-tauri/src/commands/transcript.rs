@@ -1,9 +1,17 @@
 //! Transcript segment commands
-use crate::models::{NewTranscriptSegment, TranscriptSegment};
-use crate::schema::transcript_segments;
+use crate::models::transcript_segment::encode_words;
+use crate::models::{NewTranscriptSegment, TranscriptSegment, WordTiming};
+use crate::schema::{audio_files, channel_speakers, transcript_segments};
+use crate::services::access_service::{require_audio_role, require_segment_role, CaseRole};
+use crate::services::activity_service::log_activity;
+use crate::services::auth_service::require_admin;
+use crate::services::export_service::{build_transcript_export, TranscriptExport};
 use crate::services::get_pool;
+use crate::services::review_service::ensure_transcript_unlocked;
+use crate::services::status_service::AudioStatus;
 use diesel::prelude::*;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Deserialize)]
 pub struct CreateSegmentInput {
@@ -12,6 +20,9 @@ pub struct CreateSegmentInput {
     pub start_time: f32,
     pub end_time: f32,
     pub text: String,
+    pub words: Option<Vec<WordTiming>>,
+    pub channel: Option<i32>,
+    pub user_id: Option<i32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -19,12 +30,48 @@ pub struct UpdateSegmentInput {
     pub speaker_id: Option<i32>,
     pub text: Option<String>,
     pub is_deleted: Option<bool>,
+    pub user_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PurgeSegmentsInput {
+    pub audio_file_id: Option<i32>,
+    pub retention_days: i32,
+    pub user_id: Option<i32>,
+}
+
+/// A recognized word below the confidence threshold, for review
+#[derive(Debug, Serialize)]
+pub struct LowConfidenceWord {
+    pub segment_id: i32,
+    pub speaker_id: Option<i32>,
+    pub word: String,
+    pub start: f32,
+    pub end: f32,
+    pub confidence: f32,
+}
+
+/// Speaker assigned to each channel of a multi-channel recording
+fn channel_speaker_map(
+    conn: &mut SqliteConnection,
+    audio_file_id: i32,
+) -> QueryResult<HashMap<i32, i32>> {
+    channel_speakers::table
+        .filter(channel_speakers::audio_file_id.eq(audio_file_id))
+        .select((channel_speakers::channel, channel_speakers::speaker_id))
+        .load::<(i32, i32)>(conn)
+        .map(|rows| rows.into_iter().collect())
 }
 
 /// Get all transcript segments for an audio file
 #[tauri::command]
-pub fn get_transcript_segments(audio_file_id: i32) -> Result<Vec<TranscriptSegment>, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+pub fn get_transcript_segments(
+    audio_file_id: i32,
+    user_id: Option<i32>,
+) -> Result<Vec<TranscriptSegment>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Reader)?;
 
     transcript_segments::table
         .filter(transcript_segments::audio_file_id.eq(audio_file_id))
@@ -36,14 +83,31 @@ pub fn get_transcript_segments(audio_file_id: i32) -> Result<Vec<TranscriptSegme
 /// Create a new transcript segment
 #[tauri::command]
 pub fn create_transcript_segment(input: CreateSegmentInput) -> Result<TranscriptSegment, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(
+        &mut conn,
+        input.user_id,
+        input.audio_file_id,
+        CaseRole::Editor,
+    )?;
+    ensure_transcript_unlocked(&mut conn, input.audio_file_id)?;
+
+    let speaker_by_channel =
+        channel_speaker_map(&mut conn, input.audio_file_id).map_err(|e| e.to_string())?;
 
     let new_segment = NewTranscriptSegment {
         audio_file_id: input.audio_file_id,
-        speaker_id: input.speaker_id,
+        speaker_id: input.speaker_id.or_else(|| {
+            input
+                .channel
+                .and_then(|c| speaker_by_channel.get(&c).copied())
+        }),
         start_time: input.start_time,
         end_time: input.end_time,
         text: input.text,
+        words: input.words.as_deref().map(encode_words),
+        channel: input.channel,
     };
 
     diesel::insert_into(transcript_segments::table)
@@ -63,9 +127,15 @@ pub fn update_transcript_segment(
     id: i32,
     input: UpdateSegmentInput,
 ) -> Result<TranscriptSegment, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
     let target = transcript_segments::table.find(id);
 
+    require_segment_role(&mut conn, input.user_id, id, CaseRole::Editor)?;
+    let segment = target
+        .first::<TranscriptSegment>(&mut conn)
+        .map_err(|e| format!("Segment not found: {}", e))?;
+    ensure_transcript_unlocked(&mut conn, segment.audio_file_id)?;
+
     if let Some(speaker_id) = input.speaker_id {
         diesel::update(target)
             .set(transcript_segments::speaker_id.eq(speaker_id))
@@ -81,22 +151,85 @@ pub fn update_transcript_segment(
     }
 
     if let Some(is_deleted) = input.is_deleted {
+        let deleted_at = is_deleted.then(|| chrono::Utc::now().naive_utc());
         diesel::update(target)
-            .set(transcript_segments::is_deleted.eq(if is_deleted { 1 } else { 0 }))
+            .set((
+                transcript_segments::is_deleted.eq(if is_deleted { 1 } else { 0 }),
+                transcript_segments::deleted_at.eq(deleted_at),
+            ))
             .execute(&mut conn)
             .map_err(|e| e.to_string())?;
     }
 
-    transcript_segments::table
+    let segment = transcript_segments::table
         .find(id)
         .first::<TranscriptSegment>(&mut conn)
-        .map_err(|e| format!("Segment not found: {}", e))
+        .map_err(|e| format!("Segment not found: {}", e))?;
+
+    log_activity(
+        &mut conn,
+        input.user_id,
+        "edit",
+        "segment",
+        Some(segment.id),
+        serde_json::to_string(&segment).ok(),
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(segment)
+}
+
+/// Get words recognized below `threshold` confidence across an audio file, in playback order
+#[tauri::command]
+pub fn get_low_confidence_words(
+    audio_file_id: i32,
+    threshold: Option<f32>,
+    user_id: Option<i32>,
+) -> Result<Vec<LowConfidenceWord>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+    let threshold = threshold.unwrap_or(0.6);
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Reader)?;
+
+    let segments = transcript_segments::table
+        .filter(transcript_segments::audio_file_id.eq(audio_file_id))
+        .filter(transcript_segments::is_deleted.eq(0))
+        .filter(transcript_segments::words.is_not_null())
+        .order(transcript_segments::start_time.asc())
+        .load::<TranscriptSegment>(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+    Ok(segments
+        .iter()
+        .flat_map(|segment| {
+            segment
+                .word_timings()
+                .into_iter()
+                .filter(|w| w.confidence < threshold)
+                .map(|w| LowConfidenceWord {
+                    segment_id: segment.id,
+                    speaker_id: segment.speaker_id,
+                    word: w.word,
+                    start: w.start,
+                    end: w.end,
+                    confidence: w.confidence,
+                })
+        })
+        .collect())
 }
 
 /// Delete a transcript segment permanently
 #[tauri::command]
-pub fn delete_transcript_segment(id: i32) -> Result<(), String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+pub fn delete_transcript_segment(id: i32, user_id: Option<i32>) -> Result<(), String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_segment_role(&mut conn, user_id, id, CaseRole::Editor)?;
+    let audio_file_id = transcript_segments::table
+        .find(id)
+        .select(transcript_segments::audio_file_id)
+        .first::<i32>(&mut conn)
+        .map_err(|e| format!("Segment not found: {}", e))?;
+    ensure_transcript_unlocked(&mut conn, audio_file_id)?;
 
     diesel::delete(transcript_segments::table.find(id))
         .execute(&mut conn)
@@ -110,17 +243,29 @@ pub fn delete_transcript_segment(id: i32) -> Result<(), String> {
 pub fn bulk_create_segments(
     audio_file_id: i32,
     segments: Vec<CreateSegmentInput>,
+    user_id: Option<i32>,
 ) -> Result<usize, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Editor)?;
+    ensure_transcript_unlocked(&mut conn, audio_file_id)?;
+
+    // Segments from a mapped channel inherit that channel's speaker
+    let speaker_by_channel =
+        channel_speaker_map(&mut conn, audio_file_id).map_err(|e| e.to_string())?;
 
     let new_segments: Vec<NewTranscriptSegment> = segments
         .into_iter()
         .map(|s| NewTranscriptSegment {
             audio_file_id,
-            speaker_id: s.speaker_id,
+            speaker_id: s
+                .speaker_id
+                .or_else(|| s.channel.and_then(|c| speaker_by_channel.get(&c).copied())),
             start_time: s.start_time,
             end_time: s.end_time,
             text: s.text,
+            words: s.words.as_deref().map(encode_words),
+            channel: s.channel,
         })
         .collect();
 
@@ -131,3 +276,167 @@ pub fn bulk_create_segments(
 
     Ok(count)
 }
+
+// ============================================
+// Soft-delete recovery commands
+// ============================================
+
+/// Get soft-deleted segments for an audio file (most recently deleted first)
+#[tauri::command]
+pub fn get_deleted_segments(
+    audio_file_id: i32,
+    user_id: Option<i32>,
+) -> Result<Vec<TranscriptSegment>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Reader)?;
+
+    transcript_segments::table
+        .filter(transcript_segments::audio_file_id.eq(audio_file_id))
+        .filter(transcript_segments::is_deleted.eq(1))
+        .order(transcript_segments::deleted_at.desc())
+        .load::<TranscriptSegment>(&mut conn)
+        .map_err(|e| e.to_string())
+}
+
+/// Restore soft-deleted segments of an audio file in one transaction.
+/// Restores all of them when `segment_ids` is omitted.
+#[tauri::command]
+pub fn restore_segments(
+    audio_file_id: i32,
+    segment_ids: Option<Vec<i32>>,
+    user_id: Option<i32>,
+) -> Result<usize, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Editor)?;
+    ensure_transcript_unlocked(&mut conn, audio_file_id)?;
+
+    conn.transaction::<usize, diesel::result::Error, _>(|conn| {
+        let deleted = transcript_segments::table
+            .filter(transcript_segments::audio_file_id.eq(audio_file_id))
+            .filter(transcript_segments::is_deleted.eq(1))
+            .into_boxed();
+
+        let deleted = match segment_ids {
+            Some(ids) => deleted.filter(transcript_segments::id.eq_any(ids)),
+            None => deleted,
+        };
+
+        let ids: Vec<i32> = deleted.select(transcript_segments::id).load(conn)?;
+
+        diesel::update(transcript_segments::table.filter(transcript_segments::id.eq_any(&ids)))
+            .set((
+                transcript_segments::is_deleted.eq(0),
+                transcript_segments::deleted_at.eq(None::<chrono::NaiveDateTime>),
+            ))
+            .execute(conn)
+    })
+    .map_err(|e| format!("Failed to restore segments: {}", e))
+}
+
+/// Permanently delete segments that were soft-deleted more than `retention_days` ago
+#[tauri::command]
+pub fn purge_deleted_segments(input: PurgeSegmentsInput) -> Result<usize, String> {
+    if input.retention_days < 0 {
+        return Err("Retention days must not be negative".to_string());
+    }
+
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    match input.audio_file_id {
+        Some(audio_file_id) => {
+            require_audio_role(&mut conn, input.user_id, audio_file_id, CaseRole::Editor)?;
+            ensure_transcript_unlocked(&mut conn, audio_file_id)?;
+        }
+        // Purging every case at once is an admin task
+        None => {
+            require_admin(&mut conn, input.user_id).map_err(|e| e.to_string())?;
+        }
+    }
+
+    let cutoff_date =
+        chrono::Utc::now().naive_utc() - chrono::Duration::days(input.retention_days as i64);
+
+    conn.transaction::<usize, diesel::result::Error, _>(|conn| {
+        // Approved transcripts are locked, including their deleted segments
+        let locked: Vec<i32> = audio_files::table
+            .filter(audio_files::status.eq(AudioStatus::Approved.as_str()))
+            .select(audio_files::id)
+            .load(conn)?;
+
+        let expired = transcript_segments::table
+            .filter(transcript_segments::is_deleted.eq(1))
+            .filter(transcript_segments::deleted_at.lt(cutoff_date))
+            .filter(transcript_segments::audio_file_id.ne_all(&locked))
+            .into_boxed();
+
+        let expired = match input.audio_file_id {
+            Some(audio_file_id) => {
+                expired.filter(transcript_segments::audio_file_id.eq(audio_file_id))
+            }
+            None => expired,
+        };
+
+        let ids: Vec<i32> = expired.select(transcript_segments::id).load(conn)?;
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let count =
+            diesel::delete(transcript_segments::table.filter(transcript_segments::id.eq_any(&ids)))
+                .execute(conn)?;
+
+        let details = serde_json::json!({
+            "operation": "purge_segments",
+            "retention_days": input.retention_days,
+            "cutoff": cutoff_date,
+            "segment_ids": ids,
+        });
+        log_activity(
+            conn,
+            input.user_id,
+            "delete",
+            "audio_file",
+            input.audio_file_id,
+            Some(details.to_string()),
+        )?;
+
+        Ok(count)
+    })
+    .map_err(|e| format!("Failed to purge segments: {}", e))
+}
+
+/// Export the redacted transcript of an audio file, with its annotations unless excluded
+#[tauri::command]
+pub fn export_transcript(
+    audio_file_id: i32,
+    include_annotations: Option<bool>,
+    user_id: Option<i32>,
+) -> Result<TranscriptExport, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Reader)?;
+
+    let include_annotations = include_annotations.unwrap_or(true);
+    let export = build_transcript_export(&mut conn, audio_file_id, include_annotations)?;
+
+    log_activity(
+        &mut conn,
+        user_id,
+        "export",
+        "audio_file",
+        Some(audio_file_id),
+        Some(
+            serde_json::json!({
+                "operation": "export_transcript",
+                "segments": export.segments.len(),
+                "annotations": export.annotations.len(),
+            })
+            .to_string(),
+        ),
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(export)
+}
@@ -0,0 +1,654 @@
+//! Chunked transcription commands (long recordings split into overlapping windows,
+//! transcribed in parallel and stitched back into one transcript)
+use crate::models::{
+    AudioFile, NewTranscriptSegment, NewTranscriptionChunk, NewTranscriptionJob, TimeRange,
+    TranscriptionChunk, TranscriptionJob, VadAnalysis,
+};
+use crate::schema::{
+    audio_files, channel_speakers, transcript_segments, transcription_chunks, transcription_jobs,
+    vad_analyses,
+};
+use crate::services::access_service::{require_audio_role, CaseRole};
+use crate::services::activity_service::log_activity;
+use crate::services::chunking_service::{
+    plan_chunks, plan_chunks_in, stitch, ChunkParams, ChunkSegment,
+};
+use crate::services::enhancement_service::ensure_enhanced_copy;
+use crate::services::ffmpeg::{probe_duration, run_ffmpeg, OutputFormat};
+use crate::services::get_pool;
+use crate::services::review_service::ensure_transcript_unlocked;
+use crate::services::status_service::{
+    check_transition, record_transition, transition_audio_status, transition_error, AudioStatus,
+};
+use crate::services::storage::storage_dir;
+use crate::services::transcriber::transcribe_wav;
+use crate::services::vad_service::transcription_ranges;
+use crate::services::working_copy_service::{
+    analysis_path, ensure_channel_tracks, WORKING_SAMPLE_RATE,
+};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+/// Upper bound on concurrent chunks (each one also takes a pooled connection to save)
+const MAX_WORKERS: usize = 3;
+
+/// Padding kept around speech regions when silence is skipped (seconds)
+const SPEECH_PADDING: f32 = 0.3;
+
+/// Audio files with a transcription running in this process
+static RUNNING: Mutex<BTreeSet<i32>> = Mutex::new(BTreeSet::new());
+
+/// Options of a transcription job (stored with it so a resumed job keeps its plan)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TranscriptionParams {
+    #[serde(flatten)]
+    pub chunks: ChunkParams,
+    /// Transcribe the enhanced copy (current preset) instead of the working copy
+    pub enhanced: bool,
+    /// Transcribe each channel of a multi-channel recording as its own track
+    pub per_channel: bool,
+    /// Only transcribe the speech regions of the stored voice activity analysis
+    pub skip_silence: bool,
+}
+
+impl Default for TranscriptionParams {
+    fn default() -> Self {
+        Self {
+            chunks: ChunkParams::default(),
+            enhanced: false,
+            per_channel: true,
+            skip_silence: false,
+        }
+    }
+}
+
+impl TranscriptionParams {
+    fn normalized(mut self) -> Self {
+        self.chunks = self.chunks.normalized();
+        self
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TranscribeInput {
+    pub audio_file_id: i32,
+    pub params: Option<TranscriptionParams>,
+    pub user_id: Option<i32>,
+}
+
+/// Payload of the `transcription-progress` event, emitted after each chunk
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptionProgress {
+    pub job_id: i32,
+    pub audio_file_id: i32,
+    pub chunk_index: i32,
+    pub completed: usize,
+    pub total: usize,
+    pub error: Option<String>,
+}
+
+/// Marks an audio file as being transcribed until dropped
+struct RunningGuard(i32);
+
+impl RunningGuard {
+    fn claim(audio_file_id: i32) -> Result<Self, String> {
+        let mut running = RUNNING.lock().map_err(|e| e.to_string())?;
+        if !running.insert(audio_file_id) {
+            return Err("A transcription of this audio file is already running".to_string());
+        }
+        Ok(Self(audio_file_id))
+    }
+}
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        if let Ok(mut running) = RUNNING.lock() {
+            running.remove(&self.0);
+        }
+    }
+}
+
+/// Transcribe an audio file in overlapping chunks processed in parallel.
+/// An unfinished job of the same file is resumed from its last completed chunks.
+#[tauri::command]
+pub async fn transcribe_audio_chunked(
+    app: AppHandle,
+    input: TranscribeInput,
+) -> Result<TranscriptionJob, String> {
+    tauri::async_runtime::spawn_blocking(move || run_transcription(&app, input))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn run_transcription(app: &AppHandle, input: TranscribeInput) -> Result<TranscriptionJob, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+    require_audio_role(
+        &mut conn,
+        input.user_id,
+        input.audio_file_id,
+        CaseRole::Editor,
+    )?;
+    let _running = RunningGuard::claim(input.audio_file_id)?;
+
+    let audio = audio_files::table
+        .find(input.audio_file_id)
+        .first::<AudioFile>(&mut conn)
+        .map_err(|e| format!("Audio file not found: {}", e))?;
+    ensure_transcript_unlocked(&mut conn, audio.id)?;
+
+    // Validated before any job is created; a file left in processing by a crash
+    // is resumed as is
+    let from = if audio.status == AudioStatus::Processing.as_str() {
+        None
+    } else {
+        Some(check_transition(&audio, AudioStatus::Processing)?)
+    };
+
+    let unfinished = transcription_jobs::table
+        .filter(transcription_jobs::audio_file_id.eq(audio.id))
+        .filter(transcription_jobs::status.ne("completed"))
+        .order(transcription_jobs::id.desc())
+        .first::<TranscriptionJob>(&mut conn)
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    let job = match unfinished {
+        Some(job) => {
+            let stored: TranscriptionParams =
+                serde_json::from_str(&job.params).map_err(|e| e.to_string())?;
+            if input
+                .params
+                .is_some_and(|params| params.normalized() != stored)
+            {
+                return Err(format!(
+                    "Unfinished transcription job {} of this file used other parameters; \
+                     resume it with the same parameters",
+                    job.id
+                ));
+            }
+            if from.is_some() {
+                transition_audio_status(
+                    &mut conn,
+                    audio.id,
+                    AudioStatus::Processing,
+                    input.user_id,
+                    Some(format!("Transcription job {}", job.id)),
+                )?;
+            }
+            job
+        }
+        None => {
+            let params = input.params.unwrap_or_default().normalized();
+            create_job(&mut conn, &audio, &params, from, input.user_id)?
+        }
+    };
+    let params: TranscriptionParams =
+        serde_json::from_str(&job.params).map_err(|e| e.to_string())?;
+
+    diesel::update(transcription_jobs::table.find(job.id))
+        .set((
+            transcription_jobs::status.eq("running"),
+            transcription_jobs::error.eq(None::<String>),
+        ))
+        .execute(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+    let pending = transcription_chunks::table
+        .filter(transcription_chunks::job_id.eq(job.id))
+        .filter(transcription_chunks::status.ne("completed"))
+        .order(transcription_chunks::chunk_index.desc())
+        .load::<TranscriptionChunk>(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+    let channels: BTreeSet<Option<i32>> = pending.iter().map(|chunk| chunk.channel).collect();
+    let mut sources = BTreeMap::new();
+    for channel in channels {
+        // Sealed sources are decrypted per chunk as ffmpeg reads them
+        sources.insert(
+            channel,
+            source_path(&mut conn, &audio, &params, channel, input.user_id)?,
+        );
+    }
+
+    // Workers save through their own pooled connections
+    drop(conn);
+
+    let work_dir = storage_dir("chunks")
+        .map_err(|e| e.to_string())?
+        .join(format!("job{}", job.id));
+    std::fs::create_dir_all(&work_dir).map_err(|e| e.to_string())?;
+
+    let total = job.total_chunks as usize;
+    let cores = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+    let workers = (cores / 4).clamp(1, MAX_WORKERS).min(pending.len().max(1));
+    let threads = (cores / workers).max(1);
+    let completed = AtomicUsize::new(total - pending.len());
+    let queue = Mutex::new(pending);
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                while let Some(chunk) = queue.lock().map(|mut q| q.pop()).unwrap_or(None) {
+                    let source = &sources[&chunk.channel];
+                    let result = process_chunk(source, &work_dir, &chunk, threads);
+                    let error = save_chunk(chunk.id, result).err();
+                    let done = if error.is_none() {
+                        completed.fetch_add(1, Ordering::SeqCst) + 1
+                    } else {
+                        completed.load(Ordering::SeqCst)
+                    };
+
+                    let _ = app.emit(
+                        "transcription-progress",
+                        TranscriptionProgress {
+                            job_id: job.id,
+                            audio_file_id: job.audio_file_id,
+                            chunk_index: chunk.chunk_index,
+                            completed: done,
+                            total,
+                            error,
+                        },
+                    );
+                }
+            });
+        }
+    });
+
+    let _ = std::fs::remove_dir_all(&work_dir);
+
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+    finish_job(&mut conn, job.id, input.user_id)
+}
+
+/// File a chunk is cut from: the channel track, or the enhanced or working copy
+fn source_path(
+    conn: &mut SqliteConnection,
+    audio: &AudioFile,
+    params: &TranscriptionParams,
+    channel: Option<i32>,
+    user_id: Option<i32>,
+) -> Result<PathBuf, String> {
+    match channel {
+        Some(channel) => ensure_channel_tracks(conn, audio, user_id)?
+            .into_iter()
+            .nth(channel as usize)
+            .map(|track| PathBuf::from(track.file_path))
+            .ok_or_else(|| format!("Channel {} not found", channel)),
+        None if params.enhanced => Ok(PathBuf::from(
+            ensure_enhanced_copy(conn, audio.id, user_id)?.file_path,
+        )),
+        None => Ok(analysis_path(conn, audio)),
+    }
+}
+
+/// Windows to transcribe: the whole recording, or only its speech regions
+fn plan_windows(
+    conn: &mut SqliteConnection,
+    audio: &AudioFile,
+    params: &TranscriptionParams,
+) -> Result<Vec<TimeRange>, String> {
+    let duration = if audio.duration > 0.0 {
+        audio.duration
+    } else {
+        probe_duration(&analysis_path(conn, audio)).map_err(|e| e.to_string())?
+    };
+
+    if !params.skip_silence {
+        return Ok(plan_chunks(duration, &params.chunks));
+    }
+
+    let analysis = vad_analyses::table
+        .filter(vad_analyses::audio_file_id.eq(audio.id))
+        .first::<VadAnalysis>(conn)
+        .optional()
+        .map_err(|e| e.to_string())?
+        .ok_or("Run voice activity detection on this file first")?;
+    let speech = transcription_ranges(
+        &analysis.speech_regions(),
+        duration,
+        SPEECH_PADDING,
+        f32::INFINITY,
+    );
+    Ok(plan_chunks_in(&speech, &params.chunks))
+}
+
+fn create_job(
+    conn: &mut SqliteConnection,
+    audio: &AudioFile,
+    params: &TranscriptionParams,
+    from: Option<AudioStatus>,
+    user_id: Option<i32>,
+) -> Result<TranscriptionJob, String> {
+    let channels: Vec<Option<i32>> = if params.per_channel && audio.channels >= 2 {
+        if params.enhanced {
+            return Err(
+                "The enhanced copy is mixed down; turn off per-channel transcription to use it"
+                    .to_string(),
+            );
+        }
+        (0..audio.channels).map(Some).collect()
+    } else {
+        vec![None]
+    };
+    let windows = plan_windows(conn, audio, params)?;
+
+    let new_job = NewTranscriptionJob {
+        audio_file_id: audio.id,
+        params: serde_json::to_string(params).map_err(|e| e.to_string())?,
+        total_chunks: (channels.len() * windows.len()) as i32,
+        created_by: user_id,
+    };
+
+    conn.transaction::<TranscriptionJob, diesel::result::Error, _>(|conn| {
+        diesel::insert_into(transcription_jobs::table)
+            .values(&new_job)
+            .execute(conn)?;
+
+        let job = transcription_jobs::table
+            .order(transcription_jobs::id.desc())
+            .first::<TranscriptionJob>(conn)?;
+
+        let new_chunks: Vec<NewTranscriptionChunk> = channels
+            .iter()
+            .flat_map(|channel| windows.iter().map(move |range| (*channel, range)))
+            .enumerate()
+            .map(|(index, (channel, range))| NewTranscriptionChunk {
+                job_id: job.id,
+                chunk_index: index as i32,
+                start_time: range.start,
+                end_time: range.end,
+                channel,
+            })
+            .collect();
+
+        diesel::insert_into(transcription_chunks::table)
+            .values(&new_chunks)
+            .execute(conn)?;
+
+        if let Some(from) = from {
+            record_transition(
+                conn,
+                audio.id,
+                from,
+                AudioStatus::Processing,
+                user_id,
+                Some(format!("Transcription job {}", job.id)),
+            )?;
+        }
+
+        Ok(job)
+    })
+    .map_err(|e| match e {
+        diesel::result::Error::RollbackTransaction => transition_error(e),
+        e => format!("Failed to create transcription job: {}", e),
+    })
+}
+
+/// Cut one window out of its source and transcribe it (absolute times)
+fn process_chunk(
+    source: &Path,
+    work_dir: &Path,
+    chunk: &TranscriptionChunk,
+    threads: usize,
+) -> Result<Vec<ChunkSegment>, String> {
+    let format = OutputFormat::Wav;
+    let chunk_path = work_dir.join(format!(
+        "chunk_{:05}.{}",
+        chunk.chunk_index,
+        format.extension()
+    ));
+
+    let mut args = vec![
+        "-ss".to_string(),
+        format!("{:.3}", chunk.start_time),
+        "-i".to_string(),
+        source.to_string_lossy().to_string(),
+        "-t".to_string(),
+        format!("{:.3}", chunk.end_time - chunk.start_time),
+        "-ac".to_string(),
+        "1".to_string(),
+        "-ar".to_string(),
+        WORKING_SAMPLE_RATE.to_string(),
+    ];
+    args.extend(format.codec_args());
+    args.push(chunk_path.to_string_lossy().to_string());
+
+    run_ffmpeg(&args).map_err(|e| e.to_string())?;
+    let segments = transcribe_wav(&chunk_path, threads);
+    let _ = std::fs::remove_file(&chunk_path);
+
+    Ok(segments?
+        .into_iter()
+        .map(|s| ChunkSegment {
+            start: chunk.start_time + s.start,
+            end: (chunk.start_time + s.end).min(chunk.end_time),
+            text: s.text,
+        })
+        .collect())
+}
+
+/// Persist the outcome of one chunk so an interrupted job can resume
+fn save_chunk(chunk_id: i32, result: Result<Vec<ChunkSegment>, String>) -> Result<(), String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+    let target = transcription_chunks::table.find(chunk_id);
+
+    match result {
+        Ok(segments) => {
+            diesel::update(target)
+                .set((
+                    transcription_chunks::status.eq("completed"),
+                    transcription_chunks::segments
+                        .eq(serde_json::to_string(&segments).map_err(|e| e.to_string())?),
+                    transcription_chunks::error.eq(None::<String>),
+                    transcription_chunks::completed_at.eq(chrono::Utc::now().naive_utc()),
+                ))
+                .execute(&mut conn)
+                .map_err(|e| format!("Failed to save chunk: {}", e))?;
+            Ok(())
+        }
+        Err(error) => {
+            diesel::update(target)
+                .set((
+                    transcription_chunks::status.eq("failed"),
+                    transcription_chunks::error.eq(&error),
+                ))
+                .execute(&mut conn)
+                .map_err(|e| format!("Failed to save chunk: {}", e))?;
+            Err(error)
+        }
+    }
+}
+
+/// Stitch the chunks of a job into transcript segments once all of them completed
+fn finish_job(
+    conn: &mut SqliteConnection,
+    job_id: i32,
+    user_id: Option<i32>,
+) -> Result<TranscriptionJob, String> {
+    let job = transcription_jobs::table
+        .find(job_id)
+        .first::<TranscriptionJob>(conn)
+        .map_err(|e| format!("Transcription job not found: {}", e))?;
+
+    let chunks = transcription_chunks::table
+        .filter(transcription_chunks::job_id.eq(job_id))
+        .order(transcription_chunks::chunk_index.asc())
+        .load::<TranscriptionChunk>(conn)
+        .map_err(|e| e.to_string())?;
+
+    let failed = chunks.iter().filter(|c| c.status != "completed").count();
+    if failed > 0 {
+        diesel::update(transcription_jobs::table.find(job_id))
+            .set((
+                transcription_jobs::status.eq("failed"),
+                transcription_jobs::error.eq(format!(
+                    "{} of {} chunks failed; run again to resume",
+                    failed,
+                    chunks.len()
+                )),
+            ))
+            .execute(conn)
+            .map_err(|e| e.to_string())?;
+
+        transition_audio_status(
+            conn,
+            job.audio_file_id,
+            AudioStatus::Failed,
+            user_id,
+            Some(format!(
+                "Transcription job {}: {} chunks failed",
+                job_id, failed
+            )),
+        )?;
+    } else {
+        let speakers: HashMap<i32, i32> = channel_speakers::table
+            .filter(channel_speakers::audio_file_id.eq(job.audio_file_id))
+            .select((channel_speakers::channel, channel_speakers::speaker_id))
+            .load::<(i32, i32)>(conn)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .collect();
+
+        // Each channel is its own timeline; chunks are stored channel by channel
+        let mut by_channel: BTreeMap<Option<i32>, Vec<(TimeRange, Vec<ChunkSegment>)>> =
+            BTreeMap::new();
+        for c in &chunks {
+            let segments = c
+                .segments
+                .as_deref()
+                .and_then(|raw| serde_json::from_str(raw).ok())
+                .unwrap_or_default();
+            by_channel.entry(c.channel).or_default().push((
+                TimeRange {
+                    start: c.start_time,
+                    end: c.end_time,
+                },
+                segments,
+            ));
+        }
+
+        let mut new_segments: Vec<NewTranscriptSegment> = by_channel
+            .iter()
+            .flat_map(|(channel, output)| {
+                let speaker_id = channel.and_then(|channel| speakers.get(&channel).copied());
+                stitch(output)
+                    .into_iter()
+                    .map(move |s| NewTranscriptSegment {
+                        audio_file_id: job.audio_file_id,
+                        speaker_id,
+                        start_time: s.start,
+                        end_time: s.end,
+                        text: s.text,
+                        words: None,
+                        channel: *channel,
+                    })
+            })
+            .collect();
+        new_segments.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+
+        conn.transaction::<(), diesel::result::Error, _>(|conn| {
+            // The new transcript replaces the previous one, which stays restorable
+            let replaced = diesel::update(
+                transcript_segments::table
+                    .filter(transcript_segments::audio_file_id.eq(job.audio_file_id))
+                    .filter(transcript_segments::is_deleted.eq(0)),
+            )
+            .set((
+                transcript_segments::is_deleted.eq(1),
+                transcript_segments::deleted_at.eq(chrono::Utc::now().naive_utc()),
+            ))
+            .execute(conn)?;
+
+            diesel::insert_into(transcript_segments::table)
+                .values(&new_segments)
+                .execute(conn)?;
+
+            diesel::update(transcription_jobs::table.find(job_id))
+                .set((
+                    transcription_jobs::status.eq("completed"),
+                    transcription_jobs::segment_count.eq(new_segments.len() as i32),
+                    transcription_jobs::completed_at.eq(chrono::Utc::now().naive_utc()),
+                ))
+                .execute(conn)?;
+
+            log_activity(
+                conn,
+                user_id,
+                "create",
+                "audio_file",
+                Some(job.audio_file_id),
+                Some(
+                    serde_json::json!({
+                        "operation": "chunked_transcription",
+                        "job_id": job_id,
+                        "chunks": chunks.len(),
+                        "segments": new_segments.len(),
+                        "replaced_segments": replaced,
+                    })
+                    .to_string(),
+                ),
+            )
+        })
+        .map_err(|e| format!("Failed to save transcript: {}", e))?;
+
+        transition_audio_status(
+            conn,
+            job.audio_file_id,
+            AudioStatus::Transcribed,
+            user_id,
+            Some(format!("Transcription job {}", job_id)),
+        )?;
+    }
+
+    transcription_jobs::table
+        .find(job_id)
+        .first::<TranscriptionJob>(conn)
+        .map_err(|e| e.to_string())
+}
+
+/// Get the transcription jobs of an audio file (newest first)
+#[tauri::command]
+pub fn get_transcription_jobs(
+    audio_file_id: i32,
+    user_id: Option<i32>,
+) -> Result<Vec<TranscriptionJob>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Reader)?;
+
+    transcription_jobs::table
+        .filter(transcription_jobs::audio_file_id.eq(audio_file_id))
+        .order(transcription_jobs::id.desc())
+        .load::<TranscriptionJob>(&mut conn)
+        .map_err(|e| e.to_string())
+}
+
+/// Get the chunks of a transcription job in order
+#[tauri::command]
+pub fn get_transcription_chunks(
+    job_id: i32,
+    user_id: Option<i32>,
+) -> Result<Vec<TranscriptionChunk>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    let audio_file_id = transcription_jobs::table
+        .find(job_id)
+        .select(transcription_jobs::audio_file_id)
+        .first::<i32>(&mut conn)
+        .map_err(|e| format!("Transcription job not found: {}", e))?;
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Reader)?;
+
+    transcription_chunks::table
+        .filter(transcription_chunks::job_id.eq(job_id))
+        .order(transcription_chunks::chunk_index.asc())
+        .load::<TranscriptionChunk>(&mut conn)
+        .map_err(|e| e.to_string())
+}
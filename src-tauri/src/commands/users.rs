@@ -1,5 +1,6 @@
 //! User CRUD commands
-use crate::models::{NewUser, User};
+use crate::models::pagination::invalid_sort;
+use crate::models::{ListQuery, NewUser, Page, SortDirection, User};
 use crate::schema::users;
 use crate::services::get_pool;
 use diesel::prelude::*;
@@ -23,21 +24,68 @@ pub struct UpdateUserInput {
     pub avatar: Option<String>,
 }
 
-/// Get all users
+/// Users matching the query's search (name, email, username) and date range
+fn user_query(query: &ListQuery) -> users::BoxedQuery<'static, diesel::sqlite::Sqlite> {
+    let mut boxed = users::table.into_boxed();
+    if let Some(pattern) = query.search_pattern() {
+        boxed = boxed.filter(
+            users::name
+                .like(pattern.clone())
+                .escape('\\')
+                .or(users::email.like(pattern.clone()).escape('\\'))
+                .or(users::username.like(pattern).escape('\\')),
+        );
+    }
+    if let Some(from) = query.date_from {
+        boxed = boxed.filter(users::created_at.ge(from));
+    }
+    if let Some(to) = query.date_to {
+        boxed = boxed.filter(users::created_at.le(to));
+    }
+    boxed
+}
+
+/// Get a page of users (by name by default). Sortable by name, email, role or created_at.
 #[tauri::command]
-pub fn get_users() -> Result<Vec<User>, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+pub fn get_users(query: Option<ListQuery>) -> Result<Page<User>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    let query = query.unwrap_or_default();
+    let page_size = query.page_size(50);
+    let offset = query.offset(page_size);
+
+    let total = user_query(&query)
+        .count()
+        .get_result::<i64>(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+    let desc = query.descending(SortDirection::Asc);
+    let sorted = match query.sort_field("name") {
+        "name" if desc => user_query(&query).order(users::name.desc()),
+        "name" => user_query(&query).order(users::name.asc()),
+        "email" if desc => user_query(&query).order(users::email.desc()),
+        "email" => user_query(&query).order(users::email.asc()),
+        "role" if desc => user_query(&query).order(users::role.desc()),
+        "role" => user_query(&query).order(users::role.asc()),
+        "created_at" if desc => user_query(&query).order(users::created_at.desc()),
+        "created_at" => user_query(&query).order(users::created_at.asc()),
+        field => return Err(invalid_sort(field)),
+    };
 
-    users::table
-        .order(users::name.asc())
+    let items = sorted
+        .then_order_by(users::id.asc())
+        .limit(page_size)
+        .offset(offset)
         .load::<User>(&mut conn)
-        .map_err(|e| e.to_string())
+        .map_err(|e| e.to_string())?;
+
+    Ok(Page::new(items, total, offset, page_size))
 }
 
 /// Get a single user by ID
 #[tauri::command]
 pub fn get_user(id: i32) -> Result<User, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
     users::table
         .find(id)
@@ -50,7 +98,7 @@ pub fn get_user(id: i32) -> Result<User, String> {
 pub fn create_user(input: CreateUserInput) -> Result<User, String> {
     use crate::services::auth_service::hash_password;
 
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
     let password_hash = hash_password(&input.password).map_err(|e| e.to_string())?;
 
@@ -77,7 +125,7 @@ pub fn create_user(input: CreateUserInput) -> Result<User, String> {
 /// Update a user
 #[tauri::command]
 pub fn update_user(id: i32, input: UpdateUserInput) -> Result<User, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
     let target = users::table.find(id);
 
     if let Some(name) = input.name {
@@ -114,7 +162,7 @@ pub fn update_user(id: i32, input: UpdateUserInput) -> Result<User, String> {
 /// Delete a user
 #[tauri::command]
 pub fn delete_user(id: i32) -> Result<(), String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
     diesel::delete(users::table.find(id))
         .execute(&mut conn)
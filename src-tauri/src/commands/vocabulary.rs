@@ -30,7 +30,7 @@ pub struct CreateReplacementWordInput {
 /// Get all alert words
 #[tauri::command]
 pub fn get_alert_words() -> Result<Vec<AlertWord>, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
     alert_words::table
         .order(alert_words::keyword.asc())
@@ -41,7 +41,7 @@ pub fn get_alert_words() -> Result<Vec<AlertWord>, String> {
 /// Get alert words by category
 #[tauri::command]
 pub fn get_alert_words_by_category(category: String) -> Result<Vec<AlertWord>, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
     alert_words::table
         .filter(alert_words::category.eq(category))
@@ -53,7 +53,7 @@ pub fn get_alert_words_by_category(category: String) -> Result<Vec<AlertWord>, S
 /// Create a new alert word
 #[tauri::command]
 pub fn create_alert_word(input: CreateAlertWordInput) -> Result<AlertWord, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
     let new_word = NewAlertWord {
         keyword: input.keyword,
@@ -75,7 +75,7 @@ pub fn create_alert_word(input: CreateAlertWordInput) -> Result<AlertWord, Strin
 /// Delete an alert word
 #[tauri::command]
 pub fn delete_alert_word(id: i32) -> Result<(), String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
     diesel::delete(alert_words::table.find(id))
         .execute(&mut conn)
@@ -91,7 +91,7 @@ pub fn delete_alert_word(id: i32) -> Result<(), String> {
 /// Get all replacement words
 #[tauri::command]
 pub fn get_replacement_words() -> Result<Vec<ReplacementWord>, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
     replacement_words::table
         .order(replacement_words::original.asc())
@@ -104,7 +104,7 @@ pub fn get_replacement_words() -> Result<Vec<ReplacementWord>, String> {
 pub fn create_replacement_word(
     input: CreateReplacementWordInput,
 ) -> Result<ReplacementWord, String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
     let new_word = NewReplacementWord {
         original: input.original,
@@ -126,7 +126,7 @@ pub fn create_replacement_word(
 /// Delete a replacement word
 #[tauri::command]
 pub fn delete_replacement_word(id: i32) -> Result<(), String> {
-    let mut conn = get_pool().get().map_err(|e| e.to_string())?;
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
 
     diesel::delete(replacement_words::table.find(id))
         .execute(&mut conn)
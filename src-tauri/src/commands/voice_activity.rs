@@ -0,0 +1,115 @@
+//! Voice activity detection commands (speech regions and silence report)
+use crate::models::vad_analysis::encode_ranges;
+use crate::models::{AudioFile, NewVadAnalysis, TimeRange, VadAnalysis};
+use crate::schema::{audio_files, vad_analyses};
+use crate::services::access_service::{require_audio_role, CaseRole};
+use crate::services::ffmpeg::stream_pcm;
+use crate::services::get_pool;
+use crate::services::vad_service::{
+    transcription_ranges, SpeechDetector, VadParams, VAD_SAMPLE_RATE,
+};
+use crate::services::working_copy_service::analysis_path;
+use diesel::prelude::*;
+
+/// Run voice activity detection on an audio file and store the result
+/// (replaces any previous analysis of the file)
+#[tauri::command]
+pub async fn analyze_voice_activity(
+    audio_file_id: i32,
+    params: Option<VadParams>,
+    user_id: Option<i32>,
+) -> Result<VadAnalysis, String> {
+    // Streams the whole recording through ffmpeg
+    tauri::async_runtime::spawn_blocking(move || run_voice_activity(audio_file_id, params, user_id))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+fn run_voice_activity(
+    audio_file_id: i32,
+    params: Option<VadParams>,
+    user_id: Option<i32>,
+) -> Result<VadAnalysis, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+    let params = params.unwrap_or_default();
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Editor)?;
+
+    let audio = audio_files::table
+        .find(audio_file_id)
+        .first::<AudioFile>(&mut conn)
+        .map_err(|e| format!("Audio file not found: {}", e))?;
+
+    let mut detector = SpeechDetector::new(VAD_SAMPLE_RATE, &params);
+    stream_pcm(
+        &analysis_path(&mut conn, &audio),
+        VAD_SAMPLE_RATE,
+        |samples| detector.push(samples),
+    )
+    .map_err(|e| e.to_string())?;
+    let output = detector.finish();
+
+    let new_analysis = NewVadAnalysis {
+        audio_file_id,
+        duration: output.duration,
+        speech_duration: output.speech_duration,
+        speech_ratio: if output.duration > 0.0 {
+            output.speech_duration / output.duration
+        } else {
+            0.0
+        },
+        regions: encode_ranges(&output.regions),
+        long_gaps: encode_ranges(&output.long_gaps),
+        params: serde_json::to_string(&params).map_err(|e| e.to_string())?,
+    };
+
+    conn.transaction::<VadAnalysis, diesel::result::Error, _>(|conn| {
+        diesel::delete(vad_analyses::table.filter(vad_analyses::audio_file_id.eq(audio_file_id)))
+            .execute(conn)?;
+
+        diesel::insert_into(vad_analyses::table)
+            .values(&new_analysis)
+            .execute(conn)?;
+
+        vad_analyses::table
+            .filter(vad_analyses::audio_file_id.eq(audio_file_id))
+            .first::<VadAnalysis>(conn)
+    })
+    .map_err(|e| format!("Failed to save voice activity: {}", e))
+}
+
+/// Get the stored voice activity analysis of an audio file, if any
+#[tauri::command]
+pub fn get_voice_activity(
+    audio_file_id: i32,
+    user_id: Option<i32>,
+) -> Result<Option<VadAnalysis>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Reader)?;
+
+    vad_analyses::table
+        .filter(vad_analyses::audio_file_id.eq(audio_file_id))
+        .first::<VadAnalysis>(&mut conn)
+        .optional()
+        .map_err(|e| e.to_string())
+}
+
+/// Get the ranges a transcriber should process so silence is skipped
+#[tauri::command]
+pub fn get_transcription_regions(
+    audio_file_id: i32,
+    padding: Option<f32>,
+    max_length: Option<f32>,
+    user_id: Option<i32>,
+) -> Result<Vec<TimeRange>, String> {
+    let analysis = get_voice_activity(audio_file_id, user_id)?
+        .ok_or("Run voice activity detection on this file first")?;
+
+    Ok(transcription_ranges(
+        &analysis.speech_regions(),
+        analysis.duration,
+        padding.unwrap_or(0.3),
+        max_length.unwrap_or(30.0).max(1.0),
+    ))
+}
@@ -0,0 +1,164 @@
+//! Watch folder commands (drop folders ingested automatically into a case)
+use crate::models::{NewWatchFolder, WatchFolder};
+use crate::schema::watch_folders;
+use crate::services::access_service::{accessible_case_ids, require_case_role, CaseRole};
+use crate::services::get_pool;
+use crate::services::watch_service::WATCH_POST_ACTIONS;
+use diesel::prelude::*;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateWatchFolderInput {
+    pub path: String,
+    pub case_id: i32,
+    pub file_pattern: Option<String>,
+    pub post_action: Option<String>,
+    pub move_to: Option<String>,
+    pub user_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateWatchFolderInput {
+    pub case_id: Option<i32>,
+    pub file_pattern: Option<String>,
+    pub post_action: Option<String>,
+    pub move_to: Option<String>,
+    pub is_active: Option<bool>,
+    pub user_id: Option<i32>,
+}
+
+fn validate_post_action(post_action: &str) -> Result<(), String> {
+    if WATCH_POST_ACTIONS.contains(&post_action) {
+        Ok(())
+    } else {
+        Err(format!("Invalid post action: {}", post_action))
+    }
+}
+
+/// A watch folder whose case the caller may edit
+fn find_editable_folder(
+    conn: &mut SqliteConnection,
+    id: i32,
+    user_id: Option<i32>,
+) -> Result<WatchFolder, String> {
+    let folder = watch_folders::table
+        .find(id)
+        .first::<WatchFolder>(conn)
+        .map_err(|e| format!("Watch folder not found: {}", e))?;
+    require_case_role(conn, user_id, folder.case_id, CaseRole::Editor)?;
+    Ok(folder)
+}
+
+/// Get the watch folders of the cases the caller can see
+#[tauri::command]
+pub fn get_watch_folders(user_id: Option<i32>) -> Result<Vec<WatchFolder>, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    let mut query = watch_folders::table.into_boxed();
+    if let Some(ids) = accessible_case_ids(&mut conn, user_id)? {
+        query = query.filter(watch_folders::case_id.eq_any(ids));
+    }
+
+    query
+        .order(watch_folders::created_at.desc())
+        .load::<WatchFolder>(&mut conn)
+        .map_err(|e| e.to_string())
+}
+
+/// Register a folder to be watched for new audio files
+#[tauri::command]
+pub fn create_watch_folder(input: CreateWatchFolderInput) -> Result<WatchFolder, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    let post_action = input.post_action.unwrap_or_else(|| "leave".to_string());
+    validate_post_action(&post_action)?;
+    if !std::path::Path::new(&input.path).is_dir() {
+        return Err(format!("Folder does not exist: {}", input.path));
+    }
+    require_case_role(&mut conn, input.user_id, input.case_id, CaseRole::Editor)?;
+
+    let new_folder = NewWatchFolder {
+        path: input.path,
+        case_id: input.case_id,
+        file_pattern: input.file_pattern.unwrap_or_else(|| "*".to_string()),
+        post_action,
+        move_to: input.move_to.filter(|dir| !dir.trim().is_empty()),
+        created_by: input.user_id,
+    };
+
+    diesel::insert_into(watch_folders::table)
+        .values(&new_folder)
+        .execute(&mut conn)
+        .map_err(|e| format!("Failed to create watch folder: {}", e))?;
+
+    watch_folders::table
+        .order(watch_folders::id.desc())
+        .first::<WatchFolder>(&mut conn)
+        .map_err(|e| e.to_string())
+}
+
+/// Update a watch folder (an empty `move_to` resets it to the default subfolder)
+#[tauri::command]
+pub fn update_watch_folder(id: i32, input: UpdateWatchFolderInput) -> Result<WatchFolder, String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    // Moving a folder to another case needs edit access on both cases
+    find_editable_folder(&mut conn, id, input.user_id)?;
+    let target = watch_folders::table.find(id);
+
+    if let Some(case_id) = input.case_id {
+        require_case_role(&mut conn, input.user_id, case_id, CaseRole::Editor)?;
+        diesel::update(target)
+            .set(watch_folders::case_id.eq(case_id))
+            .execute(&mut conn)
+            .map_err(|e| e.to_string())?;
+    }
+
+    if let Some(file_pattern) = input.file_pattern {
+        diesel::update(target)
+            .set(watch_folders::file_pattern.eq(file_pattern))
+            .execute(&mut conn)
+            .map_err(|e| e.to_string())?;
+    }
+
+    if let Some(post_action) = input.post_action {
+        validate_post_action(&post_action)?;
+        diesel::update(target)
+            .set(watch_folders::post_action.eq(post_action))
+            .execute(&mut conn)
+            .map_err(|e| e.to_string())?;
+    }
+
+    if let Some(move_to) = input.move_to {
+        diesel::update(target)
+            .set(watch_folders::move_to.eq(Some(move_to).filter(|dir| !dir.trim().is_empty())))
+            .execute(&mut conn)
+            .map_err(|e| e.to_string())?;
+    }
+
+    if let Some(is_active) = input.is_active {
+        diesel::update(target)
+            .set(watch_folders::is_active.eq(if is_active { 1 } else { 0 }))
+            .execute(&mut conn)
+            .map_err(|e| e.to_string())?;
+    }
+
+    watch_folders::table
+        .find(id)
+        .first::<WatchFolder>(&mut conn)
+        .map_err(|e| format!("Watch folder not found: {}", e))
+}
+
+/// Stop watching a folder (files already ingested are kept)
+#[tauri::command]
+pub fn delete_watch_folder(id: i32, user_id: Option<i32>) -> Result<(), String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    find_editable_folder(&mut conn, id, user_id)?;
+
+    diesel::delete(watch_folders::table.find(id))
+        .execute(&mut conn)
+        .map_err(|e| format!("Failed to delete watch folder: {}", e))?;
+
+    Ok(())
+}
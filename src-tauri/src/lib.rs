@@ -8,8 +8,74 @@ mod models;
 mod schema;
 mod services;
 
+use services::access_service::{require_audio_role, CaseRole};
+use services::encryption_service;
+use tauri::http::{header, Request, Response, StatusCode};
 use tauri::Manager;
 
+/// Stream playback audio to the webview at
+/// `evidence://localhost/<audio_file_id>?user_id=<user_id>`, honouring Range requests.
+/// The user needs read access to the case; sealed files are decrypted chunk by chunk.
+fn serve_evidence(request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let error = |status: StatusCode, message: String| {
+        Response::builder()
+            .status(status)
+            .body(message.into_bytes())
+            .unwrap()
+    };
+
+    if encryption_service::is_enabled() && !encryption_service::is_unlocked() {
+        return error(
+            StatusCode::FORBIDDEN,
+            "Encrypted store is locked".to_string(),
+        );
+    }
+    let Ok(audio_file_id) = request.uri().path().trim_start_matches('/').parse::<i32>() else {
+        return error(StatusCode::BAD_REQUEST, "Invalid audio file id".to_string());
+    };
+    let user_id = request
+        .uri()
+        .query()
+        .and_then(|query| {
+            query
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("user_id="))
+        })
+        .and_then(|value| value.parse::<i32>().ok());
+    let range = request
+        .headers()
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok());
+
+    let mut conn = match services::get_pool().and_then(|pool| pool.get().map_err(|e| e.to_string()))
+    {
+        Ok(conn) => conn,
+        Err(e) => return error(StatusCode::SERVICE_UNAVAILABLE, e),
+    };
+    if let Err(e) = require_audio_role(&mut conn, user_id, audio_file_id, CaseRole::Reader) {
+        return error(StatusCode::FORBIDDEN, e);
+    }
+
+    let chunk =
+        services::working_copy_service::read_playback_range(&mut conn, audio_file_id, range);
+    match chunk {
+        Ok((chunk, content_type)) => {
+            let mut response = Response::builder()
+                .header(header::CONTENT_TYPE, content_type)
+                .header(header::ACCEPT_RANGES, "bytes");
+            if chunk.partial {
+                let end = (chunk.start + chunk.data.len() as u64).saturating_sub(1);
+                response = response.status(StatusCode::PARTIAL_CONTENT).header(
+                    header::CONTENT_RANGE,
+                    format!("bytes {}-{}/{}", chunk.start, end, chunk.total),
+                );
+            }
+            response.body(chunk.data).unwrap()
+        }
+        Err(e) => error(StatusCode::NOT_FOUND, e),
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -30,15 +96,29 @@ pub fn run() {
 
             std::fs::create_dir_all(&app_dir).expect("Failed to create app data dir");
 
-            services::init_db(&app_dir).expect("Failed to initialize database");
+            services::init_storage(&app_dir).expect("Failed to initialize storage");
+            encryption_service::clear_plaintext_copies();
+
+            if encryption_service::is_enabled() {
+                // The database opens once the master passphrase is entered (unlock_encryption)
+                println!("Encrypted store locked, waiting for the master passphrase");
+            } else {
+                services::init_db(&app_dir).expect("Failed to initialize database");
+
+                // Manage DbPool in Tauri state for commands that use State<DbPool>
+                let pool = services::get_pool()?.clone();
+                app.manage(pool);
 
-            // Manage DbPool in Tauri state for commands that use State<DbPool>
-            let pool = services::get_pool().clone();
-            app.manage(pool);
+                // Poll watch folders for new audio files in the background
+                services::start_watch_folders();
+                // Take scheduled database snapshots in the background
+                services::start_snapshots();
+            }
 
             println!("SPEXOR Client started successfully");
             Ok(())
         })
+        .register_uri_scheme_protocol("evidence", |_ctx, request| serve_evidence(&request))
         .invoke_handler(tauri::generate_handler![
             // Greet (demo)
             commands::greet::greet,
@@ -54,18 +134,110 @@ pub fn run() {
             commands::create_case,
             commands::update_case,
             commands::delete_case,
+            commands::get_case_progress,
+            commands::get_case_timeline,
+            commands::filter_cases,
+            commands::get_case_assignees,
+            commands::set_case_assignees,
+            // Case access
+            commands::get_case_members,
+            commands::set_case_member,
+            commands::remove_case_member,
+            commands::get_case_access_log,
+            commands::get_user_case_access,
             // Audio files
             commands::get_audio_files,
             commands::get_audio_file,
             commands::upload_audio,
             commands::update_audio_file,
             commands::delete_audio_file,
+            commands::set_audio_status,
+            commands::get_audio_status_history,
+            commands::get_audio_derivatives,
+            commands::normalize_audio,
+            commands::get_playback_path,
+            commands::import_audio_folder,
+            // Recording time
+            commands::set_recorded_at,
+            commands::detect_recorded_at,
+            commands::get_segment_absolute_time,
+            commands::get_recording_time_patterns,
+            commands::create_recording_time_pattern,
+            commands::delete_recording_time_pattern,
+            // Watch folders
+            commands::get_watch_folders,
+            commands::create_watch_folder,
+            commands::update_watch_folder,
+            commands::delete_watch_folder,
+            // Clips
+            commands::extract_audio_clip,
+            // Redactions
+            commands::get_redactions,
+            commands::create_redaction,
+            commands::delete_redaction,
+            commands::render_redacted_audio,
+            commands::get_redacted_transcript,
+            // Voice activity
+            commands::analyze_voice_activity,
+            commands::get_voice_activity,
+            commands::get_transcription_regions,
+            // Enhancement
+            commands::get_enhancement_preset,
+            commands::save_enhancement_preset,
+            commands::render_enhanced_audio,
+            // Spectrogram
+            commands::render_spectrogram,
+            commands::clear_spectrogram_cache,
+            // Channels
+            commands::detect_audio_channels,
+            commands::split_audio_channels,
+            commands::get_channel_speakers,
+            commands::assign_channel_speaker,
             // Transcript segments
             commands::get_transcript_segments,
             commands::create_transcript_segment,
             commands::update_transcript_segment,
             commands::delete_transcript_segment,
             commands::bulk_create_segments,
+            commands::get_low_confidence_words,
+            commands::get_deleted_segments,
+            commands::restore_segments,
+            commands::purge_deleted_segments,
+            commands::export_transcript,
+            // Transcription
+            commands::transcribe_audio_chunked,
+            commands::get_transcription_jobs,
+            commands::get_transcription_chunks,
+            // Reviews
+            commands::get_reviews,
+            commands::get_assigned_reviews,
+            commands::assign_reviewer,
+            commands::get_review_comments,
+            commands::add_review_comment,
+            commands::approve_transcript,
+            commands::reject_transcript,
+            commands::reopen_transcript,
+            // Annotations
+            commands::get_annotations,
+            commands::create_annotation,
+            commands::update_annotation,
+            commands::delete_annotation,
+            // Bookmarks
+            commands::get_bookmarks,
+            commands::get_case_bookmarks,
+            commands::create_bookmark,
+            commands::update_bookmark,
+            commands::delete_bookmark,
+            // Case reports
+            commands::get_report_templates,
+            commands::create_report_template,
+            commands::update_report_template,
+            commands::delete_report_template,
+            commands::generate_case_report,
+            // Case bundles
+            commands::get_bundle_public_key,
+            commands::export_case_bundle,
+            commands::import_case_bundle,
             // Speakers
             commands::get_speakers,
             commands::get_speaker,
@@ -101,6 +273,19 @@ pub fn run() {
             commands::get_activity_logs_by_action,
             commands::create_activity_log,
             commands::cleanup_old_logs,
+            // Dashboard
+            commands::get_dashboard_stats,
+            // Encryption
+            commands::get_encryption_status,
+            commands::unlock_encryption,
+            commands::enable_encryption,
+            commands::rotate_encryption_keys,
+            // Backups
+            commands::backup_database,
+            commands::list_database_snapshots,
+            commands::get_backup_schedule,
+            commands::update_backup_schedule,
+            commands::restore_database,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
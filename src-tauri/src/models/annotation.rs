@@ -0,0 +1,44 @@
+//! Annotation model - analyst notes on a transcript segment or a time range of an audio file
+use crate::models::tags::{decode_tags, serialize_tags};
+use crate::schema::annotations;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize)]
+#[diesel(table_name = annotations)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct Annotation {
+    pub id: i32,
+    pub audio_file_id: i32,
+    /// Anchoring segment; None for a free time range (or once the segment is purged)
+    pub segment_id: Option<i32>,
+    pub start_time: f32,
+    pub end_time: f32,
+    pub author_id: Option<i32>,
+    pub body: String,
+    #[serde(serialize_with = "serialize_tags")]
+    pub tags: String,
+    pub color: Option<String>,
+    pub is_resolved: i32,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+impl Annotation {
+    pub fn tag_list(&self) -> Vec<String> {
+        decode_tags(&self.tags)
+    }
+}
+
+#[derive(Debug, Insertable, Deserialize)]
+#[diesel(table_name = annotations)]
+pub struct NewAnnotation {
+    pub audio_file_id: i32,
+    pub segment_id: Option<i32>,
+    pub start_time: f32,
+    pub end_time: f32,
+    pub author_id: Option<i32>,
+    pub body: String,
+    pub tags: String,
+    pub color: Option<String>,
+}
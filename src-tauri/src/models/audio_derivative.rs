@@ -0,0 +1,29 @@
+//! Audio derivative model - files generated from an audio file (redacted, enhanced, ...)
+use crate::schema::audio_derivatives;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize)]
+#[diesel(table_name = audio_derivatives)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct AudioDerivative {
+    pub id: i32,
+    pub audio_file_id: i32,
+    pub kind: String,
+    pub file_path: String,
+    pub sha256: String,
+    pub params: Option<String>,
+    pub created_by: Option<i32>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, Deserialize)]
+#[diesel(table_name = audio_derivatives)]
+pub struct NewAudioDerivative {
+    pub audio_file_id: i32,
+    pub kind: String,
+    pub file_path: String,
+    pub sha256: String,
+    pub params: Option<String>,
+    pub created_by: Option<i32>,
+}
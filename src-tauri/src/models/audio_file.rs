@@ -14,6 +14,14 @@ pub struct AudioFile {
     pub duration: f32,
     pub status: String,
     pub created_at: chrono::NaiveDateTime,
+    pub channels: i32,
+    pub sha256: Option<String>,
+    /// Real-world start of the recording, in UTC
+    pub recorded_at: Option<chrono::NaiveDateTime>,
+    /// UTC offset of the place it was recorded, e.g. "+07:00"
+    pub recorded_tz: Option<String>,
+    /// "manual", "metadata" or "filename"
+    pub recorded_at_source: Option<String>,
 }
 
 #[derive(Debug, Insertable, Deserialize)]
@@ -24,6 +32,8 @@ pub struct NewAudioFile {
     pub file_path: String,
     pub duration: f32,
     pub status: String,
+    pub channels: i32,
+    pub sha256: Option<String>,
 }
 
 #[derive(Debug, AsChangeset, Deserialize, Default)]
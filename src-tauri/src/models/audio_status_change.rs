@@ -0,0 +1,27 @@
+//! Audio status change model - one transition of an audio file's lifecycle
+use crate::schema::audio_status_history;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize)]
+#[diesel(table_name = audio_status_history)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct AudioStatusChange {
+    pub id: i32,
+    pub audio_file_id: i32,
+    pub from_status: String,
+    pub to_status: String,
+    pub reason: Option<String>,
+    pub changed_by: Option<i32>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, Deserialize)]
+#[diesel(table_name = audio_status_history)]
+pub struct NewAudioStatusChange {
+    pub audio_file_id: i32,
+    pub from_status: String,
+    pub to_status: String,
+    pub reason: Option<String>,
+    pub changed_by: Option<i32>,
+}
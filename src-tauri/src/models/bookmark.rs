@@ -0,0 +1,39 @@
+//! Bookmark model - important moments of a recording
+use crate::schema::{bookmark_segments, bookmarks};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize)]
+#[diesel(table_name = bookmarks)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct Bookmark {
+    pub id: i32,
+    pub audio_file_id: i32,
+    pub start_time: f32,
+    pub end_time: f32,
+    pub title: String,
+    pub description: Option<String>,
+    /// "low", "medium", "high" or "critical"
+    pub severity: String,
+    pub created_by: Option<i32>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, Deserialize)]
+#[diesel(table_name = bookmarks)]
+pub struct NewBookmark {
+    pub audio_file_id: i32,
+    pub start_time: f32,
+    pub end_time: f32,
+    pub title: String,
+    pub description: Option<String>,
+    pub severity: String,
+    pub created_by: Option<i32>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = bookmark_segments)]
+pub struct NewBookmarkSegment {
+    pub bookmark_id: i32,
+    pub segment_id: i32,
+}
@@ -1,5 +1,6 @@
 //! Case model - represents investigation cases
-use crate::schema::cases;
+use crate::models::tags::{decode_tags, serialize_tags};
+use crate::schema::{case_assignments, cases};
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 
@@ -13,7 +14,21 @@ pub struct Case {
     pub title: String,
     pub description: Option<String>,
     pub created_at: chrono::NaiveDateTime,
+    /// Maintained by database triggers on changes to the case and its children
     pub updated_at: chrono::NaiveDateTime,
+    /// "open", "in-progress", "closed" or "archived"
+    pub status: String,
+    /// "low", "medium", "high" or "urgent"
+    pub priority: String,
+    #[serde(serialize_with = "serialize_tags")]
+    pub tags: String,
+    pub due_date: Option<chrono::NaiveDate>,
+}
+
+impl Case {
+    pub fn tag_list(&self) -> Vec<String> {
+        decode_tags(&self.tags)
+    }
 }
 
 /// Insertable struct for creating new cases
@@ -23,6 +38,10 @@ pub struct NewCase {
     pub code: String,
     pub title: String,
     pub description: Option<String>,
+    pub status: String,
+    pub priority: String,
+    pub tags: String,
+    pub due_date: Option<chrono::NaiveDate>,
 }
 
 /// Changeset struct for updating cases
@@ -34,3 +53,22 @@ pub struct UpdateCase {
     pub description: Option<String>,
     pub updated_at: Option<chrono::NaiveDateTime>,
 }
+
+/// A user assigned to work on a case
+#[derive(Debug, Clone, Queryable, Selectable, Serialize)]
+#[diesel(table_name = case_assignments)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct CaseAssignment {
+    pub case_id: i32,
+    pub user_id: i32,
+    pub assigned_by: Option<i32>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = case_assignments)]
+pub struct NewCaseAssignment {
+    pub case_id: i32,
+    pub user_id: i32,
+    pub assigned_by: Option<i32>,
+}
@@ -0,0 +1,25 @@
+//! Case member model - per-case access control
+use crate::schema::case_members;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize)]
+#[diesel(table_name = case_members)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct CaseMember {
+    pub case_id: i32,
+    pub user_id: i32,
+    /// "owner", "editor" or "reader"
+    pub role: String,
+    pub added_by: Option<i32>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, Deserialize)]
+#[diesel(table_name = case_members)]
+pub struct NewCaseMember {
+    pub case_id: i32,
+    pub user_id: i32,
+    pub role: String,
+    pub added_by: Option<i32>,
+}
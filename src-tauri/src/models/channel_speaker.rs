@@ -0,0 +1,23 @@
+//! Channel speaker model - maps a channel of a multi-channel recording to a speaker
+use crate::schema::channel_speakers;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize)]
+#[diesel(table_name = channel_speakers)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct ChannelSpeaker {
+    pub id: i32,
+    pub audio_file_id: i32,
+    pub channel: i32,
+    pub speaker_id: i32,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, Deserialize)]
+#[diesel(table_name = channel_speakers)]
+pub struct NewChannelSpeaker {
+    pub audio_file_id: i32,
+    pub channel: i32,
+    pub speaker_id: i32,
+}
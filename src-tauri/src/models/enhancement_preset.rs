@@ -0,0 +1,23 @@
+//! Enhancement preset model - audio enhancement parameters stored per audio file
+use crate::schema::enhancement_presets;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize)]
+#[diesel(table_name = enhancement_presets)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct EnhancementPreset {
+    pub id: i32,
+    pub audio_file_id: i32,
+    pub params: String,
+    pub updated_by: Option<i32>,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, Deserialize)]
+#[diesel(table_name = enhancement_presets)]
+pub struct NewEnhancementPreset {
+    pub audio_file_id: i32,
+    pub params: String,
+    pub updated_by: Option<i32>,
+}
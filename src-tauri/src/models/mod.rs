@@ -10,26 +10,61 @@
 
 pub mod activity_log;
 pub mod alert_word;
+pub mod annotation;
+pub mod audio_derivative;
 pub mod audio_file;
+pub mod audio_status_change;
+pub mod bookmark;
 pub mod case;
+pub mod case_member;
+pub mod channel_speaker;
+pub mod enhancement_preset;
 pub mod notification;
+pub mod pagination;
+pub mod recording_time_pattern;
+pub mod redaction;
 pub mod replacement_word;
+pub mod report_template;
+pub mod review;
 pub mod speaker;
+pub mod tags;
 pub mod transcript_segment;
+pub mod transcription_job;
 pub mod user;
+pub mod vad_analysis;
 pub mod voice_sample;
+pub mod watch_folder;
 
 // Re-exports for convenience
 pub use activity_log::{ActivityLog, NewActivityLog};
 pub use alert_word::{AlertWord, NewAlertWord, UpdateAlertWord};
+pub use annotation::{Annotation, NewAnnotation};
+pub use audio_derivative::{AudioDerivative, NewAudioDerivative};
 pub use audio_file::{AudioFile, NewAudioFile, UpdateAudioFile};
-pub use case::{Case, NewCase, UpdateCase};
+pub use audio_status_change::{AudioStatusChange, NewAudioStatusChange};
+pub use bookmark::{Bookmark, NewBookmark, NewBookmarkSegment};
+pub use case::{Case, CaseAssignment, NewCase, NewCaseAssignment, UpdateCase};
+pub use case_member::{CaseMember, NewCaseMember};
+pub use channel_speaker::{ChannelSpeaker, NewChannelSpeaker};
+pub use enhancement_preset::{EnhancementPreset, NewEnhancementPreset};
 pub use notification::{NewNotification, Notification, UpdateNotification};
+pub use pagination::{ListQuery, Page, SortDirection};
+pub use recording_time_pattern::{NewRecordingTimePattern, RecordingTimePattern};
+pub use redaction::{NewRedaction, Redaction};
 pub use replacement_word::{NewReplacementWord, ReplacementWord, UpdateReplacementWord};
+pub use report_template::{NewReportTemplate, ReportTemplate};
+pub use review::{NewReviewAssignment, NewReviewComment, ReviewAssignment, ReviewComment};
 pub use speaker::{NewSpeaker, Speaker, UpdateSpeaker};
-pub use transcript_segment::{NewTranscriptSegment, TranscriptSegment, UpdateTranscriptSegment};
+pub use transcript_segment::{
+    NewTranscriptSegment, TranscriptSegment, UpdateTranscriptSegment, WordTiming,
+};
+pub use transcription_job::{
+    NewTranscriptionChunk, NewTranscriptionJob, TranscriptionChunk, TranscriptionJob,
+};
 pub use user::{NewUser, UpdateUser, User};
+pub use vad_analysis::{NewVadAnalysis, TimeRange, VadAnalysis};
 pub use voice_sample::{NewVoiceSample, VoiceSample};
+pub use watch_folder::{NewWatchFolder, UpdateWatchFolder, WatchFolder};
 
 // Common response types for Tauri commands
 use serde::Serialize;
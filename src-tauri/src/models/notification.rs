@@ -17,6 +17,8 @@ pub struct Notification {
     pub is_read: i32,
     pub is_important: i32,
     pub created_at: chrono::NaiveDateTime,
+    /// Recipient (None = everyone)
+    pub user_id: Option<i32>,
 }
 
 #[derive(Debug, Insertable, Deserialize)]
@@ -28,6 +30,7 @@ pub struct NewNotification {
     pub message: Option<String>,
     pub entity_type: Option<String>,
     pub entity_id: Option<i32>,
+    pub user_id: Option<i32>,
 }
 
 #[derive(Debug, AsChangeset, Deserialize, Default)]
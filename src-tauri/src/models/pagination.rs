@@ -0,0 +1,143 @@
+//! Common query input and paged result envelope for list commands
+use serde::{Deserialize, Serialize};
+
+/// Largest page a list command returns
+pub const MAX_PAGE_SIZE: i64 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    Asc,
+    Desc,
+}
+
+/// Paging, sorting and filtering of a list command; every field is optional
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ListQuery {
+    /// 1-based page number (ignored when `cursor` is set)
+    pub page: Option<i64>,
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+    pub page_size: Option<i64>,
+    /// Column to sort by; each command documents the columns it accepts
+    pub sort_by: Option<String>,
+    pub sort_direction: Option<SortDirection>,
+    /// Text searched for in the list's text columns
+    pub search: Option<String>,
+    /// Inclusive creation date range
+    pub date_from: Option<chrono::NaiveDateTime>,
+    pub date_to: Option<chrono::NaiveDateTime>,
+}
+
+impl ListQuery {
+    pub fn page_size(&self, default: i64) -> i64 {
+        self.page_size.unwrap_or(default).clamp(1, MAX_PAGE_SIZE)
+    }
+
+    /// Rows to skip, from the cursor or else the page number
+    pub fn offset(&self, page_size: i64) -> i64 {
+        match self.cursor.as_deref().and_then(|c| c.parse::<i64>().ok()) {
+            Some(offset) => offset.max(0),
+            None => (self.page.unwrap_or(1).max(1) - 1) * page_size,
+        }
+    }
+
+    pub fn sort_field<'a>(&'a self, default: &'a str) -> &'a str {
+        self.sort_by.as_deref().unwrap_or(default)
+    }
+
+    pub fn descending(&self, default: SortDirection) -> bool {
+        self.sort_direction.unwrap_or(default) == SortDirection::Desc
+    }
+
+    /// LIKE pattern for `search` with `%`, `_` and `\` escaped by `\`; None when blank
+    pub fn search_pattern(&self) -> Option<String> {
+        let search = self.search.as_deref()?.trim();
+        if search.is_empty() {
+            return None;
+        }
+        let mut escaped = String::with_capacity(search.len() + 2);
+        for c in search.chars() {
+            if matches!(c, '%' | '_' | '\\') {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        Some(format!("%{}%", escaped))
+    }
+}
+
+/// One page of a list with the total number of matching rows
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T: Serialize> {
+    pub items: Vec<T>,
+    pub total: i64,
+    /// 1-based page number of these items
+    pub page: i64,
+    pub page_size: i64,
+    /// Cursor for the next page; None on the last page
+    pub next_cursor: Option<String>,
+}
+
+impl<T: Serialize> Page<T> {
+    pub fn new(items: Vec<T>, total: i64, offset: i64, page_size: i64) -> Self {
+        let end = offset + items.len() as i64;
+        Self {
+            next_cursor: (end < total).then(|| end.to_string()),
+            page: offset / page_size + 1,
+            items,
+            total,
+            page_size,
+        }
+    }
+}
+
+/// Reject sort columns a list command does not support
+pub fn invalid_sort(field: &str) -> String {
+    format!("Invalid sort field: {}", field)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paging() {
+        let query = ListQuery {
+            page: Some(3),
+            page_size: Some(20),
+            search: Some("50%_off".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(query.offset(query.page_size(50)), 40);
+        assert_eq!(query.search_pattern().as_deref(), Some(r"%50\%\_off%"));
+
+        let page = Page::new(vec![1; 20], 65, 40, 20);
+        assert_eq!(page.page, 3);
+        assert_eq!(page.next_cursor.as_deref(), Some("60"));
+        assert!(Page::new(vec![1; 5], 65, 60, 20).next_cursor.is_none());
+
+        let next = ListQuery {
+            cursor: Some("60".to_string()),
+            page: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(next.offset(20), 60);
+        assert_eq!(ListQuery::default().page_size(1000), MAX_PAGE_SIZE);
+    }
+
+    #[test]
+    fn test_camel_case_fields() {
+        let query: ListQuery =
+            serde_json::from_str(r#"{"pageSize":5,"sortBy":"name","sortDirection":"asc"}"#)
+                .unwrap();
+        assert_eq!(query.page_size(50), 5);
+        assert_eq!(query.sort_field("created_at"), "name");
+
+        let page = serde_json::to_value(Page::new(vec![1], 3, 0, 1)).unwrap();
+        assert_eq!(page["pageSize"], 1);
+        assert_eq!(page["nextCursor"], "1");
+    }
+}
@@ -0,0 +1,23 @@
+//! Recording time pattern model - file name formats carrying the recording start
+use crate::schema::recording_time_patterns;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize)]
+#[diesel(table_name = recording_time_patterns)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct RecordingTimePattern {
+    pub id: i32,
+    /// chrono format, e.g. "%Y%m%d_%H%M%S"
+    pub pattern: String,
+    /// UTC offset the recorder's clock was set to, e.g. "+07:00"
+    pub timezone: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, Deserialize)]
+#[diesel(table_name = recording_time_patterns)]
+pub struct NewRecordingTimePattern {
+    pub pattern: String,
+    pub timezone: String,
+}
@@ -0,0 +1,29 @@
+//! Redaction model - audio ranges silenced or bleeped before disclosure
+use crate::schema::redactions;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize)]
+#[diesel(table_name = redactions)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct Redaction {
+    pub id: i32,
+    pub audio_file_id: i32,
+    pub start_time: f32,
+    pub end_time: f32,
+    pub mode: String,
+    pub reason: String,
+    pub created_by: Option<i32>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, Deserialize)]
+#[diesel(table_name = redactions)]
+pub struct NewRedaction {
+    pub audio_file_id: i32,
+    pub start_time: f32,
+    pub end_time: f32,
+    pub mode: String,
+    pub reason: String,
+    pub created_by: Option<i32>,
+}
@@ -0,0 +1,27 @@
+//! Report template model - user-editable layouts for generated case reports
+use crate::schema::report_templates;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize)]
+#[diesel(table_name = report_templates)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct ReportTemplate {
+    pub id: i32,
+    pub name: String,
+    /// Template text; see `report_service::render_template` for the syntax
+    pub body: String,
+    pub is_default: i32,
+    pub created_by: Option<i32>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, Deserialize)]
+#[diesel(table_name = report_templates)]
+pub struct NewReportTemplate {
+    pub name: String,
+    pub body: String,
+    pub is_default: i32,
+    pub created_by: Option<i32>,
+}
@@ -0,0 +1,48 @@
+//! Review models - second-analyst review of transcripts
+use crate::schema::{review_assignments, review_comments};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize)]
+#[diesel(table_name = review_assignments)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct ReviewAssignment {
+    pub id: i32,
+    pub audio_file_id: i32,
+    pub reviewer_id: i32,
+    pub assigned_by: Option<i32>,
+    /// "pending", "approved", "rejected", "cancelled" or "reopened"
+    pub status: String,
+    pub decision_reason: Option<String>,
+    pub decided_at: Option<chrono::NaiveDateTime>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, Deserialize)]
+#[diesel(table_name = review_assignments)]
+pub struct NewReviewAssignment {
+    pub audio_file_id: i32,
+    pub reviewer_id: i32,
+    pub assigned_by: Option<i32>,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize)]
+#[diesel(table_name = review_comments)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct ReviewComment {
+    pub id: i32,
+    pub review_id: i32,
+    pub segment_id: i32,
+    pub author_id: Option<i32>,
+    pub body: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, Deserialize)]
+#[diesel(table_name = review_comments)]
+pub struct NewReviewComment {
+    pub review_id: i32,
+    pub segment_id: i32,
+    pub author_id: Option<i32>,
+    pub body: String,
+}
@@ -0,0 +1,22 @@
+//! Free-form tag lists, stored as a compact JSON list of strings
+use serde::{Serialize, Serializer};
+
+/// Encode tags as a JSON list, trimmed and without empties or duplicates
+pub fn encode_tags(tags: &[String]) -> String {
+    let mut cleaned: Vec<&str> = Vec::new();
+    for tag in tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+        if !cleaned.contains(&tag) {
+            cleaned.push(tag);
+        }
+    }
+    serde_json::to_string(&cleaned).unwrap_or_else(|_| "[]".to_string())
+}
+
+pub fn decode_tags(raw: &str) -> Vec<String> {
+    serde_json::from_str(raw).unwrap_or_default()
+}
+
+/// Expose stored tags to the frontend as a list rather than a JSON string
+pub fn serialize_tags<S: Serializer>(tags: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    decode_tags(tags).serialize(serializer)
+}
@@ -1,7 +1,7 @@
 //! Transcript segment model
 use crate::schema::transcript_segments;
 use diesel::prelude::*;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
 
 #[derive(Debug, Clone, Queryable, Selectable, Serialize)]
 #[diesel(table_name = transcript_segments)]
@@ -15,6 +15,17 @@ pub struct TranscriptSegment {
     pub text: String,
     pub is_deleted: i32,
     pub created_at: chrono::NaiveDateTime,
+    pub deleted_at: Option<chrono::NaiveDateTime>,
+    #[serde(serialize_with = "serialize_words")]
+    pub words: Option<String>,
+    pub channel: Option<i32>,
+}
+
+impl TranscriptSegment {
+    /// Decode the stored word timings (empty when the segment has none)
+    pub fn word_timings(&self) -> Vec<WordTiming> {
+        self.words.as_deref().map(decode_words).unwrap_or_default()
+    }
 }
 
 #[derive(Debug, Insertable, Deserialize)]
@@ -25,6 +36,8 @@ pub struct NewTranscriptSegment {
     pub start_time: f32,
     pub end_time: f32,
     pub text: String,
+    pub words: Option<String>,
+    pub channel: Option<i32>,
 }
 
 #[derive(Debug, AsChangeset, Deserialize, Default)]
@@ -34,3 +47,73 @@ pub struct UpdateTranscriptSegment {
     pub text: Option<String>,
     pub is_deleted: Option<i32>,
 }
+
+/// Timing and recognizer confidence (0.0 - 1.0) of a single word
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WordTiming {
+    pub word: String,
+    pub start: f32,
+    pub end: f32,
+    pub confidence: f32,
+}
+
+/// Encode word timings into the compact `[[word, start, end, confidence], ...]` form
+pub fn encode_words(words: &[WordTiming]) -> String {
+    let tuples: Vec<(&str, f32, f32, f32)> = words
+        .iter()
+        .map(|w| (w.word.as_str(), w.start, w.end, w.confidence))
+        .collect();
+    serde_json::to_string(&tuples).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Decode the compact form produced by `encode_words`
+pub fn decode_words(raw: &str) -> Vec<WordTiming> {
+    serde_json::from_str::<Vec<(String, f32, f32, f32)>>(raw)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(word, start, end, confidence)| WordTiming {
+            word,
+            start,
+            end,
+            confidence,
+        })
+        .collect()
+}
+
+/// Expose stored words to the frontend as objects rather than raw tuples
+fn serialize_words<S: Serializer>(
+    words: &Option<String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match words {
+        Some(raw) => decode_words(raw).serialize(serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_words_roundtrip() {
+        let words = vec![
+            WordTiming {
+                word: "xin".to_string(),
+                start: 1.0,
+                end: 1.25,
+                confidence: 0.92,
+            },
+            WordTiming {
+                word: "chào".to_string(),
+                start: 1.25,
+                end: 1.6,
+                confidence: 0.41,
+            },
+        ];
+
+        let encoded = encode_words(&words);
+        assert_eq!(encoded, r#"[["xin",1.0,1.25,0.92],["chào",1.25,1.6,0.41]]"#);
+        assert_eq!(decode_words(&encoded), words);
+    }
+}
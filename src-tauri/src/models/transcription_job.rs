@@ -0,0 +1,59 @@
+//! Transcription job model - chunked transcription of long recordings
+use crate::schema::{transcription_chunks, transcription_jobs};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize)]
+#[diesel(table_name = transcription_jobs)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct TranscriptionJob {
+    pub id: i32,
+    pub audio_file_id: i32,
+    /// "running", "failed" or "completed"
+    pub status: String,
+    pub params: String,
+    pub total_chunks: i32,
+    pub segment_count: i32,
+    pub error: Option<String>,
+    pub created_by: Option<i32>,
+    pub created_at: chrono::NaiveDateTime,
+    pub completed_at: Option<chrono::NaiveDateTime>,
+}
+
+#[derive(Debug, Insertable, Deserialize)]
+#[diesel(table_name = transcription_jobs)]
+pub struct NewTranscriptionJob {
+    pub audio_file_id: i32,
+    pub params: String,
+    pub total_chunks: i32,
+    pub created_by: Option<i32>,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize)]
+#[diesel(table_name = transcription_chunks)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct TranscriptionChunk {
+    pub id: i32,
+    pub job_id: i32,
+    pub chunk_index: i32,
+    pub start_time: f32,
+    pub end_time: f32,
+    /// "pending", "failed" or "completed"
+    pub status: String,
+    /// Recognizer output in absolute time (JSON), kept so a job can resume
+    pub segments: Option<String>,
+    pub error: Option<String>,
+    pub completed_at: Option<chrono::NaiveDateTime>,
+    /// Channel track transcribed; `None` for the mixed-down recording
+    pub channel: Option<i32>,
+}
+
+#[derive(Debug, Insertable, Deserialize)]
+#[diesel(table_name = transcription_chunks)]
+pub struct NewTranscriptionChunk {
+    pub job_id: i32,
+    pub chunk_index: i32,
+    pub start_time: f32,
+    pub end_time: f32,
+    pub channel: Option<i32>,
+}
@@ -0,0 +1,72 @@
+//! Voice activity analysis model
+use crate::schema::vad_analyses;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize, Serializer};
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize)]
+#[diesel(table_name = vad_analyses)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct VadAnalysis {
+    pub id: i32,
+    pub audio_file_id: i32,
+    pub duration: f32,
+    pub speech_duration: f32,
+    pub speech_ratio: f32,
+    #[serde(serialize_with = "serialize_ranges")]
+    pub regions: String,
+    #[serde(serialize_with = "serialize_ranges")]
+    pub long_gaps: String,
+    pub params: String,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+impl VadAnalysis {
+    /// Decode the stored speech regions
+    pub fn speech_regions(&self) -> Vec<TimeRange> {
+        decode_ranges(&self.regions)
+    }
+}
+
+#[derive(Debug, Insertable, Deserialize)]
+#[diesel(table_name = vad_analyses)]
+pub struct NewVadAnalysis {
+    pub audio_file_id: i32,
+    pub duration: f32,
+    pub speech_duration: f32,
+    pub speech_ratio: f32,
+    pub regions: String,
+    pub long_gaps: String,
+    pub params: String,
+}
+
+/// A time range in seconds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TimeRange {
+    pub start: f32,
+    pub end: f32,
+}
+
+impl TimeRange {
+    pub fn length(&self) -> f32 {
+        self.end - self.start
+    }
+}
+
+/// Encode ranges into the compact `[[start, end], ...]` form
+pub fn encode_ranges(ranges: &[TimeRange]) -> String {
+    let pairs: Vec<(f32, f32)> = ranges.iter().map(|r| (r.start, r.end)).collect();
+    serde_json::to_string(&pairs).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Decode the compact form produced by `encode_ranges`
+pub fn decode_ranges(raw: &str) -> Vec<TimeRange> {
+    serde_json::from_str::<Vec<(f32, f32)>>(raw)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(start, end)| TimeRange { start, end })
+        .collect()
+}
+
+fn serialize_ranges<S: Serializer>(ranges: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    decode_ranges(ranges).serialize(serializer)
+}
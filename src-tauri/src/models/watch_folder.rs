@@ -0,0 +1,44 @@
+//! Watch folder model - drop folders ingested automatically into a case
+use crate::schema::watch_folders;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize)]
+#[diesel(table_name = watch_folders)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct WatchFolder {
+    pub id: i32,
+    pub path: String,
+    pub case_id: i32,
+    /// Wildcard patterns separated by ';' (e.g. "*.wav;*.amr")
+    pub file_pattern: String,
+    /// "leave" or "move"
+    pub post_action: String,
+    /// Destination of ingested files when post_action is "move"
+    pub move_to: Option<String>,
+    pub is_active: i32,
+    pub last_scan_at: Option<chrono::NaiveDateTime>,
+    pub created_by: Option<i32>,
+    pub created_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, Deserialize)]
+#[diesel(table_name = watch_folders)]
+pub struct NewWatchFolder {
+    pub path: String,
+    pub case_id: i32,
+    pub file_pattern: String,
+    pub post_action: String,
+    pub move_to: Option<String>,
+    pub created_by: Option<i32>,
+}
+
+#[derive(Debug, AsChangeset, Deserialize, Default)]
+#[diesel(table_name = watch_folders)]
+pub struct UpdateWatchFolder {
+    pub case_id: Option<i32>,
+    pub file_pattern: Option<String>,
+    pub post_action: Option<String>,
+    pub move_to: Option<String>,
+    pub is_active: Option<i32>,
+}
@@ -22,6 +22,36 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    annotations (id) {
+        id -> Integer,
+        audio_file_id -> Integer,
+        segment_id -> Nullable<Integer>,
+        start_time -> Float,
+        end_time -> Float,
+        author_id -> Nullable<Integer>,
+        body -> Text,
+        tags -> Text,
+        color -> Nullable<Text>,
+        is_resolved -> Integer,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    audio_derivatives (id) {
+        id -> Integer,
+        audio_file_id -> Integer,
+        kind -> Text,
+        file_path -> Text,
+        sha256 -> Text,
+        params -> Nullable<Text>,
+        created_by -> Nullable<Integer>,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     audio_files (id) {
         id -> Integer,
@@ -31,6 +61,63 @@ diesel::table! {
         duration -> Float,
         status -> Text,
         created_at -> Timestamp,
+        channels -> Integer,
+        sha256 -> Nullable<Text>,
+        recorded_at -> Nullable<Timestamp>,
+        recorded_tz -> Nullable<Text>,
+        recorded_at_source -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    audio_status_history (id) {
+        id -> Integer,
+        audio_file_id -> Integer,
+        from_status -> Text,
+        to_status -> Text,
+        reason -> Nullable<Text>,
+        changed_by -> Nullable<Integer>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    bookmark_segments (bookmark_id, segment_id) {
+        bookmark_id -> Integer,
+        segment_id -> Integer,
+    }
+}
+
+diesel::table! {
+    bookmarks (id) {
+        id -> Integer,
+        audio_file_id -> Integer,
+        start_time -> Float,
+        end_time -> Float,
+        title -> Text,
+        description -> Nullable<Text>,
+        severity -> Text,
+        created_by -> Nullable<Integer>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    case_assignments (case_id, user_id) {
+        case_id -> Integer,
+        user_id -> Integer,
+        assigned_by -> Nullable<Integer>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    case_members (case_id, user_id) {
+        case_id -> Integer,
+        user_id -> Integer,
+        role -> Text,
+        added_by -> Nullable<Integer>,
+        created_at -> Timestamp,
     }
 }
 
@@ -42,6 +129,30 @@ diesel::table! {
         description -> Nullable<Text>,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        status -> Text,
+        priority -> Text,
+        tags -> Text,
+        due_date -> Nullable<Date>,
+    }
+}
+
+diesel::table! {
+    channel_speakers (id) {
+        id -> Integer,
+        audio_file_id -> Integer,
+        channel -> Integer,
+        speaker_id -> Integer,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    enhancement_presets (id) {
+        id -> Integer,
+        audio_file_id -> Integer,
+        params -> Text,
+        updated_by -> Nullable<Integer>,
+        updated_at -> Timestamp,
     }
 }
 
@@ -57,6 +168,29 @@ diesel::table! {
         is_read -> Integer,
         is_important -> Integer,
         created_at -> Timestamp,
+        user_id -> Nullable<Integer>,
+    }
+}
+
+diesel::table! {
+    recording_time_patterns (id) {
+        id -> Integer,
+        pattern -> Text,
+        timezone -> Text,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    redactions (id) {
+        id -> Integer,
+        audio_file_id -> Integer,
+        start_time -> Float,
+        end_time -> Float,
+        mode -> Text,
+        reason -> Text,
+        created_by -> Nullable<Integer>,
+        created_at -> Timestamp,
     }
 }
 
@@ -70,6 +204,42 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    report_templates (id) {
+        id -> Integer,
+        name -> Text,
+        body -> Text,
+        is_default -> Integer,
+        created_by -> Nullable<Integer>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    review_assignments (id) {
+        id -> Integer,
+        audio_file_id -> Integer,
+        reviewer_id -> Integer,
+        assigned_by -> Nullable<Integer>,
+        status -> Text,
+        decision_reason -> Nullable<Text>,
+        decided_at -> Nullable<Timestamp>,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    review_comments (id) {
+        id -> Integer,
+        review_id -> Integer,
+        segment_id -> Integer,
+        author_id -> Nullable<Integer>,
+        body -> Text,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     speakers (id) {
         id -> Integer,
@@ -92,6 +262,39 @@ diesel::table! {
         text -> Text,
         is_deleted -> Integer,
         created_at -> Timestamp,
+        deleted_at -> Nullable<Timestamp>,
+        words -> Nullable<Text>,
+        channel -> Nullable<Integer>,
+    }
+}
+
+diesel::table! {
+    transcription_chunks (id) {
+        id -> Integer,
+        job_id -> Integer,
+        chunk_index -> Integer,
+        start_time -> Float,
+        end_time -> Float,
+        status -> Text,
+        segments -> Nullable<Text>,
+        error -> Nullable<Text>,
+        completed_at -> Nullable<Timestamp>,
+        channel -> Nullable<Integer>,
+    }
+}
+
+diesel::table! {
+    transcription_jobs (id) {
+        id -> Integer,
+        audio_file_id -> Integer,
+        status -> Text,
+        params -> Text,
+        total_chunks -> Integer,
+        segment_count -> Integer,
+        error -> Nullable<Text>,
+        created_by -> Nullable<Integer>,
+        created_at -> Timestamp,
+        completed_at -> Nullable<Timestamp>,
     }
 }
 
@@ -109,6 +312,20 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    vad_analyses (id) {
+        id -> Integer,
+        audio_file_id -> Integer,
+        duration -> Float,
+        speech_duration -> Float,
+        speech_ratio -> Float,
+        regions -> Text,
+        long_gaps -> Text,
+        params -> Text,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     voice_samples (id) {
         id -> Integer,
@@ -120,21 +337,97 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    watch_folder_files (watch_folder_id, file_path) {
+        watch_folder_id -> Integer,
+        file_path -> Text,
+        file_size -> BigInt,
+        modified_ms -> Nullable<BigInt>,
+        handled_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    watch_folders (id) {
+        id -> Integer,
+        path -> Text,
+        case_id -> Integer,
+        file_pattern -> Text,
+        post_action -> Text,
+        move_to -> Nullable<Text>,
+        is_active -> Integer,
+        last_scan_at -> Nullable<Timestamp>,
+        created_by -> Nullable<Integer>,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::joinable!(activity_logs -> users (user_id));
+diesel::joinable!(annotations -> audio_files (audio_file_id));
+diesel::joinable!(annotations -> transcript_segments (segment_id));
+diesel::joinable!(annotations -> users (author_id));
+diesel::joinable!(audio_derivatives -> audio_files (audio_file_id));
+diesel::joinable!(audio_derivatives -> users (created_by));
 diesel::joinable!(audio_files -> cases (case_id));
+diesel::joinable!(audio_status_history -> audio_files (audio_file_id));
+diesel::joinable!(audio_status_history -> users (changed_by));
+diesel::joinable!(bookmark_segments -> bookmarks (bookmark_id));
+diesel::joinable!(bookmark_segments -> transcript_segments (segment_id));
+diesel::joinable!(bookmarks -> audio_files (audio_file_id));
+diesel::joinable!(bookmarks -> users (created_by));
+diesel::joinable!(case_assignments -> cases (case_id));
+diesel::joinable!(case_members -> cases (case_id));
+diesel::joinable!(channel_speakers -> audio_files (audio_file_id));
+diesel::joinable!(channel_speakers -> speakers (speaker_id));
+diesel::joinable!(enhancement_presets -> audio_files (audio_file_id));
+diesel::joinable!(enhancement_presets -> users (updated_by));
+diesel::joinable!(notifications -> users (user_id));
+diesel::joinable!(redactions -> audio_files (audio_file_id));
+diesel::joinable!(redactions -> users (created_by));
+diesel::joinable!(report_templates -> users (created_by));
+diesel::joinable!(review_assignments -> audio_files (audio_file_id));
+diesel::joinable!(review_comments -> review_assignments (review_id));
+diesel::joinable!(review_comments -> transcript_segments (segment_id));
+diesel::joinable!(review_comments -> users (author_id));
 diesel::joinable!(transcript_segments -> audio_files (audio_file_id));
 diesel::joinable!(transcript_segments -> speakers (speaker_id));
+diesel::joinable!(transcription_chunks -> transcription_jobs (job_id));
+diesel::joinable!(transcription_jobs -> audio_files (audio_file_id));
+diesel::joinable!(transcription_jobs -> users (created_by));
+diesel::joinable!(vad_analyses -> audio_files (audio_file_id));
 diesel::joinable!(voice_samples -> speakers (speaker_id));
+diesel::joinable!(watch_folder_files -> watch_folders (watch_folder_id));
+diesel::joinable!(watch_folders -> cases (case_id));
+diesel::joinable!(watch_folders -> users (created_by));
 
 diesel::allow_tables_to_appear_in_same_query!(
     activity_logs,
     alert_words,
+    annotations,
+    audio_derivatives,
     audio_files,
+    audio_status_history,
+    bookmark_segments,
+    bookmarks,
+    case_assignments,
+    case_members,
     cases,
+    channel_speakers,
+    enhancement_presets,
     notifications,
+    recording_time_patterns,
+    redactions,
     replacement_words,
+    report_templates,
+    review_assignments,
+    review_comments,
     speakers,
     transcript_segments,
+    transcription_chunks,
+    transcription_jobs,
     users,
+    vad_analyses,
     voice_samples,
+    watch_folder_files,
+    watch_folders,
 );
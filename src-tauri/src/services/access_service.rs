@@ -0,0 +1,182 @@
+//! Case access service: per-case membership roles. Admins can access every case.
+use crate::models::User;
+use crate::schema::{audio_files, case_members, transcript_segments, users};
+use crate::services::activity_service::log_activity;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Membership changes are logged against the case under this target type
+pub const ACCESS_TARGET: &str = "case_access";
+
+/// Role of a user on a case; each role includes the rights of the ones before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaseRole {
+    Reader,
+    Editor,
+    Owner,
+}
+
+impl CaseRole {
+    pub const ALL: [CaseRole; 3] = [CaseRole::Reader, CaseRole::Editor, CaseRole::Owner];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CaseRole::Reader => "reader",
+            CaseRole::Editor => "editor",
+            CaseRole::Owner => "owner",
+        }
+    }
+}
+
+impl std::fmt::Display for CaseRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for CaseRole {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        CaseRole::ALL
+            .into_iter()
+            .find(|role| role.as_str() == value)
+            .ok_or_else(|| format!("Invalid case role: {}", value))
+    }
+}
+
+/// Log a membership granted as a side effect (case creation, assignment)
+pub fn log_access_grant(
+    conn: &mut SqliteConnection,
+    user_id: Option<i32>,
+    case_id: i32,
+    member_id: i32,
+    role: CaseRole,
+) -> QueryResult<()> {
+    log_activity(
+        conn,
+        user_id,
+        "create",
+        ACCESS_TARGET,
+        Some(case_id),
+        Some(
+            serde_json::json!({
+                "operation": "grant_access",
+                "member_id": member_id,
+                "from": null,
+                "to": role,
+            })
+            .to_string(),
+        ),
+    )
+}
+
+/// Load the calling user, who must be signed in and active
+fn caller(conn: &mut SqliteConnection, user_id: Option<i32>) -> Result<User, String> {
+    let user_id = user_id.ok_or_else(|| "Authentication required".to_string())?;
+    let user = users::table
+        .find(user_id)
+        .first::<User>(conn)
+        .map_err(|e| format!("User not found: {}", e))?;
+    if user.is_active != 1 {
+        return Err("Account is disabled".to_string());
+    }
+    Ok(user)
+}
+
+/// The caller's role on a case (admins are owners of every case)
+pub fn case_role(
+    conn: &mut SqliteConnection,
+    user_id: Option<i32>,
+    case_id: i32,
+) -> Result<Option<CaseRole>, String> {
+    let user = caller(conn, user_id)?;
+    if user.role == "admin" {
+        return Ok(Some(CaseRole::Owner));
+    }
+
+    let role = case_members::table
+        .find((case_id, user.id))
+        .select(case_members::role)
+        .first::<String>(conn)
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    role.map(|role| role.parse()).transpose()
+}
+
+/// Deny unless the caller has at least `min` on the case
+pub fn require_case_role(
+    conn: &mut SqliteConnection,
+    user_id: Option<i32>,
+    case_id: i32,
+    min: CaseRole,
+) -> Result<CaseRole, String> {
+    match case_role(conn, user_id, case_id)? {
+        Some(role) if role >= min => Ok(role),
+        Some(_) => Err(format!("Case {} requires {} access", case_id, min)),
+        None => Err(format!("Access denied to case {}", case_id)),
+    }
+}
+
+/// `require_case_role` for the case an audio file belongs to
+pub fn require_audio_role(
+    conn: &mut SqliteConnection,
+    user_id: Option<i32>,
+    audio_file_id: i32,
+    min: CaseRole,
+) -> Result<CaseRole, String> {
+    let case_id = audio_files::table
+        .find(audio_file_id)
+        .select(audio_files::case_id)
+        .first::<i32>(conn)
+        .map_err(|e| format!("Audio file not found: {}", e))?;
+    require_case_role(conn, user_id, case_id, min)
+}
+
+/// `require_case_role` for the case a transcript segment belongs to
+pub fn require_segment_role(
+    conn: &mut SqliteConnection,
+    user_id: Option<i32>,
+    segment_id: i32,
+    min: CaseRole,
+) -> Result<CaseRole, String> {
+    let audio_file_id = transcript_segments::table
+        .find(segment_id)
+        .select(transcript_segments::audio_file_id)
+        .first::<i32>(conn)
+        .map_err(|e| format!("Segment not found: {}", e))?;
+    require_audio_role(conn, user_id, audio_file_id, min)
+}
+
+/// Ids of the cases the caller may see; None means every case (admins)
+pub fn accessible_case_ids(
+    conn: &mut SqliteConnection,
+    user_id: Option<i32>,
+) -> Result<Option<Vec<i32>>, String> {
+    let user = caller(conn, user_id)?;
+    if user.role == "admin" {
+        return Ok(None);
+    }
+
+    case_members::table
+        .filter(case_members::user_id.eq(user.id))
+        .select(case_members::case_id)
+        .load::<i32>(conn)
+        .map(Some)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case_role_order() {
+        assert!(CaseRole::Owner > CaseRole::Editor);
+        assert!(CaseRole::Editor > CaseRole::Reader);
+        assert_eq!("editor".parse::<CaseRole>(), Ok(CaseRole::Editor));
+        assert!("admin".parse::<CaseRole>().is_err());
+    }
+}
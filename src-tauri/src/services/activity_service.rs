@@ -0,0 +1,28 @@
+//! Activity log service for recording audit entries from backend operations
+use crate::models::NewActivityLog;
+use crate::schema::activity_logs;
+use diesel::prelude::*;
+
+/// Insert an audit entry using an existing connection (so it can join a transaction)
+pub fn log_activity(
+    conn: &mut SqliteConnection,
+    user_id: Option<i32>,
+    action: &str,
+    target_type: &str,
+    target_id: Option<i32>,
+    details: Option<String>,
+) -> QueryResult<()> {
+    let new_log = NewActivityLog {
+        user_id,
+        action: action.to_string(),
+        target_type: target_type.to_string(),
+        target_id,
+        details,
+    };
+
+    diesel::insert_into(activity_logs::table)
+        .values(&new_log)
+        .execute(conn)?;
+
+    Ok(())
+}
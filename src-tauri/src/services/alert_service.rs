@@ -0,0 +1,103 @@
+//! Alert service: finds alert words in transcript text
+use crate::models::{AlertWord, TranscriptSegment};
+use crate::schema::{alert_words, audio_files, transcript_segments};
+use diesel::prelude::*;
+use diesel::sql_types::Text;
+use serde::Serialize;
+
+define_sql_function! {
+    /// `contains_keyword` for SQL queries; register it with `register_keyword_match` first
+    fn matches_keyword(text: Text, keyword: Text) -> Bool;
+}
+
+/// Make `matches_keyword` callable on this connection. SQLite's LIKE lacks the Unicode
+/// case folding whole-word matching of Vietnamese text needs.
+pub fn register_keyword_match(conn: &mut SqliteConnection) -> QueryResult<()> {
+    matches_keyword_utils::register_impl(conn, |text: String, keyword: String| {
+        contains_keyword(&text, &keyword)
+    })
+}
+
+/// An alert word spoken in a transcript segment
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertHit {
+    pub alert_word_id: i32,
+    pub keyword: String,
+    pub category: String,
+    pub audio_file_id: i32,
+    pub segment_id: i32,
+    pub start_time: f32,
+    pub end_time: f32,
+    pub text: String,
+}
+
+/// Case-insensitive whole-word match, so "an" does not hit inside "bạn"
+pub fn contains_keyword(text: &str, keyword: &str) -> bool {
+    let text = text.to_lowercase();
+    let keyword = keyword.trim().to_lowercase();
+    if keyword.is_empty() {
+        return false;
+    }
+
+    text.match_indices(&keyword).any(|(at, found)| {
+        let before = text[..at].chars().next_back();
+        let after = text[at + found.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+/// Match every alert word against every segment
+pub fn find_alert_hits(segments: &[TranscriptSegment], words: &[AlertWord]) -> Vec<AlertHit> {
+    let mut hits = Vec::new();
+    for segment in segments {
+        for word in words {
+            if contains_keyword(&segment.text, &word.keyword) {
+                hits.push(AlertHit {
+                    alert_word_id: word.id,
+                    keyword: word.keyword.clone(),
+                    category: word.category.clone(),
+                    audio_file_id: segment.audio_file_id,
+                    segment_id: segment.id,
+                    start_time: segment.start_time,
+                    end_time: segment.end_time,
+                    text: segment.text.clone(),
+                });
+            }
+        }
+    }
+    hits
+}
+
+/// Alert hits in the live (not deleted) transcripts of a case's audio files
+pub fn load_case_alert_hits(
+    conn: &mut SqliteConnection,
+    case_id: i32,
+) -> QueryResult<Vec<AlertHit>> {
+    let words = alert_words::table.load::<AlertWord>(conn)?;
+    if words.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let segments = transcript_segments::table
+        .inner_join(audio_files::table)
+        .filter(audio_files::case_id.eq(case_id))
+        .filter(transcript_segments::is_deleted.eq(0))
+        .select(TranscriptSegment::as_select())
+        .load::<TranscriptSegment>(conn)?;
+
+    Ok(find_alert_hits(&segments, &words))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains_keyword() {
+        assert!(contains_keyword("Giao HÀNG lúc 9 giờ", "hàng"));
+        assert!(contains_keyword("hàng, ngay", "hàng"));
+        assert!(!contains_keyword("bạn ơi", "an"));
+        assert!(!contains_keyword("anything", "an"));
+        assert!(!contains_keyword("text", "  "));
+    }
+}
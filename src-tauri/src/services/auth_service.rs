@@ -9,6 +9,7 @@ pub enum AuthError {
     DatabaseError(String),
     InvalidCredentials,
     AccountDisabled,
+    PermissionDenied,
     HashError,
 }
 
@@ -18,6 +19,7 @@ impl std::fmt::Display for AuthError {
             AuthError::DatabaseError(e) => write!(f, "Database error: {}", e),
             AuthError::InvalidCredentials => write!(f, "Invalid username or password"),
             AuthError::AccountDisabled => write!(f, "Account is disabled"),
+            AuthError::PermissionDenied => write!(f, "Permission denied"),
             AuthError::HashError => write!(f, "Password hash error"),
         }
     }
@@ -109,3 +111,24 @@ pub fn change_password(
 
     Ok(())
 }
+
+/// Load an active admin user (for admin-only operations), using an existing connection
+pub fn require_admin(conn: &mut SqliteConnection, user_id: Option<i32>) -> Result<User, AuthError> {
+    use crate::schema::users::dsl::*;
+
+    let user = users
+        .filter(id.eq(user_id.ok_or(AuthError::PermissionDenied)?))
+        .first::<User>(conn)
+        .optional()
+        .map_err(|e| AuthError::DatabaseError(e.to_string()))?
+        .ok_or(AuthError::PermissionDenied)?;
+
+    if user.is_active != 1 {
+        return Err(AuthError::AccountDisabled);
+    }
+    if user.role != "admin" {
+        return Err(AuthError::PermissionDenied);
+    }
+
+    Ok(user)
+}
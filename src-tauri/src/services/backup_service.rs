@@ -0,0 +1,584 @@
+//! Backup service: online copies of the live database through SQLite's backup API,
+//! scheduled snapshots with retention, and restore
+use crate::services::database::{db_path, MIGRATIONS};
+use crate::services::encryption_service;
+use crate::services::get_pool;
+use crate::services::storage::{app_dir, sha256_file, storage_dir};
+use diesel::connection::SimpleConnection;
+use diesel::migration::MigrationSource;
+use diesel::prelude::*;
+use diesel::sqlite::Sqlite;
+use diesel_migrations::MigrationHarness;
+use libsqlite3_sys as ffi;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::ffi::{CStr, CString};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const SCHEDULE_FILE: &str = "backup_schedule.json";
+
+/// Snapshots live in this subfolder of the app data directory
+const SNAPSHOT_DIR: &str = "snapshots";
+const SNAPSHOT_PREFIX: &str = "spexor_";
+const SNAPSHOT_TIME_FORMAT: &str = "%Y%m%d%H%M%S";
+
+/// How often the scheduler checks whether a snapshot is due
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Pages copied per backup step; the source is unlocked between steps so writers
+/// are never blocked for long
+const PAGES_PER_STEP: i32 = 256;
+const STEP_PAUSE: Duration = Duration::from_millis(5);
+const BUSY_TIMEOUT_MS: i32 = 5000;
+/// How long a copy may go without progress while the databases stay busy or locked
+const BUSY_LIMIT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupSchedule {
+    pub enabled: bool,
+    pub interval_hours: u32,
+    /// Number of snapshots kept; older ones are deleted after each new snapshot
+    pub keep: usize,
+}
+
+impl Default for BackupSchedule {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_hours: 24,
+            keep: 7,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Backup {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+    /// Latest migration the copy was made with
+    pub schema_version: Option<String>,
+    pub created_at: String,
+    /// Snapshots deleted by the retention policy
+    pub pruned: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Snapshot {
+    pub file_name: String,
+    pub path: String,
+    pub size: u64,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Restore {
+    pub restored_from: String,
+    pub schema_version: Option<String>,
+    /// Migrations newer than the backup, applied after restoring it
+    pub migrations_applied: usize,
+    /// Snapshot of the database as it was just before the restore
+    pub safety_snapshot: String,
+}
+
+/// Raw SQLite handle for the backup API, which diesel does not expose
+struct RawDb(*mut ffi::sqlite3);
+
+impl RawDb {
+    fn open(path: &Path, flags: i32) -> Result<Self, String> {
+        let c_path = CString::new(path.to_string_lossy().as_bytes())
+            .map_err(|_| "Invalid database path".to_string())?;
+        let mut handle = std::ptr::null_mut();
+        // SAFETY: valid C string and out pointer; the handle is closed on drop even
+        // when opening failed
+        let rc =
+            unsafe { ffi::sqlite3_open_v2(c_path.as_ptr(), &mut handle, flags, std::ptr::null()) };
+        let db = RawDb(handle);
+        if rc != ffi::SQLITE_OK {
+            return Err(format!("Failed to open {}: {}", path.display(), db.error()));
+        }
+        // SAFETY: handle is open
+        unsafe { ffi::sqlite3_busy_timeout(db.0, BUSY_TIMEOUT_MS) };
+        if let Some(pragma) = encryption_service::database_key_pragma() {
+            db.exec(&pragma)?;
+        }
+        Ok(db)
+    }
+
+    fn exec(&self, sql: &str) -> Result<(), String> {
+        let c_sql = CString::new(sql).map_err(|e| e.to_string())?;
+        // SAFETY: handle is open and the statement is a valid C string
+        let rc = unsafe {
+            ffi::sqlite3_exec(
+                self.0,
+                c_sql.as_ptr(),
+                None,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+        };
+        if rc != ffi::SQLITE_OK {
+            return Err(self.error());
+        }
+        Ok(())
+    }
+
+    fn error(&self) -> String {
+        if self.0.is_null() {
+            return "out of memory".to_string();
+        }
+        // SAFETY: sqlite3_errmsg returns a NUL-terminated string owned by the handle
+        unsafe { CStr::from_ptr(ffi::sqlite3_errmsg(self.0)) }
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+impl Drop for RawDb {
+    fn drop(&mut self) {
+        // SAFETY: closing a null handle is a no-op
+        unsafe { ffi::sqlite3_close(self.0) };
+    }
+}
+
+/// Copy the main database of `source` over `dest`, a page batch at a time
+fn copy_database(source: &RawDb, dest: &RawDb) -> Result<(), String> {
+    // SAFETY: both handles are open and outlive the backup object
+    let backup =
+        unsafe { ffi::sqlite3_backup_init(dest.0, c"main".as_ptr(), source.0, c"main".as_ptr()) };
+    if backup.is_null() {
+        return Err(dest.error());
+    }
+
+    let mut busy_since: Option<Instant> = None;
+    let mut timed_out = false;
+    loop {
+        // SAFETY: backup is live until sqlite3_backup_finish
+        match unsafe { ffi::sqlite3_backup_step(backup, PAGES_PER_STEP) } {
+            ffi::SQLITE_DONE => break,
+            ffi::SQLITE_OK => {
+                busy_since = None;
+                std::thread::sleep(STEP_PAUSE)
+            }
+            ffi::SQLITE_BUSY | ffi::SQLITE_LOCKED => {
+                if busy_since.get_or_insert_with(Instant::now).elapsed() >= BUSY_LIMIT {
+                    timed_out = true;
+                    break;
+                }
+                std::thread::sleep(STEP_PAUSE)
+            }
+            // The error is reported by sqlite3_backup_finish
+            _ => break,
+        }
+    }
+
+    // SAFETY: finishes and frees the backup object
+    let rc = unsafe { ffi::sqlite3_backup_finish(backup) };
+    if timed_out {
+        return Err(format!(
+            "Database stayed busy for {} seconds; copy abandoned",
+            BUSY_LIMIT.as_secs()
+        ));
+    }
+    if rc != ffi::SQLITE_OK {
+        return Err(dest.error());
+    }
+    Ok(())
+}
+
+#[derive(QueryableByName)]
+struct IntegrityRow {
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    integrity_check: String,
+}
+
+#[derive(QueryableByName)]
+struct VersionRow {
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    version: String,
+}
+
+fn open_copy(path: &Path) -> Result<SqliteConnection, String> {
+    let mut conn = SqliteConnection::establish(&path.to_string_lossy())
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    if let Some(pragma) = encryption_service::database_key_pragma() {
+        conn.batch_execute(&pragma).map_err(|e| e.to_string())?;
+    }
+    Ok(conn)
+}
+
+/// Run `PRAGMA integrity_check` on a database file
+pub fn integrity_check(path: &Path) -> Result<(), String> {
+    let mut conn = open_copy(path)?;
+    let rows = diesel::sql_query("PRAGMA integrity_check")
+        .load::<IntegrityRow>(&mut conn)
+        .map_err(|e| {
+            if encryption_service::is_enabled() {
+                format!(
+                    "Failed to read {} (corrupt, or encrypted with a retired key): {}",
+                    path.display(),
+                    e
+                )
+            } else {
+                format!("Failed to read {}: {}", path.display(), e)
+            }
+        })?;
+
+    let problems: Vec<String> = rows
+        .into_iter()
+        .map(|row| row.integrity_check)
+        .filter(|message| message != "ok")
+        .collect();
+    if !problems.is_empty() {
+        return Err(format!(
+            "Integrity check failed for {}: {}",
+            path.display(),
+            problems.into_iter().take(5).collect::<Vec<_>>().join("; ")
+        ));
+    }
+    Ok(())
+}
+
+/// Migration versions recorded in a database file, oldest first
+fn applied_versions(path: &Path) -> Result<Vec<String>, String> {
+    let mut conn = open_copy(path)?;
+    diesel::sql_query("SELECT version FROM __diesel_schema_migrations ORDER BY version")
+        .load::<VersionRow>(&mut conn)
+        .map(|rows| rows.into_iter().map(|row| row.version).collect())
+        .map_err(|_| format!("{} is not a SPEXOR database", path.display()))
+}
+
+/// Versions of the migrations embedded in this build
+pub fn embedded_versions() -> Result<HashSet<String>, String> {
+    let migrations =
+        MigrationSource::<Sqlite>::migrations(&MIGRATIONS).map_err(|e| e.to_string())?;
+    Ok(migrations
+        .iter()
+        .map(|migration| migration.name().version().to_string())
+        .collect())
+}
+
+/// Back up the live database to `dest` while the app keeps running. The copy is
+/// written next to `dest` and only moved into place once it passed the integrity check.
+pub fn backup_database(dest: &Path) -> Result<Backup, String> {
+    if let Some(parent) = dest.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    let mut partial = dest.as_os_str().to_owned();
+    partial.push(".partial");
+    let partial = PathBuf::from(partial);
+    let _ = fs::remove_file(&partial);
+
+    let copied = (|| {
+        let source = RawDb::open(&db_path(app_dir()), ffi::SQLITE_OPEN_READONLY)?;
+        let target = RawDb::open(
+            &partial,
+            ffi::SQLITE_OPEN_READWRITE | ffi::SQLITE_OPEN_CREATE,
+        )?;
+        copy_database(&source, &target)?;
+        drop(target);
+        integrity_check(&partial)?;
+        applied_versions(&partial)
+    })();
+    let versions = match copied {
+        Ok(versions) => versions,
+        Err(e) => {
+            let _ = fs::remove_file(&partial);
+            return Err(e);
+        }
+    };
+
+    fs::rename(&partial, dest).map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+
+    Ok(Backup {
+        path: dest.to_string_lossy().to_string(),
+        size: fs::metadata(dest).map(|m| m.len()).unwrap_or(0),
+        sha256: sha256_file(dest).map_err(|e| e.to_string())?,
+        schema_version: versions.last().cloned(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        pruned: Vec::new(),
+    })
+}
+
+fn schedule_path() -> PathBuf {
+    app_dir().join(SCHEDULE_FILE)
+}
+
+/// Snapshot schedule, defaulting to daily snapshots keeping the last 7
+pub fn load_schedule() -> BackupSchedule {
+    fs::read(schedule_path())
+        .ok()
+        .and_then(|raw| serde_json::from_slice(&raw).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_schedule(schedule: &BackupSchedule) -> Result<(), String> {
+    if schedule.interval_hours == 0 {
+        return Err("Snapshot interval must be at least one hour".to_string());
+    }
+    if schedule.keep == 0 {
+        return Err("At least one snapshot must be kept".to_string());
+    }
+    let json = serde_json::to_vec_pretty(schedule).map_err(|e| e.to_string())?;
+    fs::write(schedule_path(), json).map_err(|e| format!("Failed to save backup schedule: {}", e))
+}
+
+fn snapshot_time(file_name: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    let stamp = file_name
+        .strip_prefix(SNAPSHOT_PREFIX)?
+        .strip_suffix(".db")?;
+    chrono::NaiveDateTime::parse_from_str(stamp, SNAPSHOT_TIME_FORMAT)
+        .ok()
+        .map(|time| time.and_utc())
+}
+
+fn snapshot_dir() -> Result<PathBuf, String> {
+    storage_dir(SNAPSHOT_DIR).map_err(|e| e.to_string())
+}
+
+/// Snapshots in the snapshot folder, newest first
+pub fn list_snapshots() -> Result<Vec<Snapshot>, String> {
+    snapshots_in(&snapshot_dir()?)
+}
+
+fn snapshots_in(dir: &Path) -> Result<Vec<Snapshot>, String> {
+    let mut snapshots = Vec::new();
+
+    for entry in fs::read_dir(dir).map_err(|e| e.to_string())?.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let Some(created_at) = snapshot_time(&file_name) else {
+            continue;
+        };
+        snapshots.push(Snapshot {
+            path: entry.path().to_string_lossy().to_string(),
+            size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+            created_at: created_at.to_rfc3339(),
+            file_name,
+        });
+    }
+
+    // RFC 3339 timestamps in UTC sort chronologically
+    snapshots.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(snapshots)
+}
+
+/// Take a snapshot into the snapshot folder and apply the retention policy
+pub fn take_snapshot() -> Result<Backup, String> {
+    let mut backup = write_snapshot()?;
+    backup.pruned = prune_snapshots(&snapshot_dir()?, load_schedule().keep)?;
+    Ok(backup)
+}
+
+fn write_snapshot() -> Result<Backup, String> {
+    let dir = snapshot_dir()?;
+    let file_name = format!(
+        "{}{}.db",
+        SNAPSHOT_PREFIX,
+        chrono::Utc::now().format(SNAPSHOT_TIME_FORMAT)
+    );
+    let path = dir.join(&file_name);
+    if path.exists() {
+        return Err(format!("Snapshot {} already exists", file_name));
+    }
+
+    backup_database(&path)
+}
+
+/// Delete all but the newest `keep` snapshots in `dir`, returning the deleted file names
+fn prune_snapshots(dir: &Path, keep: usize) -> Result<Vec<String>, String> {
+    let mut pruned = Vec::new();
+    for snapshot in snapshots_in(dir)?.into_iter().skip(keep.max(1)) {
+        match fs::remove_file(&snapshot.path) {
+            Ok(()) => pruned.push(snapshot.file_name),
+            Err(e) => eprintln!("Failed to delete snapshot {}: {}", snapshot.path, e),
+        }
+    }
+    Ok(pruned)
+}
+
+fn snapshot_due(dir: &Path, schedule: &BackupSchedule) -> Result<bool, String> {
+    if !schedule.enabled {
+        return Ok(false);
+    }
+    let interval = chrono::Duration::hours(i64::from(schedule.interval_hours.max(1)));
+    let latest = snapshots_in(dir)?
+        .first()
+        .and_then(|snapshot| snapshot_time(&snapshot.file_name));
+    Ok(latest.is_none_or(|time| chrono::Utc::now() - time >= interval))
+}
+
+/// Start the background thread taking scheduled snapshots (called once the database is open)
+pub fn start_snapshots() {
+    std::thread::Builder::new()
+        .name("db-snapshots".to_string())
+        .spawn(|| loop {
+            match snapshot_dir().and_then(|dir| snapshot_due(&dir, &load_schedule())) {
+                Ok(true) => {
+                    if let Err(e) = take_snapshot() {
+                        eprintln!("Scheduled database snapshot failed: {}", e);
+                    }
+                }
+                Ok(false) => {}
+                Err(e) => eprintln!("Failed to check database snapshots: {}", e),
+            }
+            std::thread::sleep(SCHEDULER_INTERVAL);
+        })
+        .expect("Failed to start snapshot thread");
+}
+
+/// Restore the live database from a backup or snapshot. The backup must pass the
+/// integrity check and may not contain migrations this build does not know; the
+/// current database is snapshotted first, then overwritten in place through the
+/// backup API so pooled connections see the restored data.
+pub fn restore_database(backup_path: &Path) -> Result<Restore, String> {
+    if !backup_path.is_file() {
+        return Err(format!("Backup not found: {}", backup_path.display()));
+    }
+    integrity_check(backup_path)?;
+
+    let versions = applied_versions(backup_path)?;
+    let embedded = embedded_versions()?;
+    let unknown: Vec<&String> = versions.iter().filter(|v| !embedded.contains(*v)).collect();
+    if !unknown.is_empty() {
+        return Err(format!(
+            "Backup was made by a newer version of the app (unknown migrations: {})",
+            unknown
+                .iter()
+                .map(|v| v.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    // Pruned only after the restore, which may be reading the oldest snapshot
+    let safety = write_snapshot()?;
+
+    {
+        let source = RawDb::open(backup_path, ffi::SQLITE_OPEN_READONLY)?;
+        let live = RawDb::open(&db_path(app_dir()), ffi::SQLITE_OPEN_READWRITE)?;
+        copy_database(&source, &live).map_err(|e| format!("Failed to restore database: {}", e))?;
+    }
+
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+    let migrations_applied = conn
+        .run_pending_migrations(MIGRATIONS)
+        .map_err(|e| format!("Migration error: {}", e))?
+        .len();
+
+    if let Err(e) = snapshot_dir().and_then(|dir| prune_snapshots(&dir, load_schedule().keep)) {
+        eprintln!("Failed to prune database snapshots: {}", e);
+    }
+
+    Ok(Restore {
+        restored_from: backup_path.to_string_lossy().to_string(),
+        schema_version: versions.last().cloned(),
+        migrations_applied,
+        safety_snapshot: safety.path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_time() {
+        let time = snapshot_time("spexor_20260301120000.db").unwrap();
+        assert_eq!(time.to_rfc3339(), "2026-03-01T12:00:00+00:00");
+        assert!(snapshot_time("spexor_20260301120000.db.partial").is_none());
+        assert!(snapshot_time("notes.db").is_none());
+    }
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("spexor_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn snapshot_name(age: chrono::Duration) -> String {
+        format!(
+            "{}{}.db",
+            SNAPSHOT_PREFIX,
+            (chrono::Utc::now() - age).format(SNAPSHOT_TIME_FORMAT)
+        )
+    }
+
+    #[test]
+    fn test_prune_snapshots() {
+        let dir = test_dir("prune");
+        let names: Vec<String> = (1..=4)
+            .map(|day| snapshot_name(chrono::Duration::days(day)))
+            .collect();
+        for name in &names {
+            fs::write(dir.join(name), b"").unwrap();
+        }
+        fs::write(dir.join("notes.db"), b"").unwrap();
+
+        let pruned = prune_snapshots(&dir, 2).unwrap();
+        assert_eq!(pruned, names[2..]);
+        assert_eq!(snapshots_in(&dir).unwrap().len(), 2);
+        assert!(dir.join("notes.db").exists());
+
+        // At least one snapshot is always kept
+        assert_eq!(prune_snapshots(&dir, 0).unwrap().len(), 1);
+        assert_eq!(snapshots_in(&dir).unwrap().len(), 1);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_snapshot_due() {
+        let dir = test_dir("due");
+        let schedule = BackupSchedule {
+            enabled: true,
+            interval_hours: 24,
+            keep: 7,
+        };
+
+        assert!(snapshot_due(&dir, &schedule).unwrap());
+
+        fs::write(dir.join(snapshot_name(chrono::Duration::hours(30))), b"").unwrap();
+        assert!(snapshot_due(&dir, &schedule).unwrap());
+
+        let recent = dir.join(snapshot_name(chrono::Duration::hours(2)));
+        fs::write(&recent, b"").unwrap();
+        assert!(!snapshot_due(&dir, &schedule).unwrap());
+
+        let disabled = BackupSchedule {
+            enabled: false,
+            ..schedule
+        };
+        fs::remove_file(&recent).unwrap();
+        assert!(!snapshot_due(&dir, &disabled).unwrap());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_restore_rejects_unknown_migrations() {
+        let dir = test_dir("restore");
+        let backup = dir.join("backup.db");
+        {
+            let mut conn = SqliteConnection::establish(&backup.to_string_lossy()).unwrap();
+            conn.run_pending_migrations(MIGRATIONS).unwrap();
+            conn.batch_execute(
+                "INSERT INTO __diesel_schema_migrations (version) VALUES ('99991231000000');",
+            )
+            .unwrap();
+        }
+
+        let err = restore_database(&backup).unwrap_err();
+        assert!(
+            err.contains("unknown migrations: 99991231000000"),
+            "{}",
+            err
+        );
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
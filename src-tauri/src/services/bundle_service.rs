@@ -0,0 +1,1292 @@
+//! Bundle service: signed, self-contained case archives for moving cases between
+//! air-gapped workstations
+use crate::models::{
+    Annotation, AudioFile, Bookmark, Case, NewAnnotation, NewAudioFile, NewBookmark,
+    NewBookmarkSegment, NewCase, NewCaseMember, NewRedaction, NewSpeaker, NewTranscriptSegment,
+    NewVoiceSample, Redaction, Speaker, TranscriptSegment, User, VoiceSample,
+};
+use crate::schema::{
+    annotations, audio_files, bookmark_segments, bookmarks, case_members, cases, redactions,
+    speakers, transcript_segments, users, voice_samples,
+};
+use crate::services::access_service::CaseRole;
+use crate::services::activity_service::log_activity;
+use crate::services::backup_service::embedded_versions;
+use crate::services::encryption_service::{open_plaintext, seal_stored_file};
+use crate::services::storage::{sha256_file, sha256_hex, storage_dir};
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_migrations::MigrationHarness;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Layout version of `data.json`; bumped whenever rows change shape
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const SIGNATURE_ENTRY: &str = "signature.json";
+const DATA_ENTRY: &str = "data.json";
+
+/// Signed table of contents: the data rows and every file, by SHA-256
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format_version: u32,
+    pub app_version: String,
+    /// Latest database migration of the exporting workstation
+    #[serde(default)]
+    pub schema_version: Option<String>,
+    pub created_at: NaiveDateTime,
+    /// Username of the exporting user
+    pub exported_by: Option<String>,
+    pub case_code: String,
+    pub data_sha256: String,
+    pub files: Vec<BundleFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleFile {
+    /// Entry name inside the archive
+    pub path: String,
+    pub sha256: String,
+    pub size: u64,
+}
+
+/// Ed25519 signature of the exact bytes of `manifest.json`
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleSignature {
+    public_key: String,
+    signature: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleCase {
+    id: i32,
+    code: String,
+    title: String,
+    description: Option<String>,
+    status: String,
+    priority: String,
+    tags: String,
+    due_date: Option<chrono::NaiveDate>,
+    created_at: NaiveDateTime,
+}
+
+/// Users are matched by username on import
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleUser {
+    id: i32,
+    username: String,
+    name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleSpeaker {
+    id: i32,
+    name: String,
+    alias: Option<String>,
+    gender: Option<String>,
+    age_estimate: Option<String>,
+    notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleVoiceSample {
+    id: i32,
+    speaker_id: i32,
+    file_name: String,
+    duration: f32,
+    /// Archive entry holding the sample
+    file: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleAudioFile {
+    id: i32,
+    file_name: String,
+    duration: f32,
+    status: String,
+    channels: i32,
+    sha256: String,
+    recorded_at: Option<NaiveDateTime>,
+    recorded_tz: Option<String>,
+    recorded_at_source: Option<String>,
+    created_at: NaiveDateTime,
+    /// Archive entry holding the recording
+    file: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleSegment {
+    id: i32,
+    audio_file_id: i32,
+    speaker_id: Option<i32>,
+    start_time: f32,
+    end_time: f32,
+    text: String,
+    is_deleted: i32,
+    deleted_at: Option<NaiveDateTime>,
+    words: Option<String>,
+    channel: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleAnnotation {
+    id: i32,
+    audio_file_id: i32,
+    segment_id: Option<i32>,
+    start_time: f32,
+    end_time: f32,
+    author_id: Option<i32>,
+    body: String,
+    tags: String,
+    color: Option<String>,
+    is_resolved: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleRedaction {
+    id: i32,
+    audio_file_id: i32,
+    start_time: f32,
+    end_time: f32,
+    mode: String,
+    reason: String,
+    created_by: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleBookmark {
+    id: i32,
+    audio_file_id: i32,
+    start_time: f32,
+    end_time: f32,
+    title: String,
+    description: Option<String>,
+    severity: String,
+    created_by: Option<i32>,
+    segment_ids: Vec<i32>,
+}
+
+/// Rows of `data.json`, with the source workstation's ids
+#[derive(Debug, Serialize, Deserialize)]
+struct BundleData {
+    case: BundleCase,
+    users: Vec<BundleUser>,
+    speakers: Vec<BundleSpeaker>,
+    voice_samples: Vec<BundleVoiceSample>,
+    audio_files: Vec<BundleAudioFile>,
+    transcript_segments: Vec<BundleSegment>,
+    annotations: Vec<BundleAnnotation>,
+    redactions: Vec<BundleRedaction>,
+    bookmarks: Vec<BundleBookmark>,
+}
+
+/// What to do when the target already has a case with the bundle's code
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaseConflictPolicy {
+    /// Import as a new case with a suffixed code, e.g. "VV-001-2"
+    #[default]
+    Rename,
+    /// Add the bundle's recordings to the existing case, skipping ones it already has
+    Merge,
+    /// Refuse the import
+    Fail,
+}
+
+/// What to do with a bundle speaker whose name matches an existing speaker
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SpeakerConflictPolicy {
+    /// Attribute the segments to the existing speaker
+    #[default]
+    Reuse,
+    /// Create a separate speaker anyway
+    Duplicate,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct BundleImportOptions {
+    pub case_conflict: CaseConflictPolicy,
+    pub speaker_conflict: SpeakerConflictPolicy,
+    /// Public keys (hex) of the workstations whose bundles are accepted, besides this one
+    pub trusted_keys: Vec<String>,
+}
+
+/// A collision met during import and how it was resolved
+#[derive(Debug, Clone, Serialize)]
+pub struct BundleConflict {
+    /// "case_code", "speaker", "audio_file", "voice_sample" or "user"
+    pub kind: String,
+    /// Id in the bundle
+    pub source_id: Option<i32>,
+    /// Id of the existing row it was matched to
+    pub target_id: Option<i32>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BundleExport {
+    pub file_path: String,
+    pub sha256: String,
+    /// Hex Ed25519 key that signed the bundle
+    pub public_key: String,
+    pub audio_files: usize,
+    pub transcript_segments: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BundleImport {
+    pub case: Case,
+    /// True when the recordings were merged into an existing case
+    pub merged: bool,
+    pub public_key: String,
+    pub exported_by: Option<String>,
+    pub audio_files: usize,
+    pub transcript_segments: usize,
+    pub annotations: usize,
+    pub redactions: usize,
+    pub bookmarks: usize,
+    pub voice_samples: usize,
+    pub speakers_created: usize,
+    pub speakers_reused: usize,
+    pub conflicts: Vec<BundleConflict>,
+    /// Audio files created, for post-processing by the caller
+    #[serde(skip)]
+    pub new_audio_ids: Vec<i32>,
+}
+
+/// This workstation's bundle signing key, created on first use
+pub fn signing_key() -> Result<SigningKey, String> {
+    let path = storage_dir("keys")
+        .map_err(|e| e.to_string())?
+        .join("bundle_signing.key");
+
+    if path.exists() {
+        let raw = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read signing key: {}", e))?;
+        let bytes: [u8; 32] = hex::decode(raw.trim())
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| "Bundle signing key is corrupt".to_string())?;
+        return Ok(SigningKey::from_bytes(&bytes));
+    }
+
+    let mut secret = [0u8; 32];
+    OsRng.fill_bytes(&mut secret);
+    let key = SigningKey::from_bytes(&secret);
+    std::fs::write(&path, hex::encode(key.to_bytes()))
+        .map_err(|e| format!("Failed to save signing key: {}", e))?;
+    Ok(key)
+}
+
+pub fn public_key_hex(key: &SigningKey) -> String {
+    hex::encode(key.verifying_key().to_bytes())
+}
+
+/// A file name safe to use as a single path component
+fn safe_file_name(name: &str) -> String {
+    let base = Path::new(name)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let cleaned: String = base
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if cleaned.trim_matches('.').is_empty() {
+        "file".to_string()
+    } else {
+        cleaned
+    }
+}
+
+/// Copy `reader` to `writer`, returning the SHA-256 and byte count of what was copied
+fn copy_hashed(reader: &mut impl Read, writer: &mut impl Write) -> std::io::Result<(String, u64)> {
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    let mut size = 0u64;
+
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        writer.write_all(&buffer[..read])?;
+        size += read as u64;
+    }
+
+    Ok((hex::encode(hasher.finalize()), size))
+}
+
+struct BundleWriter {
+    zip: zip::ZipWriter<File>,
+    files: Vec<BundleFile>,
+}
+
+impl BundleWriter {
+    fn add_file(&mut self, entry: &str, source: &Path) -> Result<BundleFile, String> {
+        let mut input = open_plaintext(source)
+            .map_err(|e| format!("Failed to open {}: {}", source.display(), e))?;
+        let large = std::fs::metadata(source).map(|m| m.len()).unwrap_or(0) >= u32::MAX as u64;
+        // Audio is already compressed
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored)
+            .large_file(large);
+
+        self.zip
+            .start_file(entry, options)
+            .map_err(|e| format!("Failed to write bundle: {}", e))?;
+        let (sha256, size) = copy_hashed(&mut input, &mut self.zip)
+            .map_err(|e| format!("Failed to write bundle: {}", e))?;
+
+        let file = BundleFile {
+            path: entry.to_string(),
+            sha256,
+            size,
+        };
+        self.files.push(file.clone());
+        Ok(file)
+    }
+
+    fn add_bytes(&mut self, entry: &str, bytes: &[u8]) -> Result<(), String> {
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+        self.zip
+            .start_file(entry, options)
+            .map_err(|e| format!("Failed to write bundle: {}", e))?;
+        self.zip
+            .write_all(bytes)
+            .map_err(|e| format!("Failed to write bundle: {}", e))
+    }
+}
+
+/// Write a case, its recordings and everything attached to them into a signed archive
+pub fn export_case_bundle(
+    conn: &mut SqliteConnection,
+    case_id: i32,
+    user_id: Option<i32>,
+    output_path: &Path,
+) -> Result<BundleExport, String> {
+    let case = cases::table
+        .find(case_id)
+        .first::<Case>(conn)
+        .map_err(|e| format!("Case not found: {}", e))?;
+    let files = audio_files::table
+        .filter(audio_files::case_id.eq(case_id))
+        .order(audio_files::id.asc())
+        .load::<AudioFile>(conn)
+        .map_err(|e| e.to_string())?;
+    let file_ids: Vec<i32> = files.iter().map(|audio| audio.id).collect();
+
+    let load_error = |e: diesel::result::Error| e.to_string();
+    let segments = transcript_segments::table
+        .filter(transcript_segments::audio_file_id.eq_any(&file_ids))
+        .order(transcript_segments::id.asc())
+        .load::<TranscriptSegment>(conn)
+        .map_err(load_error)?;
+    let speaker_ids: HashSet<i32> = segments.iter().filter_map(|s| s.speaker_id).collect();
+    let speaker_rows = speakers::table
+        .filter(speakers::id.eq_any(speaker_ids))
+        .order(speakers::id.asc())
+        .load::<Speaker>(conn)
+        .map_err(load_error)?;
+    let samples = voice_samples::table
+        .filter(voice_samples::speaker_id.eq_any(speaker_rows.iter().map(|s| s.id)))
+        .order(voice_samples::id.asc())
+        .load::<VoiceSample>(conn)
+        .map_err(load_error)?;
+    let annotation_rows = annotations::table
+        .filter(annotations::audio_file_id.eq_any(&file_ids))
+        .order(annotations::id.asc())
+        .load::<Annotation>(conn)
+        .map_err(load_error)?;
+    let redaction_rows = redactions::table
+        .filter(redactions::audio_file_id.eq_any(&file_ids))
+        .order(redactions::id.asc())
+        .load::<Redaction>(conn)
+        .map_err(load_error)?;
+    let bookmark_rows = bookmarks::table
+        .filter(bookmarks::audio_file_id.eq_any(&file_ids))
+        .order(bookmarks::id.asc())
+        .load::<Bookmark>(conn)
+        .map_err(load_error)?;
+    let bookmark_links = bookmark_segments::table
+        .filter(bookmark_segments::bookmark_id.eq_any(bookmark_rows.iter().map(|b| b.id)))
+        .select((
+            bookmark_segments::bookmark_id,
+            bookmark_segments::segment_id,
+        ))
+        .load::<(i32, i32)>(conn)
+        .map_err(load_error)?;
+
+    let user_ids: HashSet<i32> = annotation_rows
+        .iter()
+        .filter_map(|a| a.author_id)
+        .chain(redaction_rows.iter().filter_map(|r| r.created_by))
+        .chain(bookmark_rows.iter().filter_map(|b| b.created_by))
+        .chain(user_id)
+        .collect();
+    let user_rows = users::table
+        .filter(users::id.eq_any(user_ids))
+        .load::<User>(conn)
+        .map_err(load_error)?;
+    let exported_by = user_rows
+        .iter()
+        .find(|user| Some(user.id) == user_id)
+        .map(|user| user.username.clone());
+
+    let output =
+        File::create(output_path).map_err(|e| format!("Failed to create bundle: {}", e))?;
+    let mut writer = BundleWriter {
+        zip: zip::ZipWriter::new(output),
+        files: Vec::new(),
+    };
+
+    let mut bundle_files = Vec::new();
+    for audio in &files {
+        let entry = format!("audio/{}_{}", audio.id, safe_file_name(&audio.file_name));
+        let file = writer.add_file(&entry, Path::new(&audio.file_path))?;
+        if audio
+            .sha256
+            .as_deref()
+            .is_some_and(|known| known != file.sha256)
+        {
+            return Err(format!(
+                "{} no longer matches its recorded SHA-256; refusing to export",
+                audio.file_name
+            ));
+        }
+        bundle_files.push(BundleAudioFile {
+            id: audio.id,
+            file_name: audio.file_name.clone(),
+            duration: audio.duration,
+            status: audio.status.clone(),
+            channels: audio.channels,
+            sha256: file.sha256,
+            recorded_at: audio.recorded_at,
+            recorded_tz: audio.recorded_tz.clone(),
+            recorded_at_source: audio.recorded_at_source.clone(),
+            created_at: audio.created_at,
+            file: entry,
+        });
+    }
+
+    let mut bundle_samples = Vec::new();
+    for sample in &samples {
+        let entry = format!(
+            "voice_samples/{}_{}",
+            sample.id,
+            safe_file_name(&sample.file_name)
+        );
+        writer.add_file(&entry, Path::new(&sample.file_path))?;
+        bundle_samples.push(BundleVoiceSample {
+            id: sample.id,
+            speaker_id: sample.speaker_id,
+            file_name: sample.file_name.clone(),
+            duration: sample.duration,
+            file: entry,
+        });
+    }
+
+    let data = BundleData {
+        case: BundleCase {
+            id: case.id,
+            code: case.code.clone(),
+            title: case.title.clone(),
+            description: case.description.clone(),
+            status: case.status.clone(),
+            priority: case.priority.clone(),
+            tags: case.tags.clone(),
+            due_date: case.due_date,
+            created_at: case.created_at,
+        },
+        users: user_rows
+            .iter()
+            .map(|user| BundleUser {
+                id: user.id,
+                username: user.username.clone(),
+                name: user.name.clone(),
+            })
+            .collect(),
+        speakers: speaker_rows
+            .into_iter()
+            .map(|speaker| BundleSpeaker {
+                id: speaker.id,
+                name: speaker.name,
+                alias: speaker.alias,
+                gender: speaker.gender,
+                age_estimate: speaker.age_estimate,
+                notes: speaker.notes,
+            })
+            .collect(),
+        voice_samples: bundle_samples,
+        audio_files: bundle_files,
+        transcript_segments: segments
+            .into_iter()
+            .map(|segment| BundleSegment {
+                id: segment.id,
+                audio_file_id: segment.audio_file_id,
+                speaker_id: segment.speaker_id,
+                start_time: segment.start_time,
+                end_time: segment.end_time,
+                text: segment.text,
+                is_deleted: segment.is_deleted,
+                deleted_at: segment.deleted_at,
+                words: segment.words,
+                channel: segment.channel,
+            })
+            .collect(),
+        annotations: annotation_rows
+            .into_iter()
+            .map(|annotation| BundleAnnotation {
+                id: annotation.id,
+                audio_file_id: annotation.audio_file_id,
+                segment_id: annotation.segment_id,
+                start_time: annotation.start_time,
+                end_time: annotation.end_time,
+                author_id: annotation.author_id,
+                body: annotation.body,
+                tags: annotation.tags,
+                color: annotation.color,
+                is_resolved: annotation.is_resolved,
+            })
+            .collect(),
+        redactions: redaction_rows
+            .into_iter()
+            .map(|redaction| BundleRedaction {
+                id: redaction.id,
+                audio_file_id: redaction.audio_file_id,
+                start_time: redaction.start_time,
+                end_time: redaction.end_time,
+                mode: redaction.mode,
+                reason: redaction.reason,
+                created_by: redaction.created_by,
+            })
+            .collect(),
+        bookmarks: bookmark_rows
+            .into_iter()
+            .map(|bookmark| BundleBookmark {
+                segment_ids: bookmark_links
+                    .iter()
+                    .filter(|(bookmark_id, _)| *bookmark_id == bookmark.id)
+                    .map(|(_, segment_id)| *segment_id)
+                    .collect(),
+                id: bookmark.id,
+                audio_file_id: bookmark.audio_file_id,
+                start_time: bookmark.start_time,
+                end_time: bookmark.end_time,
+                title: bookmark.title,
+                description: bookmark.description,
+                severity: bookmark.severity,
+                created_by: bookmark.created_by,
+            })
+            .collect(),
+    };
+
+    let data_json = serde_json::to_vec_pretty(&data).map_err(|e| e.to_string())?;
+    writer.add_bytes(DATA_ENTRY, &data_json)?;
+
+    let schema_version = conn
+        .applied_migrations()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .max()
+        .map(|version| version.to_string());
+    let manifest = BundleManifest {
+        format_version: BUNDLE_FORMAT_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        schema_version,
+        created_at: chrono::Utc::now().naive_utc(),
+        exported_by,
+        case_code: case.code.clone(),
+        data_sha256: sha256_hex(&data_json),
+        files: std::mem::take(&mut writer.files),
+    };
+    let manifest_json = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    writer.add_bytes(MANIFEST_ENTRY, &manifest_json)?;
+
+    let key = signing_key()?;
+    let signature = BundleSignature {
+        public_key: public_key_hex(&key),
+        signature: hex::encode(key.sign(&manifest_json).to_bytes()),
+    };
+    let signature_json = serde_json::to_vec_pretty(&signature).map_err(|e| e.to_string())?;
+    writer.add_bytes(SIGNATURE_ENTRY, &signature_json)?;
+
+    writer
+        .zip
+        .finish()
+        .map_err(|e| format!("Failed to write bundle: {}", e))?;
+
+    Ok(BundleExport {
+        file_path: output_path.to_string_lossy().to_string(),
+        sha256: sha256_file(output_path).map_err(|e| format!("Failed to hash bundle: {}", e))?,
+        public_key: signature.public_key,
+        audio_files: data.audio_files.len(),
+        transcript_segments: data.transcript_segments.len(),
+    })
+}
+
+fn read_entry(archive: &mut zip::ZipArchive<File>, name: &str) -> Result<Vec<u8>, String> {
+    let mut entry = archive
+        .by_name(name)
+        .map_err(|_| format!("Bundle is missing {}", name))?;
+    let mut bytes = Vec::new();
+    entry
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read {}: {}", name, e))?;
+    Ok(bytes)
+}
+
+/// Check the signature of the manifest, returning the signer's public key.
+/// Only signers listed in `trusted_keys` are accepted.
+fn verify_signature(
+    manifest_json: &[u8],
+    signature_json: &[u8],
+    trusted_keys: &[String],
+) -> Result<String, String> {
+    let signature: BundleSignature =
+        serde_json::from_slice(signature_json).map_err(|e| format!("Invalid signature: {}", e))?;
+
+    let key_bytes: [u8; 32] = hex::decode(&signature.public_key)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| "Invalid signer key".to_string())?;
+    let key = VerifyingKey::from_bytes(&key_bytes).map_err(|_| "Invalid signer key".to_string())?;
+    let signature_bytes =
+        hex::decode(&signature.signature).map_err(|_| "Invalid signature".to_string())?;
+    let bundle_signature =
+        Signature::from_slice(&signature_bytes).map_err(|_| "Invalid signature".to_string())?;
+
+    key.verify(manifest_json, &bundle_signature)
+        .map_err(|_| "Bundle signature does not match its manifest".to_string())?;
+
+    let trusted = trusted_keys
+        .iter()
+        .any(|k| k.trim().eq_ignore_ascii_case(&signature.public_key));
+    if !trusted {
+        return Err(format!(
+            "Bundle was signed by an untrusted key: {}; add it to the trusted keys to import",
+            signature.public_key
+        ));
+    }
+    Ok(signature.public_key)
+}
+
+/// Extract a manifest entry to `destination`, checking its SHA-256 on the way
+fn extract_file(
+    archive: &mut zip::ZipArchive<File>,
+    file: &BundleFile,
+    destination: &Path,
+) -> Result<(), String> {
+    let mut entry = archive
+        .by_name(&file.path)
+        .map_err(|_| format!("Bundle is missing {}", file.path))?;
+    if let Some(parent) = destination.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let mut output =
+        File::create(destination).map_err(|e| format!("Failed to extract {}: {}", file.path, e))?;
+    let (sha256, _) = copy_hashed(&mut entry, &mut output)
+        .map_err(|e| format!("Failed to extract {}: {}", file.path, e))?;
+
+    if sha256 != file.sha256 {
+        return Err(format!("{} is corrupt (SHA-256 mismatch)", file.path));
+    }
+    Ok(())
+}
+
+/// First free code of the form "<code>-2", "<code>-3", ...
+fn free_case_code(conn: &mut SqliteConnection, code: &str) -> QueryResult<String> {
+    let taken: HashSet<String> = cases::table
+        .filter(cases::code.like(format!("{}-%", code)))
+        .select(cases::code)
+        .load::<String>(conn)?
+        .into_iter()
+        .collect();
+    Ok((2..)
+        .map(|n| format!("{}-{}", code, n))
+        .find(|candidate| !taken.contains(candidate))
+        .expect("unbounded range"))
+}
+
+fn last_insert_id(conn: &mut SqliteConnection) -> QueryResult<i32> {
+    diesel::select(diesel::dsl::sql::<diesel::sql_types::Integer>(
+        "last_insert_rowid()",
+    ))
+    .get_result(conn)
+}
+
+fn conflict(
+    kind: &str,
+    source_id: Option<i32>,
+    target_id: Option<i32>,
+    message: String,
+) -> BundleConflict {
+    BundleConflict {
+        kind: kind.to_string(),
+        source_id,
+        target_id,
+        message,
+    }
+}
+
+/// Verify and import a case bundle. Files are checked against the signed manifest before
+/// anything is written to the database; ids are remapped and collisions resolved per `options`.
+pub fn import_case_bundle(
+    conn: &mut SqliteConnection,
+    bundle_path: &Path,
+    user_id: Option<i32>,
+    options: &BundleImportOptions,
+) -> Result<BundleImport, String> {
+    let file = File::open(bundle_path).map_err(|e| format!("Failed to open bundle: {}", e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("Not a case bundle: {}", e))?;
+
+    let manifest_json = read_entry(&mut archive, MANIFEST_ENTRY)?;
+    let signature_json = read_entry(&mut archive, SIGNATURE_ENTRY)?;
+    let mut trusted_keys = options.trusted_keys.clone();
+    trusted_keys.push(public_key_hex(&signing_key()?));
+    let public_key = verify_signature(&manifest_json, &signature_json, &trusted_keys)?;
+
+    let manifest: BundleManifest =
+        serde_json::from_slice(&manifest_json).map_err(|e| format!("Invalid manifest: {}", e))?;
+    if manifest.format_version > BUNDLE_FORMAT_VERSION {
+        return Err(format!(
+            "Bundle format {} is newer than this version supports ({})",
+            manifest.format_version, BUNDLE_FORMAT_VERSION
+        ));
+    }
+    let known_versions = embedded_versions()?;
+    let unknown_version = manifest
+        .schema_version
+        .as_ref()
+        .filter(|version| !known_versions.contains(*version));
+    if let Some(version) = unknown_version {
+        return Err(format!(
+            "Bundle was exported from a newer database (migration {}); update SPEXOR first",
+            version
+        ));
+    }
+
+    let data_json = read_entry(&mut archive, DATA_ENTRY)?;
+    if sha256_hex(&data_json) != manifest.data_sha256 {
+        return Err("Bundle data does not match its manifest".to_string());
+    }
+    let data: BundleData =
+        serde_json::from_slice(&data_json).map_err(|e| format!("Invalid bundle data: {}", e))?;
+
+    let manifest_files: HashMap<&str, &BundleFile> = manifest
+        .files
+        .iter()
+        .map(|file| (file.path.as_str(), file))
+        .collect();
+    let signed_file = |path: &str| {
+        manifest_files
+            .get(path)
+            .copied()
+            .ok_or_else(|| format!("{} is not listed in the manifest", path))
+    };
+    for audio in &data.audio_files {
+        if signed_file(&audio.file)?.sha256 != audio.sha256 {
+            return Err(format!(
+                "{} does not match its manifest entry",
+                audio.file_name
+            ));
+        }
+    }
+    for sample in &data.voice_samples {
+        signed_file(&sample.file)?;
+    }
+
+    let mut conflicts = Vec::new();
+
+    // Target case
+    let existing_case = cases::table
+        .filter(cases::code.eq(&data.case.code))
+        .first::<Case>(conn)
+        .optional()
+        .map_err(|e| e.to_string())?;
+    let merge_into = match (&existing_case, options.case_conflict) {
+        (None, _) => None,
+        (Some(case), CaseConflictPolicy::Fail) => {
+            return Err(format!("Case {} already exists", case.code))
+        }
+        (Some(case), CaseConflictPolicy::Merge) => {
+            crate::services::access_service::require_case_role(
+                conn,
+                user_id,
+                case.id,
+                CaseRole::Editor,
+            )?;
+            conflicts.push(conflict(
+                "case_code",
+                Some(data.case.id),
+                Some(case.id),
+                format!("Merged into existing case {}", case.code),
+            ));
+            Some(case.clone())
+        }
+        (Some(_), CaseConflictPolicy::Rename) => None,
+    };
+    let case_code = match (&existing_case, &merge_into) {
+        (Some(case), None) => {
+            let code = free_case_code(conn, &case.code).map_err(|e| e.to_string())?;
+            conflicts.push(conflict(
+                "case_code",
+                Some(data.case.id),
+                Some(case.id),
+                format!("Case {} exists; imported as {}", case.code, code),
+            ));
+            code
+        }
+        _ => data.case.code.clone(),
+    };
+
+    // Recordings the merge target already holds
+    let mut skipped_audio: HashSet<i32> = HashSet::new();
+    if let Some(case) = &merge_into {
+        // Older rows have no stored hash; hash their files instead
+        let known: HashMap<String, i32> = audio_files::table
+            .filter(audio_files::case_id.eq(case.id))
+            .load::<AudioFile>(conn)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .filter_map(|audio| {
+                let sha256 = audio
+                    .sha256
+                    .or_else(|| sha256_file(Path::new(&audio.file_path)).ok())?;
+                Some((sha256, audio.id))
+            })
+            .collect();
+        for audio in &data.audio_files {
+            if let Some(existing_id) = known.get(&audio.sha256) {
+                skipped_audio.insert(audio.id);
+                conflicts.push(conflict(
+                    "audio_file",
+                    Some(audio.id),
+                    Some(*existing_id),
+                    format!("{} is already in the case; skipped", audio.file_name),
+                ));
+            }
+        }
+    }
+
+    // Speakers matched by name
+    let existing_speakers = speakers::table
+        .load::<Speaker>(conn)
+        .map_err(|e| e.to_string())?;
+    let mut reused_speakers: HashMap<i32, i32> = HashMap::new();
+    if options.speaker_conflict == SpeakerConflictPolicy::Reuse {
+        for speaker in &data.speakers {
+            let name = speaker.name.trim().to_lowercase();
+            if let Some(existing) = existing_speakers
+                .iter()
+                .find(|s| s.name.trim().to_lowercase() == name)
+            {
+                reused_speakers.insert(speaker.id, existing.id);
+                conflicts.push(conflict(
+                    "speaker",
+                    Some(speaker.id),
+                    Some(existing.id),
+                    format!("Speaker {} matched an existing speaker", speaker.name),
+                ));
+            }
+        }
+    }
+
+    // Voice samples a reused speaker already has
+    let mut skipped_samples: HashSet<i32> = HashSet::new();
+    for sample in &data.voice_samples {
+        let Some(target) = reused_speakers.get(&sample.speaker_id) else {
+            continue;
+        };
+        let known_hashes: HashSet<String> = voice_samples::table
+            .filter(voice_samples::speaker_id.eq(target))
+            .select(voice_samples::file_path)
+            .load::<String>(conn)
+            .map_err(|e| e.to_string())?
+            .iter()
+            .filter_map(|path| sha256_file(Path::new(path)).ok())
+            .collect();
+        if known_hashes.contains(&signed_file(&sample.file)?.sha256) {
+            skipped_samples.insert(sample.id);
+            conflicts.push(conflict(
+                "voice_sample",
+                Some(sample.id),
+                Some(*target),
+                format!(
+                    "{} is already a sample of this speaker; skipped",
+                    sample.file_name
+                ),
+            ));
+        }
+    }
+
+    // Users matched by username
+    let local_users: HashMap<String, i32> = users::table
+        .select((users::username, users::id))
+        .load::<(String, i32)>(conn)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .collect();
+    let mut user_map: HashMap<i32, i32> = HashMap::new();
+    for user in &data.users {
+        match local_users.get(&user.username) {
+            Some(local_id) => {
+                user_map.insert(user.id, *local_id);
+            }
+            None => conflicts.push(conflict(
+                "user",
+                Some(user.id),
+                None,
+                format!(
+                    "User {} ({}) does not exist here; their authorship is dropped",
+                    user.username, user.name
+                ),
+            )),
+        }
+    }
+
+    // Extract and verify files before touching the database
+    let destination = storage_dir("bundles")
+        .map_err(|e| e.to_string())?
+        .join(format!(
+            "{}_{}",
+            safe_file_name(&case_code),
+            chrono::Utc::now().format("%Y%m%d%H%M%S")
+        ));
+    let mut audio_paths: HashMap<i32, PathBuf> = HashMap::new();
+    let mut sample_paths: HashMap<i32, PathBuf> = HashMap::new();
+    let extracted = (|| {
+        for audio in data
+            .audio_files
+            .iter()
+            .filter(|a| !skipped_audio.contains(&a.id))
+        {
+            let path = destination.join("audio").join(format!(
+                "{}_{}",
+                audio.id,
+                safe_file_name(&audio.file_name)
+            ));
+            extract_file(&mut archive, signed_file(&audio.file)?, &path)?;
+            seal_stored_file(&path)?;
+            audio_paths.insert(audio.id, path);
+        }
+        for sample in data
+            .voice_samples
+            .iter()
+            .filter(|s| !skipped_samples.contains(&s.id))
+        {
+            let path = destination.join("voice_samples").join(format!(
+                "{}_{}",
+                sample.id,
+                safe_file_name(&sample.file_name)
+            ));
+            extract_file(&mut archive, signed_file(&sample.file)?, &path)?;
+            seal_stored_file(&path)?;
+            sample_paths.insert(sample.id, path);
+        }
+        Ok::<_, String>(())
+    })();
+    if let Err(e) = extracted {
+        let _ = std::fs::remove_dir_all(&destination);
+        return Err(e);
+    }
+
+    let result = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+        let case_id = match &merge_into {
+            Some(case) => case.id,
+            None => {
+                diesel::insert_into(cases::table)
+                    .values(&NewCase {
+                        code: case_code.clone(),
+                        title: data.case.title.clone(),
+                        description: data.case.description.clone(),
+                        status: data.case.status.clone(),
+                        priority: data.case.priority.clone(),
+                        tags: data.case.tags.clone(),
+                        due_date: data.case.due_date,
+                    })
+                    .execute(conn)?;
+                let case_id = last_insert_id(conn)?;
+                if let Some(user_id) = user_id {
+                    diesel::insert_into(case_members::table)
+                        .values(&NewCaseMember {
+                            case_id,
+                            user_id,
+                            role: CaseRole::Owner.to_string(),
+                            added_by: Some(user_id),
+                        })
+                        .execute(conn)?;
+                }
+                case_id
+            }
+        };
+
+        let mut speaker_map = reused_speakers.clone();
+        let mut speakers_created = 0;
+        for speaker in &data.speakers {
+            if speaker_map.contains_key(&speaker.id) {
+                continue;
+            }
+            diesel::insert_into(speakers::table)
+                .values(&NewSpeaker {
+                    name: speaker.name.clone(),
+                    alias: speaker.alias.clone(),
+                    gender: speaker.gender.clone(),
+                    age_estimate: speaker.age_estimate.clone(),
+                    notes: speaker.notes.clone(),
+                })
+                .execute(conn)?;
+            speaker_map.insert(speaker.id, last_insert_id(conn)?);
+            speakers_created += 1;
+        }
+
+        let mut voice_sample_count = 0;
+        for sample in &data.voice_samples {
+            let (Some(path), Some(speaker_id)) = (
+                sample_paths.get(&sample.id),
+                speaker_map.get(&sample.speaker_id),
+            ) else {
+                continue;
+            };
+            diesel::insert_into(voice_samples::table)
+                .values(&NewVoiceSample {
+                    speaker_id: *speaker_id,
+                    file_name: sample.file_name.clone(),
+                    file_path: path.to_string_lossy().to_string(),
+                    duration: sample.duration,
+                })
+                .execute(conn)?;
+            voice_sample_count += 1;
+        }
+
+        let mut audio_map: HashMap<i32, i32> = HashMap::new();
+        for audio in &data.audio_files {
+            let Some(path) = audio_paths.get(&audio.id) else {
+                continue;
+            };
+            diesel::insert_into(audio_files::table)
+                .values(&NewAudioFile {
+                    case_id,
+                    file_name: audio.file_name.clone(),
+                    file_path: path.to_string_lossy().to_string(),
+                    duration: audio.duration,
+                    status: audio.status.clone(),
+                    channels: audio.channels,
+                    sha256: Some(audio.sha256.clone()),
+                })
+                .execute(conn)?;
+            let id = last_insert_id(conn)?;
+            diesel::update(audio_files::table.find(id))
+                .set((
+                    audio_files::recorded_at.eq(audio.recorded_at),
+                    audio_files::recorded_tz.eq(&audio.recorded_tz),
+                    audio_files::recorded_at_source.eq(&audio.recorded_at_source),
+                ))
+                .execute(conn)?;
+            log_activity(
+                conn,
+                user_id,
+                "import",
+                "audio_file",
+                Some(id),
+                Some(
+                    serde_json::json!({
+                        "operation": "case_bundle",
+                        "case_id": case_id,
+                        "source_id": audio.id,
+                        "file_name": audio.file_name,
+                        "sha256": audio.sha256,
+                        "public_key": public_key,
+                    })
+                    .to_string(),
+                ),
+            )?;
+            audio_map.insert(audio.id, id);
+        }
+
+        let mut segment_map: HashMap<i32, i32> = HashMap::new();
+        for segment in &data.transcript_segments {
+            let Some(audio_file_id) = audio_map.get(&segment.audio_file_id) else {
+                continue;
+            };
+            diesel::insert_into(transcript_segments::table)
+                .values(&NewTranscriptSegment {
+                    audio_file_id: *audio_file_id,
+                    speaker_id: segment
+                        .speaker_id
+                        .and_then(|id| speaker_map.get(&id).copied()),
+                    start_time: segment.start_time,
+                    end_time: segment.end_time,
+                    text: segment.text.clone(),
+                    words: segment.words.clone(),
+                    channel: segment.channel,
+                })
+                .execute(conn)?;
+            let id = last_insert_id(conn)?;
+            if segment.is_deleted != 0 {
+                diesel::update(transcript_segments::table.find(id))
+                    .set((
+                        transcript_segments::is_deleted.eq(segment.is_deleted),
+                        transcript_segments::deleted_at.eq(segment.deleted_at),
+                    ))
+                    .execute(conn)?;
+            }
+            segment_map.insert(segment.id, id);
+        }
+
+        let mut annotation_count = 0;
+        for annotation in &data.annotations {
+            let Some(audio_file_id) = audio_map.get(&annotation.audio_file_id) else {
+                continue;
+            };
+            diesel::insert_into(annotations::table)
+                .values(&NewAnnotation {
+                    audio_file_id: *audio_file_id,
+                    segment_id: annotation
+                        .segment_id
+                        .and_then(|id| segment_map.get(&id).copied()),
+                    start_time: annotation.start_time,
+                    end_time: annotation.end_time,
+                    author_id: annotation
+                        .author_id
+                        .and_then(|id| user_map.get(&id).copied()),
+                    body: annotation.body.clone(),
+                    tags: annotation.tags.clone(),
+                    color: annotation.color.clone(),
+                })
+                .execute(conn)?;
+            if annotation.is_resolved != 0 {
+                let id = last_insert_id(conn)?;
+                diesel::update(annotations::table.find(id))
+                    .set(annotations::is_resolved.eq(annotation.is_resolved))
+                    .execute(conn)?;
+            }
+            annotation_count += 1;
+        }
+
+        let mut redaction_count = 0;
+        for redaction in &data.redactions {
+            let Some(audio_file_id) = audio_map.get(&redaction.audio_file_id) else {
+                continue;
+            };
+            diesel::insert_into(redactions::table)
+                .values(&NewRedaction {
+                    audio_file_id: *audio_file_id,
+                    start_time: redaction.start_time,
+                    end_time: redaction.end_time,
+                    mode: redaction.mode.clone(),
+                    reason: redaction.reason.clone(),
+                    created_by: redaction
+                        .created_by
+                        .and_then(|id| user_map.get(&id).copied()),
+                })
+                .execute(conn)?;
+            redaction_count += 1;
+        }
+
+        let mut bookmark_count = 0;
+        for bookmark in &data.bookmarks {
+            let Some(audio_file_id) = audio_map.get(&bookmark.audio_file_id) else {
+                continue;
+            };
+            diesel::insert_into(bookmarks::table)
+                .values(&NewBookmark {
+                    audio_file_id: *audio_file_id,
+                    start_time: bookmark.start_time,
+                    end_time: bookmark.end_time,
+                    title: bookmark.title.clone(),
+                    description: bookmark.description.clone(),
+                    severity: bookmark.severity.clone(),
+                    created_by: bookmark
+                        .created_by
+                        .and_then(|id| user_map.get(&id).copied()),
+                })
+                .execute(conn)?;
+            let bookmark_id = last_insert_id(conn)?;
+            let links: Vec<NewBookmarkSegment> = bookmark
+                .segment_ids
+                .iter()
+                .filter_map(|id| segment_map.get(id))
+                .map(|segment_id| NewBookmarkSegment {
+                    bookmark_id,
+                    segment_id: *segment_id,
+                })
+                .collect();
+            diesel::insert_into(bookmark_segments::table)
+                .values(&links)
+                .execute(conn)?;
+            bookmark_count += 1;
+        }
+
+        let case = cases::table.find(case_id).first::<Case>(conn)?;
+        Ok(BundleImport {
+            case,
+            merged: merge_into.is_some(),
+            public_key: public_key.clone(),
+            exported_by: manifest.exported_by.clone(),
+            audio_files: audio_map.len(),
+            transcript_segments: segment_map.len(),
+            annotations: annotation_count,
+            redactions: redaction_count,
+            bookmarks: bookmark_count,
+            voice_samples: voice_sample_count,
+            speakers_created,
+            speakers_reused: reused_speakers.len(),
+            conflicts: conflicts.clone(),
+            new_audio_ids: audio_map.values().copied().collect(),
+        })
+    });
+
+    result.map_err(|e| {
+        let _ = std::fs::remove_dir_all(&destination);
+        format!("Failed to import bundle: {}", e)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_and_file_names() {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let manifest = br#"{"format_version":1}"#;
+        let signature = serde_json::to_vec(&BundleSignature {
+            public_key: public_key_hex(&key),
+            signature: hex::encode(key.sign(manifest).to_bytes()),
+        })
+        .unwrap();
+
+        let trusted = [public_key_hex(&key).to_uppercase()];
+        assert_eq!(
+            verify_signature(manifest, &signature, &trusted),
+            Ok(public_key_hex(&key))
+        );
+        assert!(verify_signature(br#"{"format_version":2}"#, &signature, &trusted).is_err());
+        assert!(verify_signature(manifest, &signature, &["00".repeat(32)]).is_err());
+        assert!(verify_signature(manifest, &signature, &[]).is_err());
+
+        assert_eq!(safe_file_name("../../etc/passwd"), "passwd");
+        assert_eq!(safe_file_name("ghi âm 01.wav"), "ghi_âm_01.wav");
+        assert_eq!(safe_file_name(".."), "file");
+    }
+}
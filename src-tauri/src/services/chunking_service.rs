@@ -0,0 +1,223 @@
+//! Chunking service: overlapping windows for long recordings and stitching
+//! of the per-window transcripts back into one timeline
+use crate::models::TimeRange;
+use serde::{Deserialize, Serialize};
+
+/// Window sizes (stored with each job so a resumed job keeps its chunk plan)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChunkParams {
+    /// Length of one window in seconds
+    pub chunk_seconds: f32,
+    /// Seconds shared by consecutive windows, so no word is cut at a boundary
+    pub overlap_seconds: f32,
+}
+
+impl Default for ChunkParams {
+    fn default() -> Self {
+        Self {
+            chunk_seconds: 600.0,
+            overlap_seconds: 10.0,
+        }
+    }
+}
+
+impl ChunkParams {
+    /// Clamp user input to sane values (overlap at most a quarter of a window)
+    pub fn normalized(mut self) -> Self {
+        self.chunk_seconds = self.chunk_seconds.clamp(30.0, 3600.0);
+        self.overlap_seconds = self.overlap_seconds.clamp(0.0, self.chunk_seconds / 4.0);
+        self
+    }
+}
+
+/// A recognized segment in absolute time (seconds from the start of the recording)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkSegment {
+    pub start: f32,
+    pub end: f32,
+    pub text: String,
+}
+
+/// Split `duration` seconds into overlapping windows
+pub fn plan_chunks(duration: f32, params: &ChunkParams) -> Vec<TimeRange> {
+    let step = params.chunk_seconds - params.overlap_seconds;
+    let mut chunks = Vec::new();
+    let mut start = 0.0f32;
+
+    loop {
+        let end = (start + params.chunk_seconds).min(duration);
+        chunks.push(TimeRange { start, end });
+        if end >= duration {
+            break;
+        }
+        start += step;
+    }
+
+    chunks
+}
+
+/// Plan overlapping windows inside each of `ranges` (e.g. speech regions) only,
+/// so the gaps between them are never transcribed
+pub fn plan_chunks_in(ranges: &[TimeRange], params: &ChunkParams) -> Vec<TimeRange> {
+    ranges
+        .iter()
+        .filter(|range| range.length() > 0.0)
+        .flat_map(|range| {
+            plan_chunks(range.length(), params)
+                .into_iter()
+                .map(move |window| TimeRange {
+                    start: range.start + window.start,
+                    end: range.start + window.end,
+                })
+        })
+        .collect()
+}
+
+fn normalize_text(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// Merge per-window output (windows in order, segments in absolute time).
+///
+/// Inside an overlap, a segment belongs to the window whose half of the overlap
+/// contains its midpoint, so each utterance is kept once. Remaining repeats of
+/// the same text across a boundary are dropped and timestamps are made monotonic.
+pub fn stitch(chunks: &[(TimeRange, Vec<ChunkSegment>)]) -> Vec<ChunkSegment> {
+    let mut stitched: Vec<ChunkSegment> = Vec::new();
+
+    for (index, (range, segments)) in chunks.iter().enumerate() {
+        let lower = match index.checked_sub(1).map(|i| &chunks[i].0) {
+            Some(previous) if previous.end > range.start => (range.start + previous.end) / 2.0,
+            _ => f32::NEG_INFINITY,
+        };
+        let upper = match chunks.get(index + 1).map(|(next, _)| next) {
+            Some(next) if range.end > next.start => (next.start + range.end) / 2.0,
+            _ => f32::INFINITY,
+        };
+
+        let mut owned: Vec<&ChunkSegment> = segments
+            .iter()
+            .filter(|s| {
+                let midpoint = (s.start + s.end) / 2.0;
+                midpoint >= lower && midpoint < upper
+            })
+            .collect();
+        owned.sort_by(|a, b| a.start.total_cmp(&b.start));
+
+        for segment in owned {
+            let mut segment = segment.clone();
+            if let Some(last) = stitched.last() {
+                if segment.start < last.end
+                    && normalize_text(&segment.text) == normalize_text(&last.text)
+                {
+                    continue;
+                }
+                segment.start = segment.start.max(last.end);
+            }
+            if segment.end > segment.start && !segment.text.trim().is_empty() {
+                stitched.push(segment);
+            }
+        }
+    }
+
+    stitched
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start: f32, end: f32, text: &str) -> ChunkSegment {
+        ChunkSegment {
+            start,
+            end,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_plan_and_stitch_overlapping_chunks() {
+        let params = ChunkParams {
+            chunk_seconds: 60.0,
+            overlap_seconds: 10.0,
+        };
+        let chunks = plan_chunks(120.0, &params);
+        assert_eq!(
+            chunks,
+            vec![
+                TimeRange {
+                    start: 0.0,
+                    end: 60.0
+                },
+                TimeRange {
+                    start: 50.0,
+                    end: 110.0
+                },
+                TimeRange {
+                    start: 100.0,
+                    end: 120.0
+                },
+            ]
+        );
+
+        // "two" is recognized by both of the first windows, "three" straddles the cut
+        let output = vec![
+            (
+                chunks[0],
+                vec![segment(1.0, 4.0, "one"), segment(51.0, 54.0, "two")],
+            ),
+            (
+                chunks[1],
+                vec![
+                    segment(51.2, 54.1, " Two "),
+                    segment(54.5, 56.0, "three"),
+                    segment(104.0, 106.0, "four"),
+                ],
+            ),
+            (chunks[2], vec![segment(104.1, 106.0, "four")]),
+        ];
+
+        let texts: Vec<String> = stitch(&output).into_iter().map(|s| s.text).collect();
+        assert_eq!(texts, vec!["one", "two", "three", "four"]);
+    }
+
+    #[test]
+    fn test_plan_chunks_in_speech_ranges() {
+        let params = ChunkParams {
+            chunk_seconds: 60.0,
+            overlap_seconds: 10.0,
+        };
+        let ranges = vec![
+            TimeRange {
+                start: 5.0,
+                end: 20.0,
+            },
+            TimeRange {
+                start: 100.0,
+                end: 200.0,
+            },
+        ];
+
+        assert_eq!(
+            plan_chunks_in(&ranges, &params),
+            vec![
+                TimeRange {
+                    start: 5.0,
+                    end: 20.0
+                },
+                TimeRange {
+                    start: 100.0,
+                    end: 160.0
+                },
+                TimeRange {
+                    start: 150.0,
+                    end: 200.0
+                },
+            ]
+        );
+    }
+}
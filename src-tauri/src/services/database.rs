@@ -1,4 +1,6 @@
 //! Database service for connection pooling and migrations
+use crate::services::encryption_service;
+use diesel::connection::SimpleConnection;
 use diesel::prelude::*;
 use diesel::r2d2::{self, ConnectionManager, Pool};
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
@@ -10,15 +12,34 @@ pub type DbPool = Pool<ConnectionManager<SqliteConnection>>;
 
 static DB_POOL: OnceLock<DbPool> = OnceLock::new();
 
+/// Path of the database file in the app data directory
+pub fn db_path(app_dir: &std::path::Path) -> std::path::PathBuf {
+    app_dir.join("spexor.db")
+}
+
+/// Keys every new pooled connection of an encrypted database
+#[derive(Debug)]
+struct SqlCipherKey(String);
+
+impl r2d2::CustomizeConnection<SqliteConnection, r2d2::Error> for SqlCipherKey {
+    fn on_acquire(&self, conn: &mut SqliteConnection) -> Result<(), r2d2::Error> {
+        conn.batch_execute(&self.0).map_err(r2d2::Error::QueryError)
+    }
+}
+
 /// Initialize the database connection pool and run pending migrations
 pub fn init_db(app_dir: &std::path::Path) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let db_path = app_dir.join("spexor.db");
+    let db_path = db_path(app_dir);
     let db_url = db_path.to_str().ok_or("Invalid database path")?;
 
     println!("Initializing database at: {}", db_url);
 
     let manager = ConnectionManager::<SqliteConnection>::new(db_url);
-    let pool = r2d2::Pool::builder().max_size(5).build(manager)?;
+    let mut builder = r2d2::Pool::builder().max_size(5);
+    if let Some(pragma) = encryption_service::database_key_pragma() {
+        builder = builder.connection_customizer(Box::new(SqlCipherKey(pragma)));
+    }
+    let pool = builder.build(manager)?;
 
     // Run pending migrations
     {
@@ -73,11 +94,14 @@ fn seed_admin_user(
     Ok(())
 }
 
-/// Get a reference to the database pool
-pub fn get_pool() -> &'static DbPool {
+/// Get a reference to the database pool; Err while the encrypted store is locked
+pub fn get_pool() -> Result<&'static DbPool, String> {
+    if encryption_service::is_enabled() && !encryption_service::is_unlocked() {
+        return Err("Encrypted store is locked".to_string());
+    }
     DB_POOL
         .get()
-        .expect("Database not initialized. Call init_db first.")
+        .ok_or_else(|| "Database not initialized".to_string())
 }
 
 #[cfg(test)]
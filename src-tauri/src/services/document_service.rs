@@ -0,0 +1,437 @@
+//! Document service: writes report blocks out as PDF or DOCX files
+use crate::services::report_service::ReportBlock;
+use crate::services::storage::app_dir;
+use printpdf::{
+    IndirectFontRef, Line, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference, Point,
+};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 20.0;
+const PT_TO_MM: f32 = 0.352_778;
+const BODY_SIZE: f32 = 10.0;
+const TABLE_SIZE: f32 = 8.0;
+const CELL_PADDING: f32 = 1.5;
+
+/// Fonts tried after `SPEXOR_REPORT_FONT` and `<app data>/fonts/report.ttf`;
+/// the PDF built-in fonts have no Vietnamese glyphs
+const SYSTEM_FONTS: [&str; 6] = [
+    "C:\\Windows\\Fonts\\arial.ttf",
+    "C:\\Windows\\Fonts\\segoeui.ttf",
+    "/System/Library/Fonts/Supplemental/Arial.ttf",
+    "/Library/Fonts/Arial Unicode.ttf",
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/truetype/noto/NotoSans-Regular.ttf",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DocumentFormat {
+    Pdf,
+    Docx,
+}
+
+impl DocumentFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            DocumentFormat::Pdf => "pdf",
+            DocumentFormat::Docx => "docx",
+        }
+    }
+}
+
+/// Write report blocks to `path` in the given format
+pub fn write_document(
+    format: DocumentFormat,
+    title: &str,
+    blocks: &[ReportBlock],
+    path: &Path,
+) -> Result<(), String> {
+    match format {
+        DocumentFormat::Pdf => write_pdf(title, blocks, path),
+        DocumentFormat::Docx => write_docx(title, blocks, path),
+    }
+}
+
+/// Locate a TrueType font with Vietnamese glyphs for PDF output
+pub fn report_font() -> Result<PathBuf, String> {
+    let configured = std::env::var("SPEXOR_REPORT_FONT").ok().map(PathBuf::from);
+    let bundled = app_dir().join("fonts").join("report.ttf");
+
+    configured
+        .into_iter()
+        .chain([bundled])
+        .chain(SYSTEM_FONTS.iter().map(PathBuf::from))
+        .find(|path| path.is_file())
+        .ok_or_else(|| {
+            "No report font found. Set SPEXOR_REPORT_FONT or copy a TrueType font to fonts/report.ttf in the app data directory".to_string()
+        })
+}
+
+/// Break text into lines no wider than `max_width`, splitting words that do not fit
+pub fn wrap_text(text: &str, max_width: f32, width_of: impl Fn(&str) -> f32) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for paragraph in text.lines() {
+        let mut current = String::new();
+        for word in paragraph.split_whitespace() {
+            let candidate = if current.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", current, word)
+            };
+            if width_of(&candidate) <= max_width {
+                current = candidate;
+                continue;
+            }
+            if !current.is_empty() {
+                lines.push(std::mem::take(&mut current));
+            }
+            // Hard-break words longer than a line, such as hashes
+            for c in word.chars() {
+                current.push(c);
+                if width_of(&current) > max_width && current.chars().count() > 1 {
+                    current.pop();
+                    lines.push(std::mem::replace(&mut current, c.to_string()));
+                }
+            }
+        }
+        lines.push(current);
+    }
+
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines
+}
+
+struct PdfWriter<'a> {
+    doc: PdfDocumentReference,
+    font: IndirectFontRef,
+    face: ttf_parser::Face<'a>,
+    layer: PdfLayerReference,
+    /// Distance of the next line's top from the bottom of the page
+    y: f32,
+}
+
+impl PdfWriter<'_> {
+    fn text_width(&self, text: &str, size: f32) -> f32 {
+        let units = self.face.units_per_em() as f32;
+        let advance: f32 = text
+            .chars()
+            .map(|c| {
+                self.face
+                    .glyph_index(c)
+                    .and_then(|glyph| self.face.glyph_hor_advance(glyph))
+                    .map(|advance| advance as f32)
+                    .unwrap_or(units / 2.0)
+            })
+            .sum();
+        advance / units * size * PT_TO_MM
+    }
+
+    fn wrap(&self, text: &str, size: f32, max_width: f32) -> Vec<String> {
+        wrap_text(text, max_width, |line| self.text_width(line, size))
+    }
+
+    /// Start a new page unless `height` still fits on this one
+    fn reserve(&mut self, height: f32) {
+        if self.y - height >= MARGIN {
+            return;
+        }
+        let (page, layer) = self
+            .doc
+            .add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    fn line_height(size: f32) -> f32 {
+        size * PT_TO_MM * 1.4
+    }
+
+    fn text_line(&mut self, text: &str, size: f32, x: f32) {
+        let height = Self::line_height(size);
+        self.reserve(height);
+        self.y -= height;
+        self.layer
+            .use_text(text, size, Mm(x), Mm(self.y + height * 0.25), &self.font);
+    }
+
+    fn rule(&mut self, gap: f32) {
+        self.y -= gap;
+        self.layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(MARGIN), Mm(self.y)), false),
+                (Point::new(Mm(PAGE_WIDTH - MARGIN), Mm(self.y)), false),
+            ],
+            is_closed: false,
+        });
+        self.y -= gap;
+    }
+
+    fn heading(&mut self, level: u8, text: &str) {
+        let size = match level {
+            1 => 16.0,
+            2 => 13.0,
+            _ => 11.5,
+        };
+        let width = PAGE_WIDTH - 2.0 * MARGIN;
+        self.y -= size * PT_TO_MM * 0.8;
+        // Keep a heading together with the first lines that follow it
+        self.reserve(Self::line_height(size) + 3.0 * Self::line_height(BODY_SIZE));
+        for line in self.wrap(text, size, width) {
+            self.text_line(&line, size, MARGIN);
+        }
+    }
+
+    fn paragraph(&mut self, text: &str) {
+        let width = PAGE_WIDTH - 2.0 * MARGIN;
+        for line in self.wrap(text, BODY_SIZE, width) {
+            self.text_line(&line, BODY_SIZE, MARGIN);
+        }
+        self.y -= BODY_SIZE * PT_TO_MM * 0.5;
+    }
+
+    fn table(&mut self, headers: &[String], rows: &[Vec<String>]) {
+        let available = PAGE_WIDTH - 2.0 * MARGIN;
+        // Columns share the width in proportion to their (capped) natural width
+        let natural: Vec<f32> = (0..headers.len())
+            .map(|column| {
+                std::iter::once(&headers[column])
+                    .chain(rows.iter().filter_map(|row| row.get(column)))
+                    .map(|cell| self.text_width(cell, TABLE_SIZE))
+                    .fold(0.0, f32::max)
+                    .clamp(8.0, 70.0)
+            })
+            .collect();
+        let total: f32 = natural.iter().sum();
+        let widths: Vec<f32> = natural.iter().map(|w| w / total * available).collect();
+
+        self.y -= CELL_PADDING;
+        self.table_row(headers, &widths);
+        self.rule(CELL_PADDING);
+        for row in rows {
+            self.table_row(row, &widths);
+            self.rule(CELL_PADDING);
+        }
+        self.y -= BODY_SIZE * PT_TO_MM * 0.5;
+    }
+
+    fn table_row(&mut self, cells: &[String], widths: &[f32]) {
+        let wrapped: Vec<Vec<String>> = widths
+            .iter()
+            .enumerate()
+            .map(|(column, width)| {
+                let cell = cells.get(column).map(String::as_str).unwrap_or("");
+                self.wrap(cell, TABLE_SIZE, width - 2.0 * CELL_PADDING)
+            })
+            .collect();
+        let line_count = wrapped.iter().map(Vec::len).max().unwrap_or(1);
+
+        for index in 0..line_count {
+            let height = Self::line_height(TABLE_SIZE);
+            self.reserve(height);
+            self.y -= height;
+            let mut x = MARGIN;
+            for (lines, width) in wrapped.iter().zip(widths) {
+                if let Some(line) = lines.get(index) {
+                    self.layer.use_text(
+                        line.as_str(),
+                        TABLE_SIZE,
+                        Mm(x + CELL_PADDING),
+                        Mm(self.y + height * 0.25),
+                        &self.font,
+                    );
+                }
+                x += width;
+            }
+        }
+    }
+}
+
+fn write_pdf(title: &str, blocks: &[ReportBlock], path: &Path) -> Result<(), String> {
+    let font_path = report_font()?;
+    let font_bytes = std::fs::read(&font_path)
+        .map_err(|e| format!("Failed to read font {}: {}", font_path.display(), e))?;
+    let face = ttf_parser::Face::parse(&font_bytes, 0)
+        .map_err(|e| format!("Invalid font {}: {}", font_path.display(), e))?;
+
+    let (doc, page, layer) = PdfDocument::new(title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+    let font = doc
+        .add_external_font(font_bytes.as_slice())
+        .map_err(|e| format!("Failed to embed font: {}", e))?;
+    let layer = doc.get_page(page).get_layer(layer);
+
+    let mut writer = PdfWriter {
+        doc,
+        font,
+        face,
+        layer,
+        y: PAGE_HEIGHT - MARGIN,
+    };
+    for block in blocks {
+        match block {
+            ReportBlock::Heading { level, text } => writer.heading(*level, text),
+            ReportBlock::Paragraph(text) => writer.paragraph(text),
+            ReportBlock::Table { headers, rows } => writer.table(headers, rows),
+        }
+    }
+
+    let file = File::create(path).map_err(|e| format!("Failed to create report: {}", e))?;
+    writer
+        .doc
+        .save(&mut BufWriter::new(file))
+        .map_err(|e| format!("Failed to write PDF: {}", e))
+}
+
+/// Escape text for WordprocessingML, dropping characters XML does not allow
+fn xml_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// A paragraph; line breaks in the text become `<w:br/>`
+fn docx_paragraph(text: &str, style: Option<&str>, bold: bool) -> String {
+    let properties = style
+        .map(|style| format!("<w:pPr><w:pStyle w:val=\"{}\"/></w:pPr>", style))
+        .unwrap_or_default();
+    let run_properties = if bold { "<w:rPr><w:b/></w:rPr>" } else { "" };
+    let runs = text
+        .lines()
+        .map(|line| format!("<w:t xml:space=\"preserve\">{}</w:t>", xml_escape(line)))
+        .collect::<Vec<_>>()
+        .join("<w:br/>");
+    format!(
+        "<w:p>{}<w:r>{}{}</w:r></w:p>",
+        properties, run_properties, runs
+    )
+}
+
+fn docx_table(headers: &[String], rows: &[Vec<String>]) -> String {
+    // Twentieths of a point across an A4 page inside 2 cm margins
+    let column_width = 9638 / headers.len().max(1);
+    let grid: String = headers
+        .iter()
+        .map(|_| format!("<w:gridCol w:w=\"{}\"/>", column_width))
+        .collect();
+
+    let row = |cells: &[String], header: bool| {
+        let cells: String = (0..headers.len())
+            .map(|column| {
+                let cell = cells.get(column).map(String::as_str).unwrap_or("");
+                format!(
+                    "<w:tc><w:tcPr><w:tcW w:w=\"{}\" w:type=\"dxa\"/></w:tcPr>{}</w:tc>",
+                    column_width,
+                    docx_paragraph(cell, Some("TableText"), header)
+                )
+            })
+            .collect();
+        let properties = if header {
+            "<w:trPr><w:tblHeader/></w:trPr>"
+        } else {
+            ""
+        };
+        format!("<w:tr>{}{}</w:tr>", properties, cells)
+    };
+
+    let body: String = std::iter::once(row(headers, true))
+        .chain(rows.iter().map(|cells| row(cells, false)))
+        .collect();
+    format!(
+        "<w:tbl><w:tblPr><w:tblStyle w:val=\"ReportTable\"/><w:tblW w:w=\"5000\" w:type=\"pct\"/></w:tblPr><w:tblGrid>{}</w:tblGrid>{}</w:tbl>{}",
+        grid,
+        body,
+        docx_paragraph("", None, false)
+    )
+}
+
+const DOCX_CONTENT_TYPES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/word/document.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/><Override PartName="/word/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.styles+xml"/><Override PartName="/docProps/core.xml" ContentType="application/vnd.openxmlformats-package.core-properties+xml"/></Types>"#;
+
+const DOCX_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="word/document.xml"/><Relationship Id="rId2" Type="http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties" Target="docProps/core.xml"/></Relationships>"#;
+
+const DOCX_DOCUMENT_RELS: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/></Relationships>"#;
+
+const DOCX_STYLES: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:styles xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:docDefaults><w:rPrDefault><w:rPr><w:rFonts w:ascii="Arial" w:hAnsi="Arial" w:cs="Arial"/><w:sz w:val="20"/></w:rPr></w:rPrDefault><w:pPrDefault><w:pPr><w:spacing w:after="80"/></w:pPr></w:pPrDefault></w:docDefaults><w:style w:type="paragraph" w:default="1" w:styleId="Normal"><w:name w:val="Normal"/></w:style><w:style w:type="paragraph" w:styleId="Heading1"><w:name w:val="heading 1"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:pPr><w:keepNext/><w:spacing w:before="240" w:after="120"/><w:outlineLvl w:val="0"/></w:pPr><w:rPr><w:b/><w:sz w:val="32"/></w:rPr></w:style><w:style w:type="paragraph" w:styleId="Heading2"><w:name w:val="heading 2"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:pPr><w:keepNext/><w:spacing w:before="200" w:after="100"/><w:outlineLvl w:val="1"/></w:pPr><w:rPr><w:b/><w:sz w:val="26"/></w:rPr></w:style><w:style w:type="paragraph" w:styleId="Heading3"><w:name w:val="heading 3"/><w:basedOn w:val="Normal"/><w:next w:val="Normal"/><w:pPr><w:keepNext/><w:spacing w:before="160" w:after="80"/><w:outlineLvl w:val="2"/></w:pPr><w:rPr><w:b/><w:sz w:val="23"/></w:rPr></w:style><w:style w:type="paragraph" w:styleId="TableText"><w:name w:val="Table Text"/><w:basedOn w:val="Normal"/><w:pPr><w:spacing w:after="0"/></w:pPr><w:rPr><w:sz w:val="16"/></w:rPr></w:style><w:style w:type="table" w:styleId="ReportTable"><w:name w:val="Report Table"/><w:tblPr><w:tblBorders><w:top w:val="single" w:sz="4" w:space="0" w:color="808080"/><w:left w:val="single" w:sz="4" w:space="0" w:color="808080"/><w:bottom w:val="single" w:sz="4" w:space="0" w:color="808080"/><w:right w:val="single" w:sz="4" w:space="0" w:color="808080"/><w:insideH w:val="single" w:sz="4" w:space="0" w:color="808080"/><w:insideV w:val="single" w:sz="4" w:space="0" w:color="808080"/></w:tblBorders><w:tblCellMar><w:left w:w="80" w:type="dxa"/><w:right w:w="80" w:type="dxa"/></w:tblCellMar></w:tblPr></w:style></w:styles>"#;
+
+fn write_docx(title: &str, blocks: &[ReportBlock], path: &Path) -> Result<(), String> {
+    let body: String = blocks
+        .iter()
+        .map(|block| match block {
+            ReportBlock::Heading { level, text } => {
+                docx_paragraph(text, Some(&format!("Heading{}", level)), false)
+            }
+            ReportBlock::Paragraph(text) => docx_paragraph(text, None, false),
+            ReportBlock::Table { headers, rows } => docx_table(headers, rows),
+        })
+        .collect();
+    let document = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<w:document xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\"><w:body>{}<w:sectPr><w:pgSz w:w=\"11906\" w:h=\"16838\"/><w:pgMar w:top=\"1134\" w:right=\"1134\" w:bottom=\"1134\" w:left=\"1134\" w:header=\"708\" w:footer=\"708\" w:gutter=\"0\"/></w:sectPr></w:body></w:document>",
+        body
+    );
+    let core = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<cp:coreProperties xmlns:cp=\"http://schemas.openxmlformats.org/package/2006/metadata/core-properties\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\" xmlns:dcterms=\"http://purl.org/dc/terms/\" xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\"><dc:title>{}</dc:title><dcterms:created xsi:type=\"dcterms:W3CDTF\">{}</dcterms:created></cp:coreProperties>",
+        xml_escape(title),
+        chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ")
+    );
+
+    let file = File::create(path).map_err(|e| format!("Failed to create report: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    let parts = [
+        ("[Content_Types].xml", DOCX_CONTENT_TYPES),
+        ("_rels/.rels", DOCX_RELS),
+        ("word/_rels/document.xml.rels", DOCX_DOCUMENT_RELS),
+        ("word/styles.xml", DOCX_STYLES),
+        ("word/document.xml", document.as_str()),
+        ("docProps/core.xml", core.as_str()),
+    ];
+    for (name, content) in parts {
+        zip.start_file(name, options)
+            .and_then(|_| zip.write_all(content.as_bytes()).map_err(Into::into))
+            .map_err(|e| format!("Failed to write DOCX: {}", e))?;
+    }
+    zip.finish()
+        .map_err(|e| format!("Failed to write DOCX: {}", e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_text() {
+        let width = |text: &str| text.chars().count() as f32;
+        assert_eq!(
+            wrap_text("the quick brown fox", 10.0, width),
+            vec!["the quick", "brown fox"]
+        );
+        assert_eq!(
+            wrap_text("hash 0123456789abcdef", 8.0, width),
+            vec!["hash", "01234567", "89abcdef"]
+        );
+        assert_eq!(wrap_text("one\ntwo", 80.0, width), vec!["one", "two"]);
+        assert_eq!(wrap_text("", 80.0, width), vec![""]);
+    }
+}
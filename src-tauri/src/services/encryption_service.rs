@@ -0,0 +1,1023 @@
+//! Encryption service: optional at-rest encryption of the database and stored audio
+//!
+//! A master passphrase derives (Argon2id) a key that wraps random data keys kept in
+//! `keyring.json`: one for SQLCipher and numbered ones for audio. Audio the app keeps
+//! under its data directory is sealed in 64 KiB XChaCha20-Poly1305 chunks, so playback
+//! can seek without decrypting whole files. Originals registered in place are not
+//! touched.
+use crate::schema::{audio_derivatives, audio_files, voice_samples};
+use crate::services::storage::{app_dir, storage_dir};
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use diesel::connection::SimpleConnection;
+use diesel::prelude::*;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+pub const MIN_PASSPHRASE_LEN: usize = 10;
+
+const KEYRING_FILE: &str = "keyring.json";
+const KEYRING_AAD: &[u8] = b"spexor-keyring-v1";
+
+/// Argon2id cost: 64 MiB, 3 passes
+const KDF_MEMORY_KIB: u32 = 64 * 1024;
+const KDF_ITERATIONS: u32 = 3;
+const KDF_PARALLELISM: u32 = 1;
+
+/// Sealed file layout: magic, version, key id (u32 BE), nonce prefix, then chunks of
+/// ciphertext + tag. Chunk nonces are prefix || index (u32 BE) || last-chunk flag.
+const MAGIC: &[u8; 4] = b"SPXA";
+const SEALED_VERSION: u8 = 1;
+const NONCE_PREFIX_LEN: usize = 19;
+const HEADER_LEN: usize = 4 + 1 + 4 + NONCE_PREFIX_LEN;
+const CHUNK_LEN: usize = 64 * 1024;
+const TAG_LEN: usize = 16;
+
+/// Largest range returned per playback request
+const MAX_RANGE_LEN: u64 = 1024 * 1024;
+
+/// Where sealed audio is decrypted for external tools, emptied at startup
+const PLAINTEXT_DIR: &str = "tmp";
+
+type Key = [u8; 32];
+
+#[derive(Clone)]
+struct Keys {
+    database: Key,
+    /// Rotated database key waiting for the next unlock
+    pending_database: Option<Key>,
+    audio: BTreeMap<u32, Key>,
+    current_audio: u32,
+}
+
+/// Data keys of the unlocked store
+static KEYS: RwLock<Option<Keys>> = RwLock::new(None);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct WrappedKey {
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct KdfParams {
+    algorithm: String,
+    salt: String,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AudioKey {
+    id: u32,
+    key: WrappedKey,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Keyring {
+    version: u32,
+    kdf: KdfParams,
+    /// Whether spexor.db has been converted to SQLCipher yet
+    database_encrypted: bool,
+    database_key: WrappedKey,
+    /// Replacement database key, applied with `PRAGMA rekey` at the next unlock
+    pending_database_key: Option<WrappedKey>,
+    audio_keys: Vec<AudioKey>,
+    current_audio_key: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EncryptionStatus {
+    pub enabled: bool,
+    pub unlocked: bool,
+    /// False until the database has been converted at the first unlock after enabling
+    pub database_encrypted: bool,
+    pub database_rekey_pending: bool,
+    pub audio_key_id: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct KeyRotation {
+    pub audio_key_id: u32,
+    pub files_resealed: usize,
+    /// Files that could not be re-encrypted; their old key is kept
+    pub failed: Vec<String>,
+    pub retired_keys: Vec<u32>,
+    pub database_rekey_pending: bool,
+    pub passphrase_changed: bool,
+}
+
+fn random_bytes<const N: usize>() -> [u8; N] {
+    let mut bytes = [0u8; N];
+    OsRng.fill_bytes(&mut bytes);
+    bytes
+}
+
+fn corrupt_keyring<E>(_: E) -> String {
+    "Keyring is corrupt".to_string()
+}
+
+fn check_passphrase(passphrase: &str) -> Result<(), String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!(
+            "Passphrase must be at least {} characters",
+            MIN_PASSPHRASE_LEN
+        ));
+    }
+    Ok(())
+}
+
+fn new_kdf() -> KdfParams {
+    KdfParams {
+        algorithm: "argon2id".to_string(),
+        salt: hex::encode(random_bytes::<16>()),
+        memory_kib: KDF_MEMORY_KIB,
+        iterations: KDF_ITERATIONS,
+        parallelism: KDF_PARALLELISM,
+    }
+}
+
+/// Key-encryption key derived from the passphrase
+fn derive_kek(passphrase: &str, kdf: &KdfParams) -> Result<Key, String> {
+    let salt = hex::decode(&kdf.salt).map_err(corrupt_keyring)?;
+    let params = Params::new(kdf.memory_kib, kdf.iterations, kdf.parallelism, Some(32))
+        .map_err(corrupt_keyring)?;
+
+    let mut kek = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), &salt, &mut kek)
+        .map_err(|e| format!("Failed to derive key: {}", e))?;
+    Ok(kek)
+}
+
+fn wrap_key(kek: &Key, key: &Key) -> WrappedKey {
+    let nonce = random_bytes::<24>();
+    let ciphertext = XChaCha20Poly1305::new(kek.into())
+        .encrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: key,
+                aad: KEYRING_AAD,
+            },
+        )
+        .expect("sealing a 32-byte key cannot fail");
+
+    WrappedKey {
+        nonce: hex::encode(nonce),
+        ciphertext: hex::encode(ciphertext),
+    }
+}
+
+fn unwrap_key(kek: &Key, wrapped: &WrappedKey) -> Result<Key, String> {
+    let nonce = hex::decode(&wrapped.nonce)
+        .ok()
+        .filter(|nonce| nonce.len() == 24)
+        .ok_or_else(|| corrupt_keyring(()))?;
+    let ciphertext = hex::decode(&wrapped.ciphertext).map_err(corrupt_keyring)?;
+
+    let key = XChaCha20Poly1305::new(kek.into())
+        .decrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: &ciphertext,
+                aad: KEYRING_AAD,
+            },
+        )
+        .map_err(|_| "Wrong passphrase".to_string())?;
+    key.try_into().map_err(corrupt_keyring)
+}
+
+fn keyring_path() -> PathBuf {
+    app_dir().join(KEYRING_FILE)
+}
+
+fn load_keyring() -> Result<Keyring, String> {
+    let raw = fs::read(keyring_path()).map_err(|_| "Encryption is not enabled".to_string())?;
+    serde_json::from_slice(&raw).map_err(corrupt_keyring)
+}
+
+fn save_keyring(keyring: &Keyring) -> Result<(), String> {
+    let path = keyring_path();
+    let temp = path.with_extension("json.new");
+    let json = serde_json::to_vec_pretty(keyring).map_err(|e| e.to_string())?;
+
+    fs::write(&temp, json)
+        .and_then(|_| fs::rename(&temp, &path))
+        .map_err(|e| format!("Failed to save keyring: {}", e))
+}
+
+fn build_keyring(kek: &Key, kdf: KdfParams, keys: &Keys, database_encrypted: bool) -> Keyring {
+    Keyring {
+        version: 1,
+        kdf,
+        database_encrypted,
+        database_key: wrap_key(kek, &keys.database),
+        pending_database_key: keys.pending_database.map(|key| wrap_key(kek, &key)),
+        audio_keys: keys
+            .audio
+            .iter()
+            .map(|(id, key)| AudioKey {
+                id: *id,
+                key: wrap_key(kek, key),
+            })
+            .collect(),
+        current_audio_key: keys.current_audio,
+    }
+}
+
+fn open_keyring(keyring: &Keyring, passphrase: &str) -> Result<Keys, String> {
+    let kek = derive_kek(passphrase, &keyring.kdf)?;
+
+    Ok(Keys {
+        database: unwrap_key(&kek, &keyring.database_key)?,
+        pending_database: keyring
+            .pending_database_key
+            .as_ref()
+            .map(|wrapped| unwrap_key(&kek, wrapped))
+            .transpose()?,
+        audio: keyring
+            .audio_keys
+            .iter()
+            .map(|audio| Ok((audio.id, unwrap_key(&kek, &audio.key)?)))
+            .collect::<Result<_, String>>()?,
+        current_audio: keyring.current_audio_key,
+    })
+}
+
+fn keys() -> Option<Keys> {
+    KEYS.read().ok().and_then(|keys| keys.clone())
+}
+
+fn set_keys(keys: Option<Keys>) {
+    if let Ok(mut guard) = KEYS.write() {
+        *guard = keys;
+    }
+}
+
+fn audio_key(id: u32) -> Option<Key> {
+    keys().and_then(|keys| keys.audio.get(&id).copied())
+}
+
+/// Whether a keyring exists, i.e. the store needs the master passphrase at startup
+pub fn is_enabled() -> bool {
+    keyring_path().exists()
+}
+
+pub fn is_unlocked() -> bool {
+    keys().is_some()
+}
+
+pub fn status() -> EncryptionStatus {
+    let keyring = load_keyring().ok();
+
+    EncryptionStatus {
+        enabled: keyring.is_some(),
+        unlocked: is_unlocked(),
+        database_encrypted: keyring.as_ref().is_some_and(|k| k.database_encrypted),
+        database_rekey_pending: keyring
+            .as_ref()
+            .is_some_and(|k| k.pending_database_key.is_some()),
+        audio_key_id: keyring.map(|k| k.current_audio_key),
+    }
+}
+
+/// Unlock the store with the master passphrase
+pub fn unlock(passphrase: &str) -> Result<(), String> {
+    let keyring = load_keyring()?;
+    set_keys(Some(open_keyring(&keyring, passphrase)?));
+    Ok(())
+}
+
+/// Forget the unlocked keys
+pub fn lock() {
+    set_keys(None);
+}
+
+/// Turn on encryption: create the keyring and unlock it. New audio is sealed from now
+/// on; the database itself is converted at the next start, before it is opened.
+pub fn enable(conn: &mut SqliteConnection, passphrase: &str) -> Result<(), String> {
+    if is_enabled() {
+        return Err("Encryption is already enabled".to_string());
+    }
+    check_passphrase(passphrase)?;
+    // Without SQLCipher the converted database could never be opened again
+    require_sqlcipher(conn)?;
+
+    let kdf = new_kdf();
+    let kek = derive_kek(passphrase, &kdf)?;
+    let keys = Keys {
+        database: random_bytes(),
+        pending_database: None,
+        audio: BTreeMap::from([(1, random_bytes())]),
+        current_audio: 1,
+    };
+
+    save_keyring(&build_keyring(&kek, kdf, &keys, false))?;
+    set_keys(Some(keys));
+    Ok(())
+}
+
+/// Rotate every data key and optionally the passphrase. Stored audio is re-encrypted
+/// right away; the database is rekeyed at the next unlock.
+pub fn rotate_keys(passphrase: &str, new_passphrase: Option<&str>) -> Result<KeyRotation, String> {
+    let keyring = load_keyring()?;
+    let mut keys = open_keyring(&keyring, passphrase)?;
+    if let Some(new_passphrase) = new_passphrase {
+        check_passphrase(new_passphrase)?;
+    }
+
+    let kdf = new_kdf();
+    let kek = derive_kek(new_passphrase.unwrap_or(passphrase), &kdf)?;
+    let audio_key_id = keys.audio.keys().max().copied().unwrap_or(0) + 1;
+    keys.audio.insert(audio_key_id, random_bytes());
+    keys.current_audio = audio_key_id;
+    if keyring.database_encrypted {
+        keys.pending_database = Some(random_bytes());
+    } else {
+        // Not converted yet, so the new key is simply used for the conversion
+        keys.database = random_bytes();
+    }
+
+    // Old audio keys stay in the keyring until every file has been resealed
+    save_keyring(&build_keyring(
+        &kek,
+        kdf.clone(),
+        &keys,
+        keyring.database_encrypted,
+    ))?;
+    set_keys(Some(keys.clone()));
+
+    let mut sealed = Vec::new();
+    find_sealed_files(app_dir(), &mut sealed);
+    let mut files_resealed = 0;
+    let mut failed = Vec::new();
+    for path in sealed {
+        if sealed_key_id(&path) == Some(audio_key_id) {
+            continue;
+        }
+        match reseal(&path, audio_key_id, &keys.audio[&audio_key_id]) {
+            Ok(()) => files_resealed += 1,
+            Err(e) => failed.push(format!("{}: {}", path.display(), e)),
+        }
+    }
+
+    let mut retired_keys = Vec::new();
+    if failed.is_empty() {
+        retired_keys = keys
+            .audio
+            .keys()
+            .filter(|id| **id != audio_key_id)
+            .copied()
+            .collect();
+        keys.audio.retain(|id, _| *id == audio_key_id);
+        save_keyring(&build_keyring(&kek, kdf, &keys, keyring.database_encrypted))?;
+        set_keys(Some(keys.clone()));
+    }
+
+    Ok(KeyRotation {
+        audio_key_id,
+        files_resealed,
+        failed,
+        retired_keys,
+        database_rekey_pending: keys.pending_database.is_some(),
+        passphrase_changed: new_passphrase.is_some(),
+    })
+}
+
+fn key_pragma(key: &Key) -> String {
+    format!("PRAGMA key = \"x'{}'\";", hex::encode(key))
+}
+
+/// `PRAGMA key` statement for new pool connections while the store is unlocked. None
+/// until the database has been converted to SQLCipher at the next unlock after enabling.
+pub fn database_key_pragma() -> Option<String> {
+    let keys = keys()?;
+    load_keyring()
+        .ok()
+        .filter(|keyring| keyring.database_encrypted)?;
+    Some(key_pragma(&keys.database))
+}
+
+#[derive(QueryableByName)]
+struct CipherVersion {
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    #[allow(dead_code)]
+    cipher_version: String,
+}
+
+fn require_sqlcipher(conn: &mut SqliteConnection) -> Result<(), String> {
+    let versions = diesel::sql_query("PRAGMA cipher_version")
+        .load::<CipherVersion>(conn)
+        .map_err(|e| e.to_string())?;
+    if versions.is_empty() {
+        return Err("This build has no SQLCipher support".to_string());
+    }
+    Ok(())
+}
+
+fn open_keyed(path: &Path, key: &Key) -> Result<SqliteConnection, String> {
+    let mut conn = SqliteConnection::establish(&path.to_string_lossy())
+        .map_err(|e| format!("Failed to open database: {}", e))?;
+    conn.batch_execute(&key_pragma(key))
+        .map_err(|e| e.to_string())?;
+    // SQLCipher only checks the key on the first read
+    conn.batch_execute("SELECT count(*) FROM sqlite_master;")
+        .map_err(|_| "Database key does not match".to_string())?;
+    Ok(conn)
+}
+
+/// Copy a plaintext database into a SQLCipher one and swap it in
+fn encrypt_database(db_path: &Path, key: &Key) -> Result<(), String> {
+    let encrypted = db_path.with_extension("db.encrypting");
+    let _ = fs::remove_file(&encrypted);
+
+    {
+        let mut conn = SqliteConnection::establish(&db_path.to_string_lossy())
+            .map_err(|e| format!("Failed to open database: {}", e))?;
+        require_sqlcipher(&mut conn)?;
+        conn.batch_execute(&format!(
+            "ATTACH DATABASE '{}' AS encrypted KEY \"x'{}'\";
+             SELECT sqlcipher_export('encrypted');
+             DETACH DATABASE encrypted;",
+            encrypted.to_string_lossy().replace('\'', "''"),
+            hex::encode(key)
+        ))
+        .map_err(|e| format!("Failed to encrypt database: {}", e))?;
+    }
+
+    open_keyed(&encrypted, key)?;
+    fs::rename(&encrypted, db_path).map_err(|e| format!("Failed to replace database: {}", e))?;
+    for suffix in ["-journal", "-wal", "-shm"] {
+        let mut sidecar = db_path.as_os_str().to_owned();
+        sidecar.push(suffix);
+        let _ = fs::remove_file(PathBuf::from(sidecar));
+    }
+    Ok(())
+}
+
+fn is_plaintext_database(path: &Path) -> bool {
+    let mut header = [0u8; 16];
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .is_ok_and(|_| &header == b"SQLite format 3\0")
+}
+
+/// Apply `new_key` to a database file encrypted with `old_key`
+fn rekey_database(path: &Path, old_key: &Key, new_key: &Key) -> Result<(), String> {
+    match open_keyed(path, old_key) {
+        Ok(mut conn) => conn
+            .batch_execute(&format!("PRAGMA rekey = \"x'{}'\";", hex::encode(new_key)))
+            .map_err(|e| format!("Failed to rekey database: {}", e)),
+        // Rekeyed before a crash kept the keyring from being saved
+        Err(_) => open_keyed(path, new_key).map(drop),
+    }
+}
+
+/// Encrypt or rekey database snapshots the same way as the live database so they stay
+/// restorable. A snapshot that cannot be converted is reported and left as it is.
+fn prepare_snapshots(snapshots: &[PathBuf], old_key: Option<&Key>, new_key: &Key) {
+    for path in snapshots {
+        let prepared = if is_plaintext_database(path) {
+            encrypt_database(path, new_key)
+        } else if let Some(old_key) = old_key {
+            rekey_database(path, old_key, new_key)
+        } else {
+            Ok(())
+        };
+        if let Err(e) = prepared {
+            eprintln!("Failed to re-encrypt snapshot {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// Bring the database and its `snapshots` in line with the keyring before the pool
+/// opens: convert plaintext databases to SQLCipher and apply a rotated key
+pub fn prepare_database(db_path: &Path, snapshots: &[PathBuf]) -> Result<(), String> {
+    let mut keys = keys().ok_or_else(|| "Encrypted store is locked".to_string())?;
+    let mut keyring = load_keyring()?;
+
+    if !keyring.database_encrypted {
+        if db_path.exists() {
+            encrypt_database(db_path, &keys.database)?;
+        }
+        prepare_snapshots(snapshots, None, &keys.database);
+        keyring.database_encrypted = true;
+        save_keyring(&keyring)?;
+    }
+
+    if let (Some(new_key), Some(wrapped)) =
+        (keys.pending_database, keyring.pending_database_key.clone())
+    {
+        if db_path.exists() {
+            rekey_database(db_path, &keys.database, &new_key)?;
+        }
+        prepare_snapshots(snapshots, Some(&keys.database), &new_key);
+
+        keyring.database_key = wrapped;
+        keyring.pending_database_key = None;
+        save_keyring(&keyring)?;
+        keys.database = new_key;
+        keys.pending_database = None;
+        set_keys(Some(keys));
+    }
+
+    Ok(())
+}
+
+/// Read until `buf` is full or the reader is exhausted
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+fn chunk_nonce(prefix: &[u8], index: u64, last: bool) -> io::Result<XNonce> {
+    let index = u32::try_from(index).map_err(|_| io::Error::other("File is too large"))?;
+    let mut nonce = [0u8; 24];
+    nonce[..NONCE_PREFIX_LEN].copy_from_slice(prefix);
+    nonce[NONCE_PREFIX_LEN..23].copy_from_slice(&index.to_be_bytes());
+    nonce[23] = last as u8;
+    Ok(XNonce::from(nonce))
+}
+
+/// Encrypt `reader` into the sealed format
+fn seal(reader: &mut impl Read, writer: &mut impl Write, key_id: u32, key: &Key) -> io::Result<()> {
+    let prefix = random_bytes::<NONCE_PREFIX_LEN>();
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    header.extend_from_slice(MAGIC);
+    header.push(SEALED_VERSION);
+    header.extend_from_slice(&key_id.to_be_bytes());
+    header.extend_from_slice(&prefix);
+    writer.write_all(&header)?;
+
+    let cipher = XChaCha20Poly1305::new(key.into());
+    let mut current = vec![0u8; CHUNK_LEN];
+    let mut next = vec![0u8; CHUNK_LEN];
+    let mut len = read_full(reader, &mut current)?;
+
+    for index in 0.. {
+        let next_len = read_full(reader, &mut next)?;
+        let last = next_len == 0;
+        let sealed = cipher
+            .encrypt(
+                &chunk_nonce(&prefix, index, last)?,
+                Payload {
+                    msg: &current[..len],
+                    aad: &header,
+                },
+            )
+            .map_err(|_| io::Error::other("Encryption failed"))?;
+        writer.write_all(&sealed)?;
+
+        if last {
+            break;
+        }
+        std::mem::swap(&mut current, &mut next);
+        len = next_len;
+    }
+    Ok(())
+}
+
+/// Seekable plaintext view of a sealed file; chunks are authenticated as they are read
+pub struct SealedReader {
+    file: File,
+    cipher: XChaCha20Poly1305,
+    header: [u8; HEADER_LEN],
+    chunks: u64,
+    len: u64,
+    pos: u64,
+    cached: Option<(u64, Vec<u8>)>,
+}
+
+impl SealedReader {
+    fn open(mut file: File) -> io::Result<Self> {
+        let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message);
+
+        let mut header = [0u8; HEADER_LEN];
+        file.read_exact(&mut header)
+            .map_err(|_| invalid("Encrypted audio is truncated"))?;
+        if &header[..4] != MAGIC || header[4] != SEALED_VERSION {
+            return Err(invalid("Unknown encrypted audio format"));
+        }
+        let key_id = u32::from_be_bytes([header[5], header[6], header[7], header[8]]);
+        let key = audio_key(key_id).ok_or_else(|| {
+            io::Error::other(format!(
+                "Audio key {} is unavailable (store locked or key retired)",
+                key_id
+            ))
+        })?;
+
+        let sealed_chunk = (CHUNK_LEN + TAG_LEN) as u64;
+        let body = file
+            .metadata()?
+            .len()
+            .checked_sub(HEADER_LEN as u64)
+            .filter(|body| *body >= TAG_LEN as u64)
+            .ok_or_else(|| invalid("Encrypted audio is truncated"))?;
+        let chunks = body.div_ceil(sealed_chunk);
+        let len = body
+            .checked_sub(chunks * TAG_LEN as u64)
+            .ok_or_else(|| invalid("Encrypted audio is truncated"))?;
+
+        Ok(SealedReader {
+            file,
+            cipher: XChaCha20Poly1305::new((&key).into()),
+            header,
+            chunks,
+            len,
+            pos: 0,
+            cached: None,
+        })
+    }
+
+    fn chunk(&mut self, index: u64) -> io::Result<&[u8]> {
+        if self.cached.as_ref().map(|(cached, _)| *cached) != Some(index) {
+            let sealed_chunk = (CHUNK_LEN + TAG_LEN) as u64;
+            self.file
+                .seek(SeekFrom::Start(HEADER_LEN as u64 + index * sealed_chunk))?;
+            let mut sealed = vec![0u8; sealed_chunk as usize];
+            let read = read_full(&mut self.file, &mut sealed)?;
+
+            let nonce = chunk_nonce(
+                &self.header[HEADER_LEN - NONCE_PREFIX_LEN..],
+                index,
+                index + 1 == self.chunks,
+            )?;
+            let plain = self
+                .cipher
+                .decrypt(
+                    &nonce,
+                    Payload {
+                        msg: &sealed[..read],
+                        aad: &self.header,
+                    },
+                )
+                .map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "Encrypted audio failed authentication",
+                    )
+                })?;
+            self.cached = Some((index, plain));
+        }
+        Ok(self
+            .cached
+            .as_ref()
+            .map(|(_, plain)| plain.as_slice())
+            .unwrap_or(&[]))
+    }
+}
+
+impl Read for SealedReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos >= self.len || buf.is_empty() {
+            return Ok(0);
+        }
+        let index = self.pos / CHUNK_LEN as u64;
+        let offset = (self.pos % CHUNK_LEN as u64) as usize;
+        let chunk = self.chunk(index)?;
+
+        let count = chunk.len().saturating_sub(offset).min(buf.len());
+        buf[..count].copy_from_slice(&chunk[offset..offset + count]);
+        self.pos += count as u64;
+        Ok(count)
+    }
+}
+
+impl Seek for SealedReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(delta) => self.len.checked_add_signed(delta),
+            SeekFrom::Current(delta) => self.pos.checked_add_signed(delta),
+        };
+        self.pos = target.ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "Seek before start of file")
+        })?;
+        Ok(self.pos)
+    }
+}
+
+pub trait ReadSeek: Read + Seek + Send {}
+
+impl<T: Read + Seek + Send> ReadSeek for T {}
+
+/// Whether a file is in the sealed format
+pub fn is_sealed(path: &Path) -> bool {
+    sealed_key_id(path).is_some()
+}
+
+fn sealed_key_id(path: &Path) -> Option<u32> {
+    let mut header = [0u8; 9];
+    File::open(path).ok()?.read_exact(&mut header).ok()?;
+    (&header[..4] == MAGIC)
+        .then(|| u32::from_be_bytes([header[5], header[6], header[7], header[8]]))
+}
+
+/// Open a file for reading its content, decrypting it if it is sealed
+pub fn open_plaintext(path: &Path) -> io::Result<Box<dyn ReadSeek>> {
+    let file = File::open(path)?;
+    if is_sealed(path) {
+        Ok(Box::new(SealedReader::open(file)?))
+    } else {
+        Ok(Box::new(file))
+    }
+}
+
+/// Re-encrypt a file (plaintext or sealed) under the given key, replacing it atomically
+fn reseal(path: &Path, key_id: u32, key: &Key) -> io::Result<()> {
+    let mut temp = path.as_os_str().to_owned();
+    temp.push(".sealing");
+    let temp = PathBuf::from(temp);
+
+    let result = (|| {
+        let mut input = open_plaintext(path)?;
+        let mut output = io::BufWriter::new(File::create(&temp)?);
+        seal(&mut input, &mut output, key_id, key)?;
+        output
+            .into_inner()
+            .map_err(|e| e.into_error())?
+            .sync_all()?;
+        fs::rename(&temp, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+fn is_stored(path: &Path) -> bool {
+    match (path.canonicalize(), app_dir().canonicalize()) {
+        (Ok(path), Ok(dir)) => path.starts_with(dir),
+        _ => false,
+    }
+}
+
+/// Seal a file the app wrote under its data directory. Returns false (and does nothing)
+/// while encryption is off, for files elsewhere and for files already sealed.
+pub fn seal_stored_file(path: &Path) -> Result<bool, String> {
+    let Some(keys) = keys() else {
+        return Ok(false);
+    };
+    if !is_stored(path) || is_sealed(path) {
+        return Ok(false);
+    }
+
+    reseal(path, keys.current_audio, &keys.audio[&keys.current_audio])
+        .map_err(|e| format!("Failed to encrypt {}: {}", path.display(), e))?;
+    Ok(true)
+}
+
+/// Seal the audio already stored under the data directory: recordings, voice samples
+/// and derivatives. Returns the number sealed and the failures.
+pub fn seal_stored_audio(conn: &mut SqliteConnection) -> Result<(usize, Vec<String>), String> {
+    let mut paths = audio_files::table
+        .select(audio_files::file_path)
+        .load::<String>(conn)
+        .map_err(|e| e.to_string())?;
+    paths.extend(
+        voice_samples::table
+            .select(voice_samples::file_path)
+            .load::<String>(conn)
+            .map_err(|e| e.to_string())?,
+    );
+    paths.extend(
+        audio_derivatives::table
+            .select(audio_derivatives::file_path)
+            .load::<String>(conn)
+            .map_err(|e| e.to_string())?,
+    );
+    paths.sort();
+    paths.dedup();
+
+    let mut sealed = 0;
+    let mut failed = Vec::new();
+    for path in paths {
+        match seal_stored_file(Path::new(&path)) {
+            Ok(true) => sealed += 1,
+            Ok(false) => {}
+            Err(e) => failed.push(e),
+        }
+    }
+    Ok((sealed, failed))
+}
+
+fn find_sealed_files(dir: &Path, found: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            find_sealed_files(&path, found);
+        } else if is_sealed(&path) {
+            found.push(path);
+        }
+    }
+}
+
+/// A plaintext path external tools can read. Sealed files are decrypted to a temporary
+/// file that is removed when this is dropped.
+pub struct PlainFile {
+    path: PathBuf,
+    temporary: bool,
+}
+
+impl std::ops::Deref for PlainFile {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for PlainFile {
+    fn drop(&mut self) {
+        if self.temporary {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+pub fn plaintext_path(path: &Path) -> io::Result<PlainFile> {
+    if !is_sealed(path) {
+        return Ok(PlainFile {
+            path: path.to_path_buf(),
+            temporary: false,
+        });
+    }
+
+    // Keep the extension so tools can tell the format
+    let extension = path
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+    let plain = PlainFile {
+        path: storage_dir(PLAINTEXT_DIR)?.join(format!(
+            "{}{}",
+            hex::encode(random_bytes::<8>()),
+            extension
+        )),
+        temporary: true,
+    };
+
+    let mut input = open_plaintext(path)?;
+    let mut output = File::create(&plain.path)?;
+    io::copy(&mut input, &mut output)?;
+    Ok(plain)
+}
+
+/// Remove plaintext copies left behind by a crash
+pub fn clear_plaintext_copies() {
+    let _ = fs::remove_dir_all(app_dir().join(PLAINTEXT_DIR));
+}
+
+/// Part of a file's plaintext
+#[derive(Debug)]
+pub struct ByteRange {
+    pub data: Vec<u8>,
+    pub start: u64,
+    pub total: u64,
+    /// False when `data` is the whole file
+    pub partial: bool,
+}
+
+/// Inclusive byte range of an HTTP `Range: bytes=...` header; None if unsatisfiable.
+/// Only the first range of a multi-range request is honoured.
+pub fn parse_range(header: &str, total: u64) -> Option<(u64, u64)> {
+    if total == 0 {
+        return None;
+    }
+    let spec = header
+        .trim()
+        .strip_prefix("bytes=")?
+        .split(',')
+        .next()?
+        .trim();
+    let (start, end) = spec.split_once('-')?;
+
+    let (start, end) = if start.is_empty() {
+        let suffix: u64 = end.parse().ok()?;
+        if suffix == 0 {
+            return None;
+        }
+        (total.saturating_sub(suffix), total - 1)
+    } else {
+        let start: u64 = start.parse().ok()?;
+        let end = match end {
+            "" => total - 1,
+            end => end.parse::<u64>().ok()?.min(total - 1),
+        };
+        (start, end)
+    };
+
+    (start <= end && start < total).then_some((start, end))
+}
+
+/// Read part of a stored audio file for streaming playback, decrypting only the chunks
+/// it covers. At most 1 MiB is returned per call; players ask for the rest as they go.
+pub fn read_range(path: &Path, range: Option<&str>) -> Result<ByteRange, String> {
+    let read_error = |e: io::Error| format!("Failed to read {}: {}", path.display(), e);
+    let mut reader = open_plaintext(path).map_err(read_error)?;
+    let total = reader.seek(SeekFrom::End(0)).map_err(read_error)?;
+
+    let (start, end) = match range {
+        Some(header) => parse_range(header, total)
+            .ok_or_else(|| "Requested range not satisfiable".to_string())?,
+        None => (0, total.saturating_sub(1)),
+    };
+    let len = (end + 1 - start).min(MAX_RANGE_LEN);
+
+    reader.seek(SeekFrom::Start(start)).map_err(read_error)?;
+    let mut data = Vec::new();
+    reader
+        .take(len)
+        .read_to_end(&mut data)
+        .map_err(read_error)?;
+
+    Ok(ByteRange {
+        partial: range.is_some() || (data.len() as u64) < total,
+        data,
+        start,
+        total,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_and_seek() {
+        let key = [3u8; 32];
+        set_keys(Some(Keys {
+            database: [0u8; 32],
+            pending_database: None,
+            audio: BTreeMap::from([(7, key)]),
+            current_audio: 7,
+        }));
+
+        let dir = std::env::temp_dir().join(format!("spexor_seal_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("sample.wav");
+        let plain: Vec<u8> = (0..CHUNK_LEN * 2 + 100).map(|i| (i % 251) as u8).collect();
+        let mut sealed = Vec::new();
+        seal(&mut plain.as_slice(), &mut sealed, 7, &key).unwrap();
+        fs::write(&path, &sealed).unwrap();
+
+        assert_eq!(sealed_key_id(&path), Some(7));
+        let mut reader = open_plaintext(&path).unwrap();
+        let mut all = Vec::new();
+        reader.read_to_end(&mut all).unwrap();
+        assert_eq!(all, plain);
+
+        let start = CHUNK_LEN as u64 - 10;
+        reader.seek(SeekFrom::Start(start)).unwrap();
+        let mut part = [0u8; 20];
+        reader.read_exact(&mut part).unwrap();
+        assert_eq!(&part[..], &plain[start as usize..start as usize + 20]);
+
+        // A flipped bit and a dropped final chunk are both detected
+        let mut tampered = sealed.clone();
+        tampered[HEADER_LEN + 5] ^= 1;
+        fs::write(&path, &tampered).unwrap();
+        assert!(open_plaintext(&path)
+            .unwrap()
+            .read_to_end(&mut Vec::new())
+            .is_err());
+        fs::write(&path, &sealed[..HEADER_LEN + 2 * (CHUNK_LEN + TAG_LEN)]).unwrap();
+        assert!(open_plaintext(&path)
+            .unwrap()
+            .read_to_end(&mut Vec::new())
+            .is_err());
+
+        lock();
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range("bytes=0-99", 1000), Some((0, 99)));
+        assert_eq!(parse_range("bytes=900-", 1000), Some((900, 999)));
+        assert_eq!(parse_range("bytes=-100", 1000), Some((900, 999)));
+        assert_eq!(parse_range("bytes=500-5000", 1000), Some((500, 999)));
+        assert_eq!(parse_range("bytes=1000-", 1000), None);
+        assert_eq!(parse_range("items=0-1", 1000), None);
+        assert_eq!(parse_range("bytes=0-1", 0), None);
+    }
+}
@@ -0,0 +1,209 @@
+//! Audio enhancement service: builds the ffmpeg filter chain for a preset and
+//! renders the cached enhanced copy
+use crate::models::{AudioDerivative, AudioFile, EnhancementPreset, NewAudioDerivative};
+use crate::schema::{audio_derivatives, audio_files, enhancement_presets};
+use crate::services::encryption_service::seal_stored_file;
+use crate::services::ffmpeg::{run_ffmpeg, OutputFormat};
+use crate::services::storage::{sha256_file, storage_dir};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Dynamic range compression settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompressionParams {
+    pub threshold_db: f32,
+    pub ratio: f32,
+}
+
+/// Enhancement preset; `None` disables a stage
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnhancementParams {
+    /// Spectral noise reduction amount in dB
+    pub noise_reduction_db: Option<f32>,
+    /// High-pass cutoff in Hz (removes hum and rumble)
+    pub highpass_hz: Option<f32>,
+    /// Low-pass cutoff in Hz (removes hiss above the voice band)
+    pub lowpass_hz: Option<f32>,
+    pub compression: Option<CompressionParams>,
+    /// EBU R128 integrated loudness target in LUFS
+    pub loudness_lufs: Option<f32>,
+}
+
+/// Defaults tuned for narrow-band phone recordings
+impl Default for EnhancementParams {
+    fn default() -> Self {
+        Self {
+            noise_reduction_db: Some(12.0),
+            highpass_hz: Some(100.0),
+            lowpass_hz: Some(3800.0),
+            compression: Some(CompressionParams {
+                threshold_db: -24.0,
+                ratio: 3.0,
+            }),
+            loudness_lufs: Some(-16.0),
+        }
+    }
+}
+
+/// Build the ffmpeg `-af` chain: band-pass, noise reduction, compression, then
+/// loudness normalization last so the output level is predictable
+pub fn filter_chain(params: &EnhancementParams) -> Option<String> {
+    let mut filters = Vec::new();
+
+    if let Some(hz) = params.highpass_hz {
+        filters.push(format!("highpass=f={}", hz));
+    }
+    if let Some(hz) = params.lowpass_hz {
+        filters.push(format!("lowpass=f={}", hz));
+    }
+    if let Some(db) = params.noise_reduction_db {
+        filters.push(format!("afftdn=nr={}:nf=-40", db.clamp(0.01, 97.0)));
+    }
+    if let Some(compression) = &params.compression {
+        filters.push(format!(
+            "acompressor=threshold={}dB:ratio={}:attack=5:release=100",
+            compression.threshold_db,
+            compression.ratio.clamp(1.0, 20.0)
+        ));
+    }
+    if let Some(lufs) = params.loudness_lufs {
+        filters.push(format!(
+            "loudnorm=I={}:TP=-1.5:LRA=11",
+            lufs.clamp(-70.0, -5.0)
+        ));
+    }
+
+    (!filters.is_empty()).then(|| filters.join(","))
+}
+
+/// Enhancement preset of an audio file (defaults when none was saved)
+pub fn load_preset(
+    conn: &mut SqliteConnection,
+    audio_file_id: i32,
+) -> Result<EnhancementParams, String> {
+    let preset = enhancement_presets::table
+        .filter(enhancement_presets::audio_file_id.eq(audio_file_id))
+        .first::<EnhancementPreset>(conn)
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    match preset {
+        Some(preset) => serde_json::from_str(&preset.params).map_err(|e| e.to_string()),
+        None => Ok(EnhancementParams::default()),
+    }
+}
+
+/// Enhanced copy of an audio file for its current preset, rendered only when no
+/// cached copy exists for these exact parameters
+pub fn ensure_enhanced_copy(
+    conn: &mut SqliteConnection,
+    audio_file_id: i32,
+    user_id: Option<i32>,
+) -> Result<AudioDerivative, String> {
+    let params = load_preset(conn, audio_file_id)?;
+    let params_json = serde_json::to_string(&params).map_err(|e| e.to_string())?;
+
+    let cached = audio_derivatives::table
+        .filter(audio_derivatives::audio_file_id.eq(audio_file_id))
+        .filter(audio_derivatives::kind.eq("enhanced"))
+        .filter(audio_derivatives::params.eq(&params_json))
+        .order(audio_derivatives::id.desc())
+        .first::<AudioDerivative>(conn)
+        .optional()
+        .map_err(|e| e.to_string())?;
+
+    if let Some(derivative) = cached.filter(|d| std::path::Path::new(&d.file_path).exists()) {
+        return Ok(derivative);
+    }
+
+    let audio = audio_files::table
+        .find(audio_file_id)
+        .first::<AudioFile>(conn)
+        .map_err(|e| format!("Audio file not found: {}", e))?;
+
+    let format = OutputFormat::Wav;
+    let output_path = storage_dir("enhanced")
+        .map_err(|e| e.to_string())?
+        .join(format!(
+            "audio{}_enhanced_{}.{}",
+            audio.id,
+            chrono::Utc::now().format("%Y%m%d%H%M%S"),
+            format.extension()
+        ));
+
+    let mut args = vec!["-i".to_string(), audio.file_path.clone()];
+    if let Some(chain) = filter_chain(&params) {
+        args.push("-af".to_string());
+        args.push(chain);
+    }
+    args.extend(["-ar".to_string(), "44100".to_string()]);
+    args.extend(format.codec_args());
+    args.push(output_path.to_string_lossy().to_string());
+
+    run_ffmpeg(&args).map_err(|e| e.to_string())?;
+    seal_stored_file(&output_path)?;
+
+    let new_derivative = NewAudioDerivative {
+        audio_file_id,
+        kind: "enhanced".to_string(),
+        file_path: output_path.to_string_lossy().to_string(),
+        sha256: sha256_file(&output_path).map_err(|e| e.to_string())?,
+        params: Some(params_json),
+        created_by: user_id,
+    };
+
+    diesel::insert_into(audio_derivatives::table)
+        .values(&new_derivative)
+        .execute(conn)
+        .map_err(|e| format!("Failed to save enhanced audio: {}", e))?;
+
+    audio_derivatives::table
+        .order(audio_derivatives::id.desc())
+        .first::<AudioDerivative>(conn)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_chain_order_and_clamping() {
+        let chain = filter_chain(&EnhancementParams::default()).unwrap();
+        assert_eq!(
+            chain,
+            "highpass=f=100,lowpass=f=3800,afftdn=nr=12:nf=-40,\
+             acompressor=threshold=-24dB:ratio=3:attack=5:release=100,\
+             loudnorm=I=-16:TP=-1.5:LRA=11"
+        );
+
+        let extreme = EnhancementParams {
+            noise_reduction_db: Some(500.0),
+            highpass_hz: None,
+            lowpass_hz: None,
+            compression: Some(CompressionParams {
+                threshold_db: -30.0,
+                ratio: 0.5,
+            }),
+            loudness_lufs: Some(0.0),
+        };
+        assert_eq!(
+            filter_chain(&extreme).unwrap(),
+            "afftdn=nr=97:nf=-40,acompressor=threshold=-30dB:ratio=1:attack=5:release=100,\
+             loudnorm=I=-5:TP=-1.5:LRA=11"
+        );
+    }
+
+    #[test]
+    fn test_filter_chain_all_disabled() {
+        let params = EnhancementParams {
+            noise_reduction_db: None,
+            highpass_hz: None,
+            lowpass_hz: None,
+            compression: None,
+            loudness_lufs: None,
+        };
+        assert_eq!(filter_chain(&params), None);
+    }
+}
@@ -0,0 +1,59 @@
+//! Export service: assembles the transcript of an audio file for disclosure
+use crate::models::{Annotation, AudioFile, Redaction, TranscriptSegment};
+use crate::schema::{annotations, audio_files, redactions, transcript_segments};
+use crate::services::redaction_service::{mask_annotation, mask_segment};
+use diesel::prelude::*;
+use serde::Serialize;
+
+/// Transcript of one audio file with redactions applied, optionally with its annotations
+#[derive(Debug, Serialize)]
+pub struct TranscriptExport {
+    pub audio_file: AudioFile,
+    pub segments: Vec<TranscriptSegment>,
+    pub annotations: Vec<Annotation>,
+}
+
+pub fn build_transcript_export(
+    conn: &mut SqliteConnection,
+    audio_file_id: i32,
+    include_annotations: bool,
+) -> Result<TranscriptExport, String> {
+    let audio_file = audio_files::table
+        .find(audio_file_id)
+        .first::<AudioFile>(conn)
+        .map_err(|e| format!("Audio file not found: {}", e))?;
+
+    let ranges = redactions::table
+        .filter(redactions::audio_file_id.eq(audio_file_id))
+        .load::<Redaction>(conn)
+        .map_err(|e| e.to_string())?;
+
+    let segments = transcript_segments::table
+        .filter(transcript_segments::audio_file_id.eq(audio_file_id))
+        .filter(transcript_segments::is_deleted.eq(0))
+        .order(transcript_segments::start_time.asc())
+        .load::<TranscriptSegment>(conn)
+        .map_err(|e| e.to_string())?
+        .iter()
+        .map(|segment| mask_segment(segment, &ranges))
+        .collect();
+
+    let annotations = if include_annotations {
+        annotations::table
+            .filter(annotations::audio_file_id.eq(audio_file_id))
+            .order((annotations::start_time.asc(), annotations::id.asc()))
+            .load::<Annotation>(conn)
+            .map_err(|e| e.to_string())?
+            .iter()
+            .map(|annotation| mask_annotation(annotation, &ranges))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    Ok(TranscriptExport {
+        audio_file,
+        segments,
+        annotations,
+    })
+}
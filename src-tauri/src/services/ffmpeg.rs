@@ -0,0 +1,363 @@
+//! FFmpeg service for decoding, cutting and encoding audio
+//!
+//! Evidence arrives in many handset formats, so all audio processing goes
+//! through an external ffmpeg binary instead of per-format Rust decoders.
+use crate::services::encryption_service::{is_sealed, open_plaintext, plaintext_path, PlainFile};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+
+#[derive(Debug)]
+pub enum FfmpegError {
+    NotFound,
+    Io(String),
+    Failed(String),
+}
+
+impl std::fmt::Display for FfmpegError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FfmpegError::NotFound => write!(f, "ffmpeg not found (set SPEXOR_FFMPEG)"),
+            FfmpegError::Io(e) => write!(f, "ffmpeg I/O error: {}", e),
+            FfmpegError::Failed(e) => write!(f, "ffmpeg failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for FfmpegError {}
+
+/// Output container/codec for generated audio files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Wav,
+    Mp3,
+    Ogg,
+}
+
+impl OutputFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Wav => "wav",
+            OutputFormat::Mp3 => "mp3",
+            OutputFormat::Ogg => "ogg",
+        }
+    }
+
+    /// Encoder arguments for this format
+    pub fn codec_args(&self) -> Vec<String> {
+        let args: &[&str] = match self {
+            OutputFormat::Wav => &["-c:a", "pcm_s16le"],
+            OutputFormat::Mp3 => &["-c:a", "libmp3lame", "-q:a", "2"],
+            OutputFormat::Ogg => &["-c:a", "libvorbis", "-q:a", "5"],
+        };
+        args.iter().map(|a| a.to_string()).collect()
+    }
+}
+
+/// Locate a bundled tool: `SPEXOR_<NAME>` env var, next to the executable, or PATH
+pub fn tool_binary(name: &str) -> PathBuf {
+    if let Ok(path) = std::env::var(format!("SPEXOR_{}", name.to_uppercase())) {
+        return PathBuf::from(path);
+    }
+
+    let file_name = format!("{}{}", name, std::env::consts::EXE_SUFFIX);
+    if let Some(bundled) = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(&file_name)))
+        .filter(|path| path.exists())
+    {
+        return bundled;
+    }
+
+    PathBuf::from(file_name)
+}
+
+/// Readable path for an input; sealed files are decrypted for the duration of the call
+fn plain_input(path: &Path) -> Result<PlainFile, FfmpegError> {
+    plaintext_path(path).map_err(|e| FfmpegError::Io(format!("{}: {}", path.display(), e)))
+}
+
+/// Point every `-i` argument at a readable plaintext file
+fn plain_inputs(args: &mut [String]) -> Result<Vec<PlainFile>, FfmpegError> {
+    let mut inputs = Vec::new();
+    for i in 1..args.len() {
+        if args[i - 1] == "-i" {
+            let input = plain_input(Path::new(&args[i]))?;
+            args[i] = input.to_string_lossy().to_string();
+            inputs.push(input);
+        }
+    }
+    Ok(inputs)
+}
+
+/// Point the sealed `-i` input at stdin, returning the file to feed it from. Only one
+/// input can be piped; calls with several sealed inputs use temporary copies.
+fn pipe_sealed_input(args: &mut [String]) -> Option<PathBuf> {
+    let sealed: Vec<usize> = (1..args.len())
+        .filter(|&i| args[i - 1] == "-i" && is_sealed(Path::new(&args[i])))
+        .collect();
+    let [index] = sealed[..] else {
+        return None;
+    };
+    Some(PathBuf::from(std::mem::replace(
+        &mut args[index],
+        "pipe:0".to_string(),
+    )))
+}
+
+/// Start a tool with its output piped; `stdin_source` is decrypted into its stdin
+fn spawn(name: &str, args: &[String], stdin_source: Option<&Path>) -> Result<Child, FfmpegError> {
+    let input = stdin_source
+        .map(|source| {
+            open_plaintext(source)
+                .map_err(|e| FfmpegError::Io(format!("{}: {}", source.display(), e)))
+        })
+        .transpose()?;
+
+    let mut child = Command::new(tool_binary(name))
+        .args(["-hide_banner", "-loglevel", "error"])
+        .args(args)
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => FfmpegError::NotFound,
+            _ => FfmpegError::Io(e.to_string()),
+        })?;
+
+    if let Some(mut input) = input {
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        // The tool may stop reading early (e.g. after `-t`); the broken pipe ends the copy
+        std::thread::spawn(move || {
+            let _ = std::io::copy(&mut input, &mut stdin);
+        });
+    }
+    Ok(child)
+}
+
+fn output(
+    name: &str,
+    args: &[String],
+    stdin_source: Option<&Path>,
+) -> Result<Vec<u8>, FfmpegError> {
+    let output = spawn(name, args, stdin_source)?
+        .wait_with_output()
+        .map_err(|e| FfmpegError::Io(e.to_string()))?;
+
+    if !output.status.success() {
+        return Err(FfmpegError::Failed(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+
+    Ok(output.stdout)
+}
+
+/// Run a tool. A sealed input is decrypted into the tool's stdin so no plaintext copy
+/// is written; formats that need seeking (MP4 with its index at the end) cannot be read
+/// from a pipe, so a failed piped run is retried from a copy removed right after.
+fn run(name: &str, args: &[String]) -> Result<Vec<u8>, FfmpegError> {
+    let mut piped_args = args.to_vec();
+    if let Some(source) = pipe_sealed_input(&mut piped_args) {
+        match output(name, &piped_args, Some(&source)) {
+            Err(FfmpegError::Failed(_)) => {}
+            result => return result,
+        }
+    }
+
+    let mut args = args.to_vec();
+    let _inputs = plain_inputs(&mut args)?;
+    output(name, &args, None)
+}
+
+/// Run ffmpeg with the given arguments (overwrites existing outputs)
+pub fn run_ffmpeg(args: &[String]) -> Result<(), FfmpegError> {
+    let mut full_args = vec!["-y".to_string()];
+    full_args.extend_from_slice(args);
+    run("ffmpeg", &full_args).map(|_| ())
+}
+
+/// Decode `(start, duration)` seconds of an audio file (or all of it) to mono
+/// 32-bit float samples at `sample_rate`
+pub fn decode_pcm_range(
+    path: &Path,
+    sample_rate: u32,
+    range: Option<(f32, f32)>,
+) -> Result<Vec<f32>, FfmpegError> {
+    let mut args = Vec::new();
+    if let Some((start, _)) = range {
+        args.extend(["-ss".to_string(), format!("{:.3}", start)]);
+    }
+    args.extend(["-i".to_string(), path.to_string_lossy().to_string()]);
+    if let Some((_, duration)) = range {
+        args.extend(["-t".to_string(), format!("{:.3}", duration)]);
+    }
+    args.extend([
+        "-ac".to_string(),
+        "1".to_string(),
+        "-ar".to_string(),
+        sample_rate.to_string(),
+        "-f".to_string(),
+        "f32le".to_string(),
+        "-".to_string(),
+    ]);
+
+    let bytes = run("ffmpeg", &args)?;
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect())
+}
+
+/// Decode an audio file to mono 32-bit float samples at `sample_rate`, handing them
+/// to `on_samples` block by block instead of holding the whole recording in memory
+pub fn stream_pcm(
+    path: &Path,
+    sample_rate: u32,
+    mut on_samples: impl FnMut(&[f32]),
+) -> Result<(), FfmpegError> {
+    let mut args: Vec<String> = [
+        "-i",
+        &path.to_string_lossy(),
+        "-ac",
+        "1",
+        "-ar",
+        &sample_rate.to_string(),
+        "-f",
+        "f32le",
+        "-",
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect();
+
+    // Sealed input is piped like in `run`; the copy fallback is only safe before any
+    // samples were handed out
+    let mut piped_args = args.clone();
+    if let Some(source) = pipe_sealed_input(&mut piped_args) {
+        let mut delivered = false;
+        let piped = stream_samples(&piped_args, Some(&source), &mut |samples| {
+            delivered = true;
+            on_samples(samples);
+        });
+        match piped {
+            Err(FfmpegError::Failed(_)) if !delivered => {}
+            result => return result,
+        }
+    }
+
+    let _inputs = plain_inputs(&mut args)?;
+    stream_samples(&args, None, &mut on_samples)
+}
+
+fn stream_samples(
+    args: &[String],
+    stdin_source: Option<&Path>,
+    on_samples: &mut dyn FnMut(&[f32]),
+) -> Result<(), FfmpegError> {
+    let mut child = spawn("ffmpeg", args, stdin_source)?;
+
+    // Drained on its own thread so a chatty decoder cannot block on a full pipe
+    let mut stderr = child.stderr.take().expect("stderr is piped");
+    let errors = std::thread::spawn(move || {
+        let mut text = String::new();
+        let _ = stderr.read_to_string(&mut text);
+        text
+    });
+
+    let mut stdout = child.stdout.take().expect("stdout is piped");
+    let mut buffer = vec![0u8; 64 * 1024];
+    let mut samples = Vec::with_capacity(buffer.len() / 4);
+    let mut filled = 0;
+    loop {
+        let read = stdout
+            .read(&mut buffer[filled..])
+            .map_err(|e| FfmpegError::Io(e.to_string()))?;
+        if read == 0 {
+            break;
+        }
+        filled += read;
+        let whole = filled - filled % 4;
+        samples.clear();
+        samples.extend(
+            buffer[..whole]
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+        );
+        on_samples(&samples);
+        // Keep a split sample for the next read
+        buffer.copy_within(whole..filled, 0);
+        filled -= whole;
+    }
+
+    let status = child.wait().map_err(|e| FfmpegError::Io(e.to_string()))?;
+    let errors = errors.join().unwrap_or_default();
+    if !status.success() {
+        return Err(FfmpegError::Failed(errors.trim().to_string()));
+    }
+    Ok(())
+}
+
+/// Read the channel count of the first audio stream with ffprobe
+pub fn probe_channels(path: &Path) -> Result<i32, FfmpegError> {
+    let args = vec![
+        "-select_streams".to_string(),
+        "a:0".to_string(),
+        "-show_entries".to_string(),
+        "stream=channels".to_string(),
+        "-of".to_string(),
+        "csv=p=0".to_string(),
+        "-i".to_string(),
+        path.to_string_lossy().to_string(),
+    ];
+
+    let output = run("ffprobe", &args)?;
+    String::from_utf8_lossy(&output)
+        .trim()
+        .parse::<i32>()
+        .map_err(|_| FfmpegError::Failed("No audio stream found".to_string()))
+}
+
+/// Read the container duration in seconds with ffprobe
+pub fn probe_duration(path: &Path) -> Result<f32, FfmpegError> {
+    let args = vec![
+        "-show_entries".to_string(),
+        "format=duration".to_string(),
+        "-of".to_string(),
+        "csv=p=0".to_string(),
+        "-i".to_string(),
+        path.to_string_lossy().to_string(),
+    ];
+
+    let output = run("ffprobe", &args)?;
+    String::from_utf8_lossy(&output)
+        .trim()
+        .parse::<f32>()
+        .map_err(|_| FfmpegError::Failed("Unknown duration".to_string()))
+}
+
+/// Read the creation time tag of the container or first audio stream with ffprobe
+pub fn probe_creation_time(path: &Path) -> Result<Option<String>, FfmpegError> {
+    let args = vec![
+        "-show_entries".to_string(),
+        "format_tags=creation_time,date:stream_tags=creation_time".to_string(),
+        "-of".to_string(),
+        "default=nw=1:nk=1".to_string(),
+        "-i".to_string(),
+        path.to_string_lossy().to_string(),
+    ];
+
+    let output = run("ffprobe", &args)?;
+    Ok(String::from_utf8_lossy(&output)
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string))
+}
@@ -0,0 +1,254 @@
+//! Import service: scanning folders and registering audio files with duplicate detection
+use crate::models::{AudioFile, NewAudioFile};
+use crate::schema::audio_files;
+use crate::services::ffmpeg::{probe_channels, probe_duration};
+use crate::services::recording_time_service::refresh_recorded_at;
+use crate::services::status_service::AudioStatus;
+use crate::services::storage::sha256_file;
+use crate::services::working_copy_service::queue_working_copies;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// File extensions accepted as audio evidence (lowercase)
+pub const SUPPORTED_AUDIO_EXTENSIONS: [&str; 14] = [
+    "wav", "mp3", "m4a", "aac", "ogg", "oga", "opus", "flac", "wma", "amr", "3gp", "3gpp", "webm",
+    "mp4",
+];
+
+/// What to do with a file whose content already exists in another case
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicatePolicy {
+    /// Do not register it again
+    #[default]
+    Skip,
+    /// Register it in the target case, pointing at the already known evidence file
+    Link,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportStatus {
+    Imported,
+    Linked,
+    Duplicate,
+    Unsupported,
+    Failed,
+}
+
+/// Outcome of importing one file
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportFileResult {
+    pub file_path: String,
+    pub status: ImportStatus,
+    pub audio_file_id: Option<i32>,
+    pub duplicate_of: Option<i32>,
+    pub error: Option<String>,
+}
+
+impl ImportFileResult {
+    fn new(path: &Path, status: ImportStatus) -> Self {
+        Self {
+            file_path: path.to_string_lossy().to_string(),
+            status,
+            audio_file_id: None,
+            duplicate_of: None,
+            error: None,
+        }
+    }
+
+    pub fn failed(path: &Path, error: String) -> Self {
+        Self {
+            error: Some(error),
+            ..Self::new(path, ImportStatus::Failed)
+        }
+    }
+}
+
+/// Summary of an import batch
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportReport {
+    pub total: usize,
+    pub imported: usize,
+    pub linked: usize,
+    pub duplicates: usize,
+    pub unsupported: usize,
+    pub failed: usize,
+    pub results: Vec<ImportFileResult>,
+}
+
+impl ImportReport {
+    pub fn from_results(results: Vec<ImportFileResult>) -> Self {
+        let count = |status: ImportStatus| results.iter().filter(|r| r.status == status).count();
+
+        Self {
+            total: results.len(),
+            imported: count(ImportStatus::Imported),
+            linked: count(ImportStatus::Linked),
+            duplicates: count(ImportStatus::Duplicate),
+            unsupported: count(ImportStatus::Unsupported),
+            failed: count(ImportStatus::Failed),
+            results,
+        }
+    }
+}
+
+pub fn is_supported_audio(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| SUPPORTED_AUDIO_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Files found by a folder scan, plus the subdirectories that could not be read
+#[derive(Debug, Default)]
+pub struct FolderScan {
+    pub files: Vec<PathBuf>,
+    pub errors: Vec<(PathBuf, String)>,
+}
+
+/// List all files in a directory (recursively if asked), sorted by path.
+/// Symlinked directories are not followed so a link cycle cannot recurse
+/// forever; unreadable subdirectories are reported and skipped.
+pub fn scan_files(dir: &Path, recursive: bool) -> std::io::Result<FolderScan> {
+    let mut scan = FolderScan::default();
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        let entries = match std::fs::read_dir(&current) {
+            Ok(entries) => entries,
+            Err(e) if current == dir => return Err(e),
+            Err(e) => {
+                scan.errors.push((current, e.to_string()));
+                continue;
+            }
+        };
+
+        for entry in entries {
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    scan.errors.push((current.clone(), e.to_string()));
+                    continue;
+                }
+            };
+            let path = entry.path();
+            let file_type = match entry.file_type() {
+                Ok(file_type) => file_type,
+                Err(e) => {
+                    scan.errors.push((path, e.to_string()));
+                    continue;
+                }
+            };
+
+            if file_type.is_dir() {
+                if recursive {
+                    pending.push(path);
+                }
+            } else if file_type.is_file() || (file_type.is_symlink() && path.is_file()) {
+                scan.files.push(path);
+            }
+        }
+    }
+
+    scan.files.sort();
+    scan.errors.sort();
+    Ok(scan)
+}
+
+/// Register one audio file in a case, detecting duplicates by content hash
+/// across all cases
+pub fn import_audio_file(
+    conn: &mut SqliteConnection,
+    case_id: i32,
+    path: &Path,
+    policy: DuplicatePolicy,
+) -> ImportFileResult {
+    if !is_supported_audio(path) {
+        return ImportFileResult::new(path, ImportStatus::Unsupported);
+    }
+
+    match try_import(conn, case_id, path, policy) {
+        Ok(result) => result,
+        Err(e) => ImportFileResult::failed(path, e),
+    }
+}
+
+fn try_import(
+    conn: &mut SqliteConnection,
+    case_id: i32,
+    path: &Path,
+    policy: DuplicatePolicy,
+) -> Result<ImportFileResult, String> {
+    let sha256 = sha256_file(path).map_err(|e| format!("Failed to hash file: {}", e))?;
+
+    let existing = audio_files::table
+        .filter(audio_files::sha256.eq(&sha256))
+        .order(audio_files::id.asc())
+        .load::<AudioFile>(conn)
+        .map_err(|e| e.to_string())?;
+
+    let duplicate = |original: &AudioFile| ImportFileResult {
+        duplicate_of: Some(original.id),
+        ..ImportFileResult::new(path, ImportStatus::Duplicate)
+    };
+
+    if let Some(same_case) = existing.iter().find(|a| a.case_id == case_id) {
+        return Ok(duplicate(same_case));
+    }
+
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let (new_audio, status, duplicate_of) = match existing.first() {
+        Some(original) if policy == DuplicatePolicy::Skip => return Ok(duplicate(original)),
+        Some(original) => (
+            NewAudioFile {
+                case_id,
+                file_name,
+                file_path: original.file_path.clone(),
+                duration: original.duration,
+                status: AudioStatus::Uploaded.to_string(),
+                channels: original.channels,
+                sha256: Some(sha256),
+            },
+            ImportStatus::Linked,
+            Some(original.id),
+        ),
+        None => (
+            NewAudioFile {
+                case_id,
+                file_name,
+                file_path: path.to_string_lossy().to_string(),
+                duration: probe_duration(path).unwrap_or(0.0),
+                status: AudioStatus::Uploaded.to_string(),
+                channels: probe_channels(path).unwrap_or(1),
+                sha256: Some(sha256),
+            },
+            ImportStatus::Imported,
+            None,
+        ),
+    };
+
+    diesel::insert_into(audio_files::table)
+        .values(&new_audio)
+        .execute(conn)
+        .map_err(|e| format!("Failed to create audio file: {}", e))?;
+
+    let audio = audio_files::table
+        .order(audio_files::id.desc())
+        .first::<AudioFile>(conn)
+        .map_err(|e| e.to_string())?;
+
+    queue_working_copies(audio.id, None);
+    let _ = refresh_recorded_at(conn, &audio, false);
+
+    Ok(ImportFileResult {
+        audio_file_id: Some(audio.id),
+        duplicate_of,
+        ..ImportFileResult::new(path, status)
+    })
+}
@@ -2,7 +2,35 @@
 //!
 //! Contains database connection and other backend services
 
+pub mod access_service;
+pub mod activity_service;
+pub mod alert_service;
 pub mod auth_service;
+pub mod backup_service;
+pub mod bundle_service;
+pub mod chunking_service;
 pub mod database;
+pub mod document_service;
+pub mod encryption_service;
+pub mod enhancement_service;
+pub mod export_service;
+pub mod ffmpeg;
+pub mod import_service;
+pub mod notification_service;
+pub mod recording_time_service;
+pub mod redaction_service;
+pub mod report_service;
+pub mod review_service;
+pub mod spectrogram_service;
+pub mod status_service;
+pub mod storage;
+pub mod timeline_service;
+pub mod transcriber;
+pub mod vad_service;
+pub mod watch_service;
+pub mod working_copy_service;
 
+pub use backup_service::start_snapshots;
 pub use database::{get_pool, init_db};
+pub use storage::init_storage;
+pub use watch_service::start_watch_folders;
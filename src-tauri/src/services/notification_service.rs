@@ -0,0 +1,32 @@
+//! Notification service for creating notifications from backend operations
+use crate::models::NewNotification;
+use crate::schema::notifications;
+use diesel::prelude::*;
+
+/// Insert a notification using an existing connection (so it can join a transaction).
+/// `user_id` addresses it to one user; None notifies everyone.
+pub fn notify(
+    conn: &mut SqliteConnection,
+    user_id: Option<i32>,
+    notification_type: &str,
+    action: &str,
+    title: String,
+    message: Option<String>,
+    entity: Option<(&str, i32)>,
+) -> QueryResult<()> {
+    let new_notification = NewNotification {
+        notification_type: notification_type.to_string(),
+        action: action.to_string(),
+        title,
+        message,
+        entity_type: entity.map(|(entity_type, _)| entity_type.to_string()),
+        entity_id: entity.map(|(_, entity_id)| entity_id),
+        user_id,
+    };
+
+    diesel::insert_into(notifications::table)
+        .values(&new_notification)
+        .execute(conn)?;
+
+    Ok(())
+}
@@ -0,0 +1,248 @@
+//! Recording time service: when a recording really happened, from container tags,
+//! file name patterns or manual entry, and absolute times within it
+use crate::models::{AudioFile, RecordingTimePattern};
+use crate::schema::{audio_files, recording_time_patterns};
+use crate::services::ffmpeg::probe_creation_time;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDateTime, Timelike};
+use diesel::prelude::*;
+use std::path::Path;
+
+/// Container times before this year are unset-clock defaults (1904, 1970) rather
+/// than real recording times
+const EARLIEST_PLAUSIBLE_YEAR: i32 = 2000;
+
+/// A detected recording start
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedAt {
+    pub utc: NaiveDateTime,
+    pub timezone: String,
+    /// "metadata" or "filename"
+    pub source: &'static str,
+}
+
+/// Parse a UTC offset such as "+07:00", "+0700", "-05:30", "Z" or "UTC"
+pub fn parse_offset(timezone: &str) -> Result<FixedOffset, String> {
+    let tz = timezone.trim();
+    if tz.eq_ignore_ascii_case("z") || tz.eq_ignore_ascii_case("utc") {
+        return Ok(FixedOffset::east_opt(0).expect("zero offset"));
+    }
+
+    let invalid = || format!("Invalid timezone offset: {}", timezone);
+    let sign = match tz.chars().next() {
+        Some('+') => 1,
+        Some('-') => -1,
+        _ => return Err(invalid()),
+    };
+    let digits: String = tz[1..].chars().filter(|c| *c != ':').collect();
+    if digits.len() != 4 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let hours: i32 = digits[..2].parse().map_err(|_| invalid())?;
+    let minutes: i32 = digits[2..].parse().map_err(|_| invalid())?;
+    if hours > 14 || minutes > 59 {
+        return Err(invalid());
+    }
+
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).ok_or_else(invalid)
+}
+
+/// Normalize an offset to the "+07:00" form stored in the database
+pub fn normalize_offset(timezone: &str) -> Result<String, String> {
+    parse_offset(timezone).map(|offset| offset.to_string())
+}
+
+/// Convert a wall-clock time in `timezone` to UTC
+pub fn local_to_utc(local: NaiveDateTime, timezone: &str) -> Result<NaiveDateTime, String> {
+    let offset = parse_offset(timezone)?;
+    Ok(local - chrono::Duration::seconds(offset.local_minus_utc() as i64))
+}
+
+/// Convert a UTC time to wall-clock time in `timezone`
+pub fn utc_to_local(utc: NaiveDateTime, timezone: &str) -> Result<NaiveDateTime, String> {
+    let offset = parse_offset(timezone)?;
+    Ok(utc + chrono::Duration::seconds(offset.local_minus_utc() as i64))
+}
+
+/// Reject formats chrono cannot use, and formats without a date and time of day
+pub fn validate_pattern(pattern: &str) -> Result<(), String> {
+    if StrftimeItems::new(pattern).any(|item| matches!(item, Item::Error)) {
+        return Err(format!("Invalid time pattern: {}", pattern));
+    }
+    let sample = chrono::NaiveDate::from_ymd_opt(2026, 1, 3)
+        .and_then(|date| date.and_hms_opt(14, 22, 10))
+        .expect("valid sample time");
+    let rendered = sample.format(pattern).to_string();
+    match NaiveDateTime::parse_from_str(&rendered, pattern) {
+        // Seconds are optional; date, hour and minute are not
+        Ok(parsed)
+            if parsed.date() == sample.date()
+                && parsed.hour() == sample.hour()
+                && parsed.minute() == sample.minute() =>
+        {
+            Ok(())
+        }
+        _ => Err(format!(
+            "Time pattern must contain a full date and time of day: {}",
+            pattern
+        )),
+    }
+}
+
+/// Find a time written in `pattern` anywhere in a file name (extension ignored).
+/// The match must not be part of a longer run of digits.
+pub fn parse_file_name_time(file_name: &str, pattern: &str) -> Option<NaiveDateTime> {
+    let stem = Path::new(file_name).file_stem()?.to_str()?;
+
+    stem.char_indices()
+        .map(|(at, _)| at)
+        .filter(|at| !stem[..*at].ends_with(|c: char| c.is_ascii_digit()))
+        .find_map(|at| {
+            NaiveDateTime::parse_and_remainder(&stem[at..], pattern)
+                .ok()
+                .filter(|(_, rest)| !rest.starts_with(|c: char| c.is_ascii_digit()))
+                .map(|(time, _)| time)
+        })
+}
+
+/// Parse a container creation time tag. Tags without an offset are UTC by convention.
+pub fn parse_metadata_time(raw: &str) -> Option<(NaiveDateTime, String)> {
+    let raw = raw.trim();
+    if let Ok(time) = DateTime::parse_from_rfc3339(raw) {
+        return Some((time.naive_utc(), time.offset().to_string()));
+    }
+
+    [
+        "%Y-%m-%dT%H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S%.f",
+        "%Y-%m-%d %H:%M:%S",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(raw, format).ok())
+    .map(|time| (time, "+00:00".to_string()))
+}
+
+/// Whether a container time can be a real recording start: not an epoch default
+/// and not (beyond clock skew) in the future
+pub fn is_plausible_recording_time(utc: NaiveDateTime) -> bool {
+    utc.year() >= EARLIEST_PLAUSIBLE_YEAR
+        && utc <= chrono::Utc::now().naive_utc() + chrono::Duration::days(1)
+}
+
+/// Detect the start of a recording: plausible container tags first, then file
+/// name patterns
+pub fn detect_recorded_at(
+    conn: &mut SqliteConnection,
+    audio: &AudioFile,
+) -> QueryResult<Option<RecordedAt>> {
+    let tagged = probe_creation_time(Path::new(&audio.file_path))
+        .ok()
+        .flatten()
+        .and_then(|raw| parse_metadata_time(&raw))
+        .filter(|(utc, _)| is_plausible_recording_time(*utc));
+    if let Some((utc, timezone)) = tagged {
+        return Ok(Some(RecordedAt {
+            utc,
+            timezone,
+            source: "metadata",
+        }));
+    }
+
+    let patterns = recording_time_patterns::table
+        .order(recording_time_patterns::id.asc())
+        .load::<RecordingTimePattern>(conn)?;
+
+    Ok(patterns.iter().find_map(|pattern| {
+        let local = parse_file_name_time(&audio.file_name, &pattern.pattern)?;
+        let utc = local_to_utc(local, &pattern.timezone).ok()?;
+        Some(RecordedAt {
+            utc,
+            timezone: normalize_offset(&pattern.timezone).ok()?,
+            source: "filename",
+        })
+    }))
+}
+
+/// Store the detected recording start. A manually entered time is kept unless `force` is set.
+pub fn refresh_recorded_at(
+    conn: &mut SqliteConnection,
+    audio: &AudioFile,
+    force: bool,
+) -> Result<AudioFile, String> {
+    if !force && audio.recorded_at_source.as_deref() == Some("manual") {
+        return Ok(audio.clone());
+    }
+
+    let detected = detect_recorded_at(conn, audio).map_err(|e| e.to_string())?;
+    let Some(detected) = detected else {
+        return Ok(audio.clone());
+    };
+
+    diesel::update(audio_files::table.find(audio.id))
+        .set((
+            audio_files::recorded_at.eq(detected.utc),
+            audio_files::recorded_tz.eq(detected.timezone),
+            audio_files::recorded_at_source.eq(detected.source),
+        ))
+        .execute(conn)
+        .map_err(|e| format!("Failed to save recording time: {}", e))?;
+
+    audio_files::table
+        .find(audio.id)
+        .first::<AudioFile>(conn)
+        .map_err(|e| format!("Audio file not found: {}", e))
+}
+
+/// UTC time of a moment `offset_seconds` into the recording, when its start is known
+pub fn absolute_time(audio: &AudioFile, offset_seconds: f32) -> Option<NaiveDateTime> {
+    let start = audio.recorded_at?;
+    Some(start + chrono::Duration::milliseconds((offset_seconds as f64 * 1000.0).round() as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(raw: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(raw, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn test_file_name_and_metadata_times() {
+        assert_eq!(
+            parse_file_name_time("20260103_142210.amr", "%Y%m%d_%H%M%S"),
+            Some(time("2026-01-03 14:22:10"))
+        );
+        assert_eq!(
+            parse_file_name_time(
+                "Call_0901234567_2026-01-03 14-22-10.m4a",
+                "%Y-%m-%d %H-%M-%S"
+            ),
+            Some(time("2026-01-03 14:22:10"))
+        );
+        assert_eq!(
+            parse_file_name_time("call_0901234567.amr", "%Y%m%d_%H%M%S"),
+            None
+        );
+
+        assert_eq!(
+            local_to_utc(time("2026-01-03 14:22:10"), "+07:00"),
+            Ok(time("2026-01-03 07:22:10"))
+        );
+        assert_eq!(normalize_offset("-0530"), Ok("-05:30".to_string()));
+        assert!(parse_offset("Asia/Ho_Chi_Minh").is_err());
+
+        assert_eq!(
+            parse_metadata_time("2026-01-03T07:22:10.000000Z"),
+            Some((time("2026-01-03 07:22:10"), "+00:00".to_string()))
+        );
+        assert!(is_plausible_recording_time(time("2026-01-03 07:22:10")));
+        assert!(!is_plausible_recording_time(time("1970-01-01 00:00:00")));
+        assert!(!is_plausible_recording_time(time("1904-01-01 00:00:00")));
+        assert!(!is_plausible_recording_time(
+            chrono::Utc::now().naive_utc() + chrono::Duration::days(30)
+        ));
+        assert!(validate_pattern("%Y%m%d_%H%M%S").is_ok());
+        assert!(validate_pattern("%Y%m%d").is_err());
+    }
+}
@@ -0,0 +1,193 @@
+//! Redaction service: transcript masking and ffmpeg filters for redacted audio
+use crate::models::transcript_segment::encode_words;
+use crate::models::{Annotation, Redaction, TranscriptSegment};
+
+/// Replacement for redacted speech in exported transcripts
+pub const REDACTED_TEXT: &str = "[REDACTED]";
+
+/// Redaction modes accepted by `create_redaction`
+pub const REDACTION_MODES: [&str; 2] = ["silence", "tone"];
+
+fn overlaps(redaction: &Redaction, start: f32, end: f32) -> bool {
+    redaction.start_time < end && redaction.end_time > start
+}
+
+/// Mask the parts of a segment covered by redactions.
+///
+/// With word timings only the overlapping words are replaced; otherwise the
+/// whole segment text is replaced.
+pub fn mask_segment(segment: &TranscriptSegment, redactions: &[Redaction]) -> TranscriptSegment {
+    let hits: Vec<&Redaction> = redactions
+        .iter()
+        .filter(|r| r.audio_file_id == segment.audio_file_id)
+        .filter(|r| overlaps(r, segment.start_time, segment.end_time))
+        .collect();
+
+    if hits.is_empty() {
+        return segment.clone();
+    }
+
+    let mut masked = segment.clone();
+    let mut words = segment.word_timings();
+
+    if words.is_empty() {
+        masked.text = REDACTED_TEXT.to_string();
+        return masked;
+    }
+
+    let mut text: Vec<&str> = Vec::new();
+    for word in words.iter_mut() {
+        if hits.iter().any(|r| overlaps(r, word.start, word.end)) {
+            word.word = REDACTED_TEXT.to_string();
+            if text.last() != Some(&REDACTED_TEXT) {
+                text.push(REDACTED_TEXT);
+            }
+        } else {
+            text.push(&word.word);
+        }
+    }
+
+    masked.text = text.join(" ");
+    masked.words = Some(encode_words(&words));
+    masked
+}
+
+/// Mask an annotation whose time range overlaps a redaction. Notes have no word
+/// timings, so the whole body is replaced.
+pub fn mask_annotation(annotation: &Annotation, redactions: &[Redaction]) -> Annotation {
+    let mut masked = annotation.clone();
+    if redactions
+        .iter()
+        .filter(|r| r.audio_file_id == annotation.audio_file_id)
+        .any(|r| overlaps(r, annotation.start_time, annotation.end_time))
+    {
+        masked.body = REDACTED_TEXT.to_string();
+    }
+    masked
+}
+
+/// Build an ffmpeg `-filter_complex` graph that silences every redaction and
+/// overlays a 1 kHz tone on the ones in "tone" mode.
+///
+/// Expects the evidence file as input 0 and a sine source as input 1; the
+/// result is labelled `[out]`.
+pub fn redaction_filter(redactions: &[Redaction]) -> String {
+    let enable = |ranges: Vec<&Redaction>| -> String {
+        if ranges.is_empty() {
+            return "0".to_string();
+        }
+        ranges
+            .iter()
+            .map(|r| format!("between(t,{:.3},{:.3})", r.start_time, r.end_time))
+            .collect::<Vec<_>>()
+            .join("+")
+    };
+
+    let muted = enable(redactions.iter().collect());
+    let toned = enable(redactions.iter().filter(|r| r.mode == "tone").collect());
+
+    format!(
+        "[0:a]volume=0:enable='{}'[muted];\
+         [1:a]volume=0.2,volume=0:enable='not({})'[tone];\
+         [muted][tone]amix=inputs=2:duration=first:normalize=0[out]",
+        muted, toned
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::WordTiming;
+
+    fn segment(words: &[(&str, f32, f32)]) -> TranscriptSegment {
+        let timings: Vec<WordTiming> = words
+            .iter()
+            .map(|(w, s, e)| WordTiming {
+                word: w.to_string(),
+                start: *s,
+                end: *e,
+                confidence: 1.0,
+            })
+            .collect();
+
+        TranscriptSegment {
+            id: 1,
+            audio_file_id: 7,
+            speaker_id: None,
+            start_time: 10.0,
+            end_time: 14.0,
+            text: words.iter().map(|w| w.0).collect::<Vec<_>>().join(" "),
+            is_deleted: 0,
+            created_at: chrono::NaiveDateTime::default(),
+            deleted_at: None,
+            words: (!timings.is_empty()).then(|| encode_words(&timings)),
+            channel: None,
+        }
+    }
+
+    fn redaction(start_time: f32, end_time: f32) -> Redaction {
+        Redaction {
+            id: 1,
+            audio_file_id: 7,
+            start_time,
+            end_time,
+            mode: "silence".to_string(),
+            reason: "test".to_string(),
+            created_by: None,
+            created_at: chrono::NaiveDateTime::default(),
+        }
+    }
+
+    #[test]
+    fn test_mask_words_in_range() {
+        let seg = segment(&[
+            ("số", 10.0, 10.5),
+            ("tài", 10.5, 11.0),
+            ("khoản", 11.0, 11.5),
+            ("là", 11.5, 12.0),
+        ]);
+
+        let masked = mask_segment(&seg, &[redaction(10.4, 11.2)]);
+        assert_eq!(masked.text, "[REDACTED] là");
+        assert_eq!(masked.word_timings()[1].word, REDACTED_TEXT);
+    }
+
+    #[test]
+    fn test_mask_whole_segment_without_words() {
+        let mut seg = segment(&[]);
+        seg.text = "gặp nhau ở bến xe".to_string();
+
+        assert_eq!(
+            mask_segment(&seg, &[redaction(13.0, 20.0)]).text,
+            REDACTED_TEXT
+        );
+        assert_eq!(mask_segment(&seg, &[redaction(20.0, 25.0)]).text, seg.text);
+    }
+
+    #[test]
+    fn test_mask_annotation_in_range() {
+        let note = Annotation {
+            id: 1,
+            audio_file_id: 7,
+            segment_id: Some(1),
+            start_time: 10.0,
+            end_time: 14.0,
+            author_id: None,
+            body: "nhắc tới số tài khoản".to_string(),
+            tags: "[]".to_string(),
+            color: None,
+            is_resolved: 0,
+            created_at: chrono::NaiveDateTime::default(),
+            updated_at: chrono::NaiveDateTime::default(),
+        };
+
+        assert_eq!(
+            mask_annotation(&note, &[redaction(13.5, 16.0)]).body,
+            REDACTED_TEXT
+        );
+        assert_eq!(
+            mask_annotation(&note, &[redaction(14.0, 16.0)]).body,
+            note.body
+        );
+    }
+}
@@ -0,0 +1,601 @@
+//! Report service: fills a user-editable template with the evidence of a case
+use crate::models::{ActivityLog, AlertWord, Bookmark, Case, Speaker, User};
+use crate::schema::{activity_logs, alert_words, audio_files, bookmarks, cases, speakers, users};
+use crate::services::alert_service::{find_alert_hits, AlertHit};
+use crate::services::export_service::{build_transcript_export, TranscriptExport};
+use crate::services::recording_time_service::utc_to_local;
+use chrono::NaiveDateTime;
+use diesel::dsl::sql;
+use diesel::prelude::*;
+use diesel::sql_types::{Integer, Nullable};
+use std::collections::HashMap;
+
+/// Shown in place of a section that has nothing to list
+const EMPTY_SECTION: &str = "Không có dữ liệu.";
+
+/// Logged targets tied to a recording by the `audio_file_id` in their log details
+const RECORDING_TARGETS: [&str; 5] = ["segment", "annotation", "bookmark", "redaction", "review"];
+
+/// A piece of the rendered report, written out by `document_service`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReportBlock {
+    /// Level 1 to 3
+    Heading {
+        level: u8,
+        text: String,
+    },
+    Paragraph(String),
+    Table {
+        headers: Vec<String>,
+        rows: Vec<Vec<String>>,
+    },
+}
+
+/// A single value usable anywhere in a template line
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    CaseCode,
+    CaseTitle,
+    CaseDescription,
+    CaseStatus,
+    CasePriority,
+    CaseTags,
+    CaseDueDate,
+    CaseCreatedAt,
+    GeneratedAt,
+    GeneratedBy,
+    RecordingCount,
+    TotalDuration,
+}
+
+impl Field {
+    fn from_name(name: &str) -> Option<Field> {
+        Some(match name {
+            "case.code" => Field::CaseCode,
+            "case.title" => Field::CaseTitle,
+            "case.description" => Field::CaseDescription,
+            "case.status" => Field::CaseStatus,
+            "case.priority" => Field::CasePriority,
+            "case.tags" => Field::CaseTags,
+            "case.due_date" => Field::CaseDueDate,
+            "case.created_at" => Field::CaseCreatedAt,
+            "report.generated_at" => Field::GeneratedAt,
+            "report.generated_by" => Field::GeneratedBy,
+            "report.recording_count" => Field::RecordingCount,
+            "report.total_duration" => Field::TotalDuration,
+            _ => return None,
+        })
+    }
+}
+
+/// A list section that must stand on a line of its own
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Section {
+    Recordings,
+    Transcripts,
+    AlertHits,
+    Bookmarks,
+    Annotations,
+    Custody,
+}
+
+impl Section {
+    fn from_name(name: &str) -> Option<Section> {
+        Some(match name {
+            "recordings" => Section::Recordings,
+            "transcripts" => Section::Transcripts,
+            "alert_hits" => Section::AlertHits,
+            "bookmarks" => Section::Bookmarks,
+            "annotations" => Section::Annotations,
+            "custody" => Section::Custody,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Piece {
+    Text(String),
+    Field(Field),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TemplateLine {
+    Heading(u8, Vec<Piece>),
+    Paragraph(Vec<Piece>),
+    Section(Section),
+}
+
+/// Split a line into text and `{{field}}` pieces
+fn parse_pieces(line: &str, number: usize) -> Result<Vec<Piece>, String> {
+    let mut pieces = Vec::new();
+    let mut rest = line;
+
+    while let Some(open) = rest.find("{{") {
+        if open > 0 {
+            pieces.push(Piece::Text(rest[..open].to_string()));
+        }
+        let close = rest[open..]
+            .find("}}")
+            .ok_or_else(|| format!("Line {}: unclosed {{{{", number))?;
+        let name = rest[open + 2..open + close].trim();
+        match Field::from_name(name) {
+            Some(field) => pieces.push(Piece::Field(field)),
+            None if Section::from_name(name).is_some() => {
+                return Err(format!(
+                    "Line {}: {{{{{}}}}} must be on a line of its own",
+                    number, name
+                ))
+            }
+            None => {
+                return Err(format!(
+                    "Line {}: unknown placeholder {{{{{}}}}}",
+                    number, name
+                ))
+            }
+        }
+        rest = &rest[open + close + 2..];
+    }
+    if !rest.is_empty() {
+        pieces.push(Piece::Text(rest.to_string()));
+    }
+    Ok(pieces)
+}
+
+/// Parse a template. Lines starting with `#`, `##` or `###` are headings and blank lines
+/// are skipped; every other line is a paragraph. Fields such as `{{case.code}}` may appear
+/// anywhere, while sections such as `{{recordings}}` must stand on a line of their own.
+fn parse_template(body: &str) -> Result<Vec<TemplateLine>, String> {
+    let mut lines = Vec::new();
+
+    for (index, raw) in body.lines().enumerate() {
+        let number = index + 1;
+        let line = raw.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let section = line
+            .strip_prefix("{{")
+            .and_then(|inner| inner.strip_suffix("}}"))
+            .and_then(|name| Section::from_name(name.trim()));
+        if let Some(section) = section {
+            lines.push(TemplateLine::Section(section));
+            continue;
+        }
+
+        let level = line.chars().take_while(|c| *c == '#').count();
+        if level > 0 {
+            if level > 3 {
+                return Err(format!("Line {}: headings go up to ###", number));
+            }
+            let text = line[level..].trim_start();
+            lines.push(TemplateLine::Heading(
+                level as u8,
+                parse_pieces(text, number)?,
+            ));
+        } else {
+            lines.push(TemplateLine::Paragraph(parse_pieces(line, number)?));
+        }
+    }
+
+    Ok(lines)
+}
+
+/// Check a template for syntax errors and unknown placeholders
+pub fn validate_template(body: &str) -> Result<(), String> {
+    if parse_template(body)?.is_empty() {
+        return Err("Report template is empty".to_string());
+    }
+    Ok(())
+}
+
+/// Everything a case report shows
+pub struct ReportData {
+    pub case: Case,
+    pub generated_at: NaiveDateTime,
+    pub generated_by: Option<String>,
+    /// Redacted transcripts and annotations of each recording, in upload order
+    pub recordings: Vec<TranscriptExport>,
+    pub alert_hits: Vec<AlertHit>,
+    pub bookmarks: Vec<Bookmark>,
+    /// Activity on the case and its recordings, oldest first
+    pub custody: Vec<ActivityLog>,
+    pub speaker_names: HashMap<i32, String>,
+    pub user_names: HashMap<i32, String>,
+}
+
+pub fn load_report_data(
+    conn: &mut SqliteConnection,
+    case_id: i32,
+    user_id: Option<i32>,
+) -> Result<ReportData, String> {
+    let case = cases::table
+        .find(case_id)
+        .first::<Case>(conn)
+        .map_err(|e| format!("Case not found: {}", e))?;
+
+    let file_ids = audio_files::table
+        .filter(audio_files::case_id.eq(case_id))
+        .order(audio_files::id.asc())
+        .select(audio_files::id)
+        .load::<i32>(conn)
+        .map_err(|e| e.to_string())?;
+    let recordings = file_ids
+        .iter()
+        .map(|id| build_transcript_export(conn, *id, true))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Matched on the redacted text so masked words do not resurface
+    let words = alert_words::table
+        .load::<AlertWord>(conn)
+        .map_err(|e| e.to_string())?;
+    let alert_hits = recordings
+        .iter()
+        .flat_map(|export| find_alert_hits(&export.segments, &words))
+        .collect();
+
+    let bookmarks = bookmarks::table
+        .filter(bookmarks::audio_file_id.eq_any(&file_ids))
+        .order((bookmarks::audio_file_id.asc(), bookmarks::start_time.asc()))
+        .load::<Bookmark>(conn)
+        .map_err(|e| e.to_string())?;
+
+    let custody = activity_logs::table
+        .filter(
+            activity_logs::target_type
+                .eq_any(["case", "case_access"])
+                .and(activity_logs::target_id.eq(case_id))
+                .or(activity_logs::target_type
+                    .eq("audio_file")
+                    .and(activity_logs::target_id.eq_any(&file_ids)))
+                .or(activity_logs::target_type.eq_any(RECORDING_TARGETS).and(
+                    sql::<Nullable<Integer>>(
+                        "json_extract(activity_logs.details, '$.audio_file_id')",
+                    )
+                    .eq_any(&file_ids),
+                )),
+        )
+        .order((activity_logs::created_at.asc(), activity_logs::id.asc()))
+        .load::<ActivityLog>(conn)
+        .map_err(|e| e.to_string())?;
+
+    let speaker_names = speakers::table
+        .load::<Speaker>(conn)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|speaker| (speaker.id, speaker.name))
+        .collect();
+    let user_names: HashMap<i32, String> = users::table
+        .load::<User>(conn)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|user| (user.id, user.name))
+        .collect();
+
+    Ok(ReportData {
+        generated_by: user_id.and_then(|id| user_names.get(&id).cloned()),
+        case,
+        generated_at: chrono::Utc::now().naive_utc(),
+        recordings,
+        alert_hits,
+        bookmarks,
+        custody,
+        speaker_names,
+        user_names,
+    })
+}
+
+/// Offset within a recording as "hh:mm:ss"
+pub fn format_offset(seconds: f32) -> String {
+    let total = seconds.max(0.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}",
+        total / 3600,
+        total % 3600 / 60,
+        total % 60
+    )
+}
+
+fn format_utc(time: NaiveDateTime) -> String {
+    format!("{} UTC", time.format("%Y-%m-%d %H:%M:%S"))
+}
+
+/// Activity log details as "key: value; ..."; text that is not a JSON object is kept
+fn format_details(details: &str) -> String {
+    let Ok(serde_json::Value::Object(fields)) = serde_json::from_str(details) else {
+        return details.to_string();
+    };
+    fields
+        .iter()
+        .filter(|(_, value)| !value.is_null())
+        .map(|(key, value)| match value {
+            serde_json::Value::String(text) => format!("{}: {}", key, text),
+            value => format!("{}: {}", key, value),
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+impl ReportData {
+    fn file_name(&self, audio_file_id: i32) -> String {
+        self.recordings
+            .iter()
+            .find(|export| export.audio_file.id == audio_file_id)
+            .map(|export| export.audio_file.file_name.clone())
+            .unwrap_or_else(|| format!("#{}", audio_file_id))
+    }
+
+    fn user_name(&self, user_id: Option<i32>) -> String {
+        user_id
+            .and_then(|id| self.user_names.get(&id).cloned())
+            .unwrap_or_default()
+    }
+
+    fn field(&self, field: Field) -> String {
+        let case = &self.case;
+        match field {
+            Field::CaseCode => case.code.clone(),
+            Field::CaseTitle => case.title.clone(),
+            Field::CaseDescription => case.description.clone().unwrap_or_default(),
+            Field::CaseStatus => case.status.clone(),
+            Field::CasePriority => case.priority.clone(),
+            Field::CaseTags => case.tag_list().join(", "),
+            Field::CaseDueDate => case
+                .due_date
+                .map(|date| date.to_string())
+                .unwrap_or_default(),
+            Field::CaseCreatedAt => format_utc(case.created_at),
+            Field::GeneratedAt => format_utc(self.generated_at),
+            Field::GeneratedBy => self.generated_by.clone().unwrap_or_default(),
+            Field::RecordingCount => self.recordings.len().to_string(),
+            Field::TotalDuration => format_offset(
+                self.recordings
+                    .iter()
+                    .map(|export| export.audio_file.duration)
+                    .sum(),
+            ),
+        }
+    }
+
+    /// Fill in the fields of a line. None when the line has fields and all are empty,
+    /// so lines such as "Due: {{case.due_date}}" disappear instead of dangling.
+    fn fill(&self, pieces: &[Piece]) -> Option<String> {
+        let mut text = String::new();
+        let mut has_fields = false;
+        let mut has_values = false;
+        for piece in pieces {
+            match piece {
+                Piece::Text(literal) => text.push_str(literal),
+                Piece::Field(field) => {
+                    let value = self.field(*field);
+                    has_fields = true;
+                    has_values |= !value.trim().is_empty();
+                    text.push_str(&value);
+                }
+            }
+        }
+        (!has_fields || has_values).then_some(text)
+    }
+
+    fn table(&self, headers: &[&str], rows: Vec<Vec<String>>) -> Vec<ReportBlock> {
+        if rows.is_empty() {
+            return vec![ReportBlock::Paragraph(EMPTY_SECTION.to_string())];
+        }
+        vec![ReportBlock::Table {
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows,
+        }]
+    }
+
+    fn section(&self, section: Section) -> Vec<ReportBlock> {
+        match section {
+            Section::Recordings => self.table(
+                &["#", "Tệp ghi âm", "Thời lượng", "Thời điểm ghi", "SHA-256"],
+                self.recordings
+                    .iter()
+                    .enumerate()
+                    .map(|(index, export)| {
+                        let audio = &export.audio_file;
+                        let recorded = match (audio.recorded_at, audio.recorded_tz.as_deref()) {
+                            (Some(utc), Some(tz)) => utc_to_local(utc, tz)
+                                .map(|local| {
+                                    format!("{} ({})", local.format("%Y-%m-%d %H:%M:%S"), tz)
+                                })
+                                .unwrap_or_else(|_| format_utc(utc)),
+                            (Some(utc), None) => format_utc(utc),
+                            _ => String::new(),
+                        };
+                        vec![
+                            (index + 1).to_string(),
+                            audio.file_name.clone(),
+                            format_offset(audio.duration),
+                            recorded,
+                            audio.sha256.clone().unwrap_or_default(),
+                        ]
+                    })
+                    .collect(),
+            ),
+            Section::Transcripts => {
+                let mut blocks = Vec::new();
+                for export in &self.recordings {
+                    blocks.push(ReportBlock::Heading {
+                        level: 3,
+                        text: export.audio_file.file_name.clone(),
+                    });
+                    if export.segments.is_empty() {
+                        blocks.push(ReportBlock::Paragraph(EMPTY_SECTION.to_string()));
+                    }
+                    for segment in &export.segments {
+                        let speaker = segment
+                            .speaker_id
+                            .and_then(|id| self.speaker_names.get(&id).cloned())
+                            .or_else(|| segment.channel.map(|c| format!("Kênh {}", c + 1)))
+                            .unwrap_or_else(|| "Không rõ".to_string());
+                        blocks.push(ReportBlock::Paragraph(format!(
+                            "[{} - {}] {}: {}",
+                            format_offset(segment.start_time),
+                            format_offset(segment.end_time),
+                            speaker,
+                            segment.text.trim()
+                        )));
+                    }
+                }
+                if blocks.is_empty() {
+                    blocks.push(ReportBlock::Paragraph(EMPTY_SECTION.to_string()));
+                }
+                blocks
+            }
+            Section::AlertHits => self.table(
+                &["Tệp ghi âm", "Thời điểm", "Từ khóa", "Nhóm", "Nội dung"],
+                self.alert_hits
+                    .iter()
+                    .map(|hit| {
+                        vec![
+                            self.file_name(hit.audio_file_id),
+                            format_offset(hit.start_time),
+                            hit.keyword.clone(),
+                            hit.category.clone(),
+                            hit.text.clone(),
+                        ]
+                    })
+                    .collect(),
+            ),
+            Section::Bookmarks => self.table(
+                &["Tệp ghi âm", "Thời điểm", "Mức độ", "Tiêu đề", "Mô tả"],
+                self.bookmarks
+                    .iter()
+                    .map(|bookmark| {
+                        vec![
+                            self.file_name(bookmark.audio_file_id),
+                            format!(
+                                "{} - {}",
+                                format_offset(bookmark.start_time),
+                                format_offset(bookmark.end_time)
+                            ),
+                            bookmark.severity.clone(),
+                            bookmark.title.clone(),
+                            bookmark.description.clone().unwrap_or_default(),
+                        ]
+                    })
+                    .collect(),
+            ),
+            Section::Annotations => self.table(
+                &["Tệp ghi âm", "Thời điểm", "Người ghi", "Nội dung", "Nhãn"],
+                self.recordings
+                    .iter()
+                    .flat_map(|export| &export.annotations)
+                    .map(|annotation| {
+                        vec![
+                            self.file_name(annotation.audio_file_id),
+                            format!(
+                                "{} - {}",
+                                format_offset(annotation.start_time),
+                                format_offset(annotation.end_time)
+                            ),
+                            self.user_name(annotation.author_id),
+                            annotation.body.clone(),
+                            annotation.tag_list().join(", "),
+                        ]
+                    })
+                    .collect(),
+            ),
+            Section::Custody => self.table(
+                &[
+                    "Thời gian",
+                    "Người dùng",
+                    "Hành động",
+                    "Đối tượng",
+                    "Chi tiết",
+                ],
+                self.custody
+                    .iter()
+                    .map(|log| {
+                        let target = match (log.target_type.as_str(), log.target_id) {
+                            ("audio_file", Some(id)) => self.file_name(id),
+                            (kind, Some(id)) => format!("{} #{}", kind, id),
+                            (kind, None) => kind.to_string(),
+                        };
+                        vec![
+                            format_utc(log.created_at),
+                            self.user_name(log.user_id),
+                            log.action.clone(),
+                            target,
+                            log.details
+                                .as_deref()
+                                .map(format_details)
+                                .unwrap_or_default(),
+                        ]
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// Render a template into report blocks
+pub fn render_template(body: &str, data: &ReportData) -> Result<Vec<ReportBlock>, String> {
+    let mut blocks = Vec::new();
+    for line in parse_template(body)? {
+        match line {
+            TemplateLine::Heading(level, pieces) => {
+                if let Some(text) = data.fill(&pieces) {
+                    blocks.push(ReportBlock::Heading { level, text });
+                }
+            }
+            TemplateLine::Paragraph(pieces) => {
+                if let Some(text) = data.fill(&pieces) {
+                    blocks.push(ReportBlock::Paragraph(text));
+                }
+            }
+            TemplateLine::Section(section) => blocks.extend(data.section(section)),
+        }
+    }
+    Ok(blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_template() {
+        let lines =
+            parse_template("# Case {{ case.code }}\n\n{{recordings}}\nBy {{report.generated_by}}")
+                .unwrap();
+        assert_eq!(
+            lines,
+            vec![
+                TemplateLine::Heading(
+                    1,
+                    vec![
+                        Piece::Text("Case ".to_string()),
+                        Piece::Field(Field::CaseCode)
+                    ]
+                ),
+                TemplateLine::Section(Section::Recordings),
+                TemplateLine::Paragraph(vec![
+                    Piece::Text("By ".to_string()),
+                    Piece::Field(Field::GeneratedBy)
+                ]),
+            ]
+        );
+
+        assert!(parse_template("Files: {{recordings}}").is_err());
+        assert!(parse_template("{{case.unknown}}").is_err());
+        assert!(parse_template("Open {{case.code").is_err());
+        assert!(parse_template("#### Too deep").is_err());
+        assert_eq!(format_offset(3723.4), "01:02:03");
+    }
+
+    #[test]
+    fn test_format_details() {
+        assert_eq!(
+            format_details(
+                r#"{"operation":"grant_access","member_id":4,"from":null,"to":"editor"}"#
+            ),
+            "member_id: 4; operation: grant_access; to: editor"
+        );
+        assert_eq!(format_details("plain note"), "plain note");
+    }
+}
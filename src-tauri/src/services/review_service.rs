@@ -0,0 +1,21 @@
+//! Review service: locking of approved transcripts
+use crate::schema::audio_files;
+use crate::services::status_service::AudioStatus;
+use diesel::prelude::*;
+
+/// Reject transcript edits once the audio file is approved (an admin must reopen it first)
+pub fn ensure_transcript_unlocked(
+    conn: &mut SqliteConnection,
+    audio_file_id: i32,
+) -> Result<(), String> {
+    let status = audio_files::table
+        .find(audio_file_id)
+        .select(audio_files::status)
+        .first::<String>(conn)
+        .map_err(|e| format!("Audio file not found: {}", e))?;
+
+    if status == AudioStatus::Approved.as_str() {
+        return Err("Transcript is approved and locked; an admin must reopen it".to_string());
+    }
+    Ok(())
+}
@@ -0,0 +1,309 @@
+//! Spectrogram service: STFT magnitude images rendered to PNG
+use crate::services::storage::sha256_hex;
+use rustfft::num_complex::Complex;
+use rustfft::FftPlanner;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowKind {
+    Hann,
+    Hamming,
+    Blackman,
+    Rectangular,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrequencyScale {
+    Linear,
+    Mel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorMap {
+    Grayscale,
+    Viridis,
+    Inferno,
+}
+
+/// Rendering parameters; part of the tile cache key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpectrogramParams {
+    pub fft_size: usize,
+    pub window: WindowKind,
+    pub scale: FrequencyScale,
+    pub colormap: ColorMap,
+    /// Sample rate audio is decoded at (Nyquist = top of the image)
+    pub sample_rate: u32,
+    /// Image height in pixels
+    pub height: u32,
+    /// Horizontal resolution (one STFT frame per pixel column)
+    pub pixels_per_second: u32,
+    /// Dynamic range mapped onto the colour map, in dBFS
+    pub min_db: f32,
+    pub max_db: f32,
+    /// Duration covered by one tile, in seconds
+    pub tile_seconds: f32,
+}
+
+impl Default for SpectrogramParams {
+    fn default() -> Self {
+        Self {
+            fft_size: 1024,
+            window: WindowKind::Hann,
+            scale: FrequencyScale::Linear,
+            colormap: ColorMap::Viridis,
+            sample_rate: 16000,
+            height: 256,
+            pixels_per_second: 100,
+            min_db: -90.0,
+            max_db: 0.0,
+            tile_seconds: 10.0,
+        }
+    }
+}
+
+impl SpectrogramParams {
+    /// Clamp user input to sane values (FFT size to a power of two)
+    pub fn normalized(mut self) -> Self {
+        self.fft_size = self.fft_size.clamp(128, 8192).next_power_of_two();
+        self.sample_rate = self.sample_rate.clamp(4000, 96000);
+        self.height = self.height.clamp(32, 2048);
+        self.pixels_per_second = self.pixels_per_second.clamp(1, 1000);
+        self.tile_seconds = self.tile_seconds.clamp(1.0, 600.0);
+        if self.max_db <= self.min_db {
+            self.max_db = self.min_db + 1.0;
+        }
+        self
+    }
+}
+
+/// Time span of tile `index`, clipped to the recording; `None` past its end.
+/// A `duration` of 0 means unknown, in which case tiles are not clipped.
+pub fn tile_span(index: u32, tile_seconds: f32, duration: f32) -> Option<(f32, f32)> {
+    let start = index as f32 * tile_seconds;
+    let end = start + tile_seconds;
+    if duration <= 0.0 {
+        return Some((start, end));
+    }
+    (start < duration).then_some((start, end.min(duration)))
+}
+
+/// Cache directory name for a parameter set and the current version of the source
+/// file, so re-rendered working copies and changed settings never reuse stale tiles
+pub fn cache_key(params: &SpectrogramParams, source: &Path) -> Result<String, String> {
+    let metadata = std::fs::metadata(source)
+        .map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|since| since.as_nanos())
+        .unwrap_or(0);
+
+    let identity = serde_json::json!({
+        "params": params,
+        "source": source.to_string_lossy(),
+        "size": metadata.len(),
+        "modified": modified.to_string(),
+    });
+    Ok(sha256_hex(identity.to_string().as_bytes())[..16].to_string())
+}
+
+fn window_coefficients(kind: WindowKind, size: usize) -> Vec<f32> {
+    let n = (size - 1) as f32;
+    (0..size)
+        .map(|i| {
+            let x = 2.0 * std::f32::consts::PI * i as f32 / n;
+            match kind {
+                WindowKind::Hann => 0.5 - 0.5 * x.cos(),
+                WindowKind::Hamming => 0.54 - 0.46 * x.cos(),
+                WindowKind::Blackman => 0.42 - 0.5 * x.cos() + 0.08 * (2.0 * x).cos(),
+                WindowKind::Rectangular => 1.0,
+            }
+        })
+        .collect()
+}
+
+fn hz_to_mel(hz: f32) -> f32 {
+    2595.0 * (1.0 + hz / 700.0).log10()
+}
+
+fn mel_to_hz(mel: f32) -> f32 {
+    700.0 * (10f32.powf(mel / 2595.0) - 1.0)
+}
+
+/// Frequency (Hz) shown by image row `row` (0 = top)
+fn row_frequency(params: &SpectrogramParams, row: u32) -> f32 {
+    let nyquist = params.sample_rate as f32 / 2.0;
+    let fraction = 1.0 - (row as f32 + 0.5) / params.height as f32;
+    match params.scale {
+        FrequencyScale::Linear => fraction * nyquist,
+        FrequencyScale::Mel => mel_to_hz(fraction * hz_to_mel(nyquist)),
+    }
+}
+
+/// Linear interpolation over colour map control points
+fn colorize(colormap: ColorMap, value: f32) -> [u8; 3] {
+    const VIRIDIS: [[u8; 3]; 5] = [
+        [68, 1, 84],
+        [59, 82, 139],
+        [33, 145, 140],
+        [94, 201, 98],
+        [253, 231, 37],
+    ];
+    const INFERNO: [[u8; 3]; 5] = [
+        [0, 0, 4],
+        [87, 16, 110],
+        [188, 55, 84],
+        [249, 142, 9],
+        [252, 255, 164],
+    ];
+
+    let value = value.clamp(0.0, 1.0);
+    let stops: &[[u8; 3]] = match colormap {
+        ColorMap::Grayscale => {
+            let v = (value * 255.0) as u8;
+            return [v, v, v];
+        }
+        ColorMap::Viridis => &VIRIDIS,
+        ColorMap::Inferno => &INFERNO,
+    };
+
+    let position = value * (stops.len() - 1) as f32;
+    let index = (position as usize).min(stops.len() - 2);
+    let t = position - index as f32;
+    let mut rgb = [0u8; 3];
+    for (c, channel) in rgb.iter_mut().enumerate() {
+        let a = stops[index][c] as f32;
+        let b = stops[index + 1][c] as f32;
+        *channel = (a + (b - a) * t).round() as u8;
+    }
+    rgb
+}
+
+/// Render samples into an RGB image (row-major, top = highest frequency).
+/// Returns (width, height, pixels).
+pub fn render(samples: &[f32], params: &SpectrogramParams) -> (u32, u32, Vec<u8>) {
+    let fft_size = params.fft_size;
+    let hop = (params.sample_rate / params.pixels_per_second).max(1) as usize;
+    let width = (samples.len() / hop).max(1) as u32;
+    let height = params.height;
+
+    let window = window_coefficients(params.window, fft_size);
+    let window_gain: f32 = window.iter().sum::<f32>() / 2.0;
+    let fft = FftPlanner::<f32>::new().plan_fft_forward(fft_size);
+    let bin_hz = params.sample_rate as f32 / fft_size as f32;
+    let row_bins: Vec<f32> = (0..height)
+        .map(|row| row_frequency(params, row) / bin_hz)
+        .collect();
+
+    let mut pixels = vec![0u8; (width * height * 3) as usize];
+    let mut buffer = vec![Complex::new(0.0f32, 0.0); fft_size];
+
+    for x in 0..width as usize {
+        // Centre the analysis window on the column
+        let centre = x * hop + hop / 2;
+        for (i, slot) in buffer.iter_mut().enumerate() {
+            let sample = (centre + i)
+                .checked_sub(fft_size / 2)
+                .and_then(|idx| samples.get(idx))
+                .copied()
+                .unwrap_or(0.0);
+            *slot = Complex::new(sample * window[i], 0.0);
+        }
+        fft.process(&mut buffer);
+
+        let magnitudes: Vec<f32> = buffer[..fft_size / 2 + 1]
+            .iter()
+            .map(|c| 20.0 * (c.norm() / window_gain + 1e-10).log10())
+            .collect();
+
+        for (row, bin) in row_bins.iter().enumerate() {
+            let low = (bin.floor() as usize).min(magnitudes.len() - 1);
+            let high = (low + 1).min(magnitudes.len() - 1);
+            let t = bin - low as f32;
+            let db = magnitudes[low] * (1.0 - t) + magnitudes[high] * t;
+            let value = (db - params.min_db) / (params.max_db - params.min_db);
+
+            let offset = (row * width as usize + x) * 3;
+            pixels[offset..offset + 3].copy_from_slice(&colorize(params.colormap, value));
+        }
+    }
+
+    (width, height, pixels)
+}
+
+/// Write an RGB image to a PNG file
+pub fn write_png(path: &Path, width: u32, height: u32, pixels: &[u8]) -> Result<(), String> {
+    let file = std::fs::File::create(path).map_err(|e| e.to_string())?;
+    let mut encoder = png::Encoder::new(std::io::BufWriter::new(file), width, height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    writer
+        .write_image_data(pixels)
+        .map_err(|e| format!("Failed to write PNG: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tile_span_clips_last_tile() {
+        assert_eq!(tile_span(0, 10.0, 25.0), Some((0.0, 10.0)));
+        assert_eq!(tile_span(2, 10.0, 25.0), Some((20.0, 25.0)));
+        assert_eq!(tile_span(3, 10.0, 25.0), None);
+        assert_eq!(tile_span(3, 10.0, 0.0), Some((30.0, 40.0)));
+    }
+
+    #[test]
+    fn test_render_width_follows_samples() {
+        let params = SpectrogramParams::default();
+        // 5 s of a 1 kHz tone: one column per hop
+        let samples: Vec<f32> = (0..params.sample_rate as usize * 5)
+            .map(|i| (i as f32 * 2.0 * std::f32::consts::PI * 1000.0 / 16000.0).sin())
+            .collect();
+
+        let (width, height, pixels) = render(&samples, &params);
+        assert_eq!(width, 5 * params.pixels_per_second);
+        assert_eq!(height, params.height);
+        assert_eq!(pixels.len(), (width * height * 3) as usize);
+
+        // The tone row is brighter than the top row
+        let row = |r: u32| &pixels[((r * width + width / 2) * 3) as usize..][..3];
+        let tone_row = ((1.0 - 1000.0 / 8000.0) * params.height as f32) as u32;
+        let sum = |p: &[u8]| p.iter().map(|&c| c as u32).sum::<u32>();
+        assert!(sum(row(tone_row)) > sum(row(0)));
+    }
+
+    #[test]
+    fn test_cache_key_tracks_source_and_params() {
+        let dir = std::env::temp_dir().join(format!("spexor_spectrogram_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("source.wav");
+        std::fs::write(&source, b"first").unwrap();
+
+        let params = SpectrogramParams::default();
+        let key = cache_key(&params, &source).unwrap();
+        assert_eq!(key, cache_key(&params, &source).unwrap());
+
+        let other_params = SpectrogramParams {
+            fft_size: 2048,
+            ..params.clone()
+        };
+        assert_ne!(key, cache_key(&other_params, &source).unwrap());
+
+        std::fs::write(&source, b"second, longer").unwrap();
+        assert_ne!(key, cache_key(&params, &source).unwrap());
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
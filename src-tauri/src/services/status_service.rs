@@ -0,0 +1,209 @@
+//! Audio status service: the lifecycle of an audio file as a validated state machine
+use crate::models::{AudioFile, NewAudioStatusChange};
+use crate::schema::{audio_files, audio_status_history};
+use crate::services::activity_service::log_activity;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioStatus {
+    Uploaded,
+    Queued,
+    Processing,
+    Transcribed,
+    Reviewed,
+    Approved,
+    Failed,
+    Archived,
+}
+
+impl AudioStatus {
+    pub const ALL: [AudioStatus; 8] = [
+        AudioStatus::Uploaded,
+        AudioStatus::Queued,
+        AudioStatus::Processing,
+        AudioStatus::Transcribed,
+        AudioStatus::Reviewed,
+        AudioStatus::Approved,
+        AudioStatus::Failed,
+        AudioStatus::Archived,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AudioStatus::Uploaded => "uploaded",
+            AudioStatus::Queued => "queued",
+            AudioStatus::Processing => "processing",
+            AudioStatus::Transcribed => "transcribed",
+            AudioStatus::Reviewed => "reviewed",
+            AudioStatus::Approved => "approved",
+            AudioStatus::Failed => "failed",
+            AudioStatus::Archived => "archived",
+        }
+    }
+
+    /// Statuses this one may move to
+    pub fn allowed_transitions(&self) -> &'static [AudioStatus] {
+        use AudioStatus::*;
+        match self {
+            Uploaded => &[Queued, Processing, Failed, Archived],
+            Queued => &[Uploaded, Processing, Failed, Archived],
+            Processing => &[Transcribed, Failed],
+            Transcribed => &[Queued, Processing, Reviewed, Archived],
+            Reviewed => &[Transcribed, Approved, Archived],
+            // Only reopening (admin only) unlocks an approved transcript
+            Approved => &[Transcribed],
+            Failed => &[Uploaded, Queued, Processing, Archived],
+            Archived => &[Uploaded],
+        }
+    }
+
+    pub fn can_transition_to(&self, next: AudioStatus) -> bool {
+        self.allowed_transitions().contains(&next)
+    }
+}
+
+impl std::fmt::Display for AudioStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl std::str::FromStr for AudioStatus {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        AudioStatus::ALL
+            .into_iter()
+            .find(|status| status.as_str() == value)
+            .ok_or_else(|| format!("Invalid audio status: {}", value))
+    }
+}
+
+/// Check that an audio file may move to a status, returning the status it moves from
+pub fn check_transition(audio: &AudioFile, to: AudioStatus) -> Result<AudioStatus, String> {
+    let from: AudioStatus = audio.status.parse()?;
+    if !from.can_transition_to(to) {
+        return Err(format!("Illegal status transition: {} -> {}", from, to));
+    }
+    Ok(from)
+}
+
+/// `check_transition` for the generic status commands. Approval comes only from an
+/// approved review and only an admin may reopen an approved transcript, both through
+/// their own commands.
+pub fn check_manual_transition(audio: &AudioFile, to: AudioStatus) -> Result<AudioStatus, String> {
+    let from = check_transition(audio, to)?;
+    if to == AudioStatus::Approved {
+        return Err("Transcripts can only be approved through a review".to_string());
+    }
+    if from == AudioStatus::Approved {
+        return Err("Transcript is approved and locked; an admin must reopen it".to_string());
+    }
+    Ok(from)
+}
+
+/// Apply a transition validated by `check_transition` inside the caller's
+/// transaction. Fails with `RollbackTransaction` if the status changed meanwhile.
+pub fn record_transition(
+    conn: &mut SqliteConnection,
+    audio_file_id: i32,
+    from: AudioStatus,
+    to: AudioStatus,
+    user_id: Option<i32>,
+    reason: Option<String>,
+) -> QueryResult<AudioFile> {
+    let change = NewAudioStatusChange {
+        audio_file_id,
+        from_status: from.to_string(),
+        to_status: to.to_string(),
+        reason,
+        changed_by: user_id,
+    };
+
+    // Guard on the old status so concurrent transitions cannot both apply
+    let updated = diesel::update(
+        audio_files::table
+            .find(audio_file_id)
+            .filter(audio_files::status.eq(from.as_str())),
+    )
+    .set(audio_files::status.eq(to.as_str()))
+    .execute(conn)?;
+    if updated == 0 {
+        return Err(diesel::result::Error::RollbackTransaction);
+    }
+
+    diesel::insert_into(audio_status_history::table)
+        .values(&change)
+        .execute(conn)?;
+
+    log_activity(
+        conn,
+        user_id,
+        "edit",
+        "audio_file",
+        Some(audio_file_id),
+        Some(
+            serde_json::json!({
+                "operation": "status_change",
+                "from": change.from_status,
+                "to": change.to_status,
+                "reason": change.reason,
+            })
+            .to_string(),
+        ),
+    )?;
+
+    audio_files::table
+        .find(audio_file_id)
+        .first::<AudioFile>(conn)
+}
+
+/// Error message of a failed `record_transition` transaction
+pub fn transition_error(e: diesel::result::Error) -> String {
+    match e {
+        diesel::result::Error::RollbackTransaction => {
+            "Audio file status changed concurrently, please retry".to_string()
+        }
+        e => format!("Failed to change audio status: {}", e),
+    }
+}
+
+/// Move an audio file to another status, recording who did it and when.
+/// Illegal transitions are rejected.
+pub fn transition_audio_status(
+    conn: &mut SqliteConnection,
+    audio_file_id: i32,
+    to: AudioStatus,
+    user_id: Option<i32>,
+    reason: Option<String>,
+) -> Result<AudioFile, String> {
+    let audio = audio_files::table
+        .find(audio_file_id)
+        .first::<AudioFile>(conn)
+        .map_err(|e| format!("Audio file not found: {}", e))?;
+
+    let from = check_transition(&audio, to)?;
+
+    conn.transaction(|conn| record_transition(conn, audio_file_id, from, to, user_id, reason))
+        .map_err(transition_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_transitions() {
+        assert!(AudioStatus::Uploaded.can_transition_to(AudioStatus::Queued));
+        assert!(AudioStatus::Reviewed.can_transition_to(AudioStatus::Approved));
+        assert!(!AudioStatus::Uploaded.can_transition_to(AudioStatus::Approved));
+        assert!(!AudioStatus::Processing.can_transition_to(AudioStatus::Archived));
+        assert!(!AudioStatus::Approved.can_transition_to(AudioStatus::Approved));
+        assert!(!AudioStatus::Approved.can_transition_to(AudioStatus::Archived));
+
+        assert_eq!("queued".parse::<AudioStatus>(), Ok(AudioStatus::Queued));
+        assert!("pending".parse::<AudioStatus>().is_err());
+    }
+}
@@ -0,0 +1,53 @@
+//! Storage service for files the backend generates under the app data directory
+use crate::services::encryption_service::open_plaintext;
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+static APP_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Remember the app data directory (called once at startup, before init_db)
+pub fn init_storage(app_dir: &Path) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    APP_DIR
+        .set(app_dir.to_path_buf())
+        .map_err(|_| "Failed to set app data dir")?;
+    Ok(())
+}
+
+/// Get the app data directory
+pub fn app_dir() -> &'static Path {
+    APP_DIR
+        .get()
+        .expect("Storage not initialized. Call init_storage first.")
+}
+
+/// Get (and create if missing) a subdirectory of the app data directory, e.g. "clips"
+pub fn storage_dir(name: &str) -> std::io::Result<PathBuf> {
+    let dir = app_dir().join(name);
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Compute the SHA-256 of a file's content as a lowercase hex string. Sealed files
+/// are hashed decrypted, so hashes survive encryption and key rotation.
+pub fn sha256_file(path: &Path) -> std::io::Result<String> {
+    let mut file = open_plaintext(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Compute the SHA-256 of a byte slice as a lowercase hex string
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
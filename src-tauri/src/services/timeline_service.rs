@@ -0,0 +1,307 @@
+//! Timeline service: merges the key moments of a case's recordings
+use crate::models::{Annotation, AudioFile, Bookmark, Redaction, TranscriptSegment};
+use crate::schema::{annotations, audio_files, bookmarks, redactions, transcript_segments};
+use crate::services::alert_service::load_case_alert_hits;
+use crate::services::recording_time_service::absolute_time;
+use crate::services::redaction_service::mask_segment;
+use diesel::prelude::*;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// One moment of the case timeline: a bookmark, an alert hit or an annotation
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineEvent {
+    /// "bookmark", "alert_hit" or "annotation"
+    pub kind: String,
+    /// Id of the bookmark, alert word or annotation
+    pub source_id: i32,
+    pub audio_file_id: i32,
+    pub file_name: String,
+    pub segment_id: Option<i32>,
+    /// Offset within the recording in seconds
+    pub start_time: f32,
+    pub end_time: f32,
+    /// Real-world time of the moment, when the recording's start time is known
+    pub absolute_time: Option<chrono::NaiveDateTime>,
+    pub title: String,
+    pub detail: Option<String>,
+    /// Bookmark severity
+    pub severity: Option<String>,
+    /// Alert word category
+    pub category: Option<String>,
+}
+
+/// Chronological order: moments with a real-world time first, then the rest
+/// grouped by recording and ordered by offset
+pub fn sort_timeline(events: &mut [TimelineEvent]) {
+    events.sort_by(|a, b| {
+        a.absolute_time
+            .is_none()
+            .cmp(&b.absolute_time.is_none())
+            .then(a.absolute_time.cmp(&b.absolute_time))
+            .then(a.audio_file_id.cmp(&b.audio_file_id))
+            .then(
+                a.start_time
+                    .partial_cmp(&b.start_time)
+                    .unwrap_or(Ordering::Equal),
+            )
+    });
+}
+
+pub fn build_case_timeline(
+    conn: &mut SqliteConnection,
+    case_id: i32,
+) -> QueryResult<Vec<TimelineEvent>> {
+    let files: HashMap<i32, AudioFile> = audio_files::table
+        .filter(audio_files::case_id.eq(case_id))
+        .load::<AudioFile>(conn)?
+        .into_iter()
+        .map(|audio| (audio.id, audio))
+        .collect();
+    let file_ids: Vec<i32> = files.keys().copied().collect();
+
+    let event = |kind: &str, source_id: i32, audio_file_id: i32, start: f32, end: f32| {
+        let audio = files.get(&audio_file_id);
+        TimelineEvent {
+            kind: kind.to_string(),
+            source_id,
+            audio_file_id,
+            file_name: audio.map(|a| a.file_name.clone()).unwrap_or_default(),
+            segment_id: None,
+            start_time: start,
+            end_time: end,
+            absolute_time: audio.and_then(|a| absolute_time(a, start)),
+            title: String::new(),
+            detail: None,
+            severity: None,
+            category: None,
+        }
+    };
+
+    let mut events = Vec::new();
+
+    for bookmark in bookmarks::table
+        .filter(bookmarks::audio_file_id.eq_any(&file_ids))
+        .load::<Bookmark>(conn)?
+    {
+        events.push(TimelineEvent {
+            title: bookmark.title,
+            detail: bookmark.description,
+            severity: Some(bookmark.severity),
+            ..event(
+                "bookmark",
+                bookmark.id,
+                bookmark.audio_file_id,
+                bookmark.start_time,
+                bookmark.end_time,
+            )
+        });
+    }
+
+    let hits = load_case_alert_hits(conn, case_id)?;
+    let ranges = redactions::table
+        .filter(redactions::audio_file_id.eq_any(&file_ids))
+        .load::<Redaction>(conn)?;
+    let hit_ids: Vec<i32> = hits.iter().map(|hit| hit.segment_id).collect();
+    // The hit detail quotes the segment, so redacted speech is masked as in exports
+    let masked: HashMap<i32, String> = transcript_segments::table
+        .filter(transcript_segments::id.eq_any(&hit_ids))
+        .load::<TranscriptSegment>(conn)?
+        .iter()
+        .map(|segment| (segment.id, mask_segment(segment, &ranges).text))
+        .collect();
+
+    for hit in hits {
+        events.push(TimelineEvent {
+            segment_id: Some(hit.segment_id),
+            title: hit.keyword,
+            detail: masked.get(&hit.segment_id).cloned(),
+            category: Some(hit.category),
+            ..event(
+                "alert_hit",
+                hit.alert_word_id,
+                hit.audio_file_id,
+                hit.start_time,
+                hit.end_time,
+            )
+        });
+    }
+
+    for annotation in annotations::table
+        .filter(annotations::audio_file_id.eq_any(&file_ids))
+        .load::<Annotation>(conn)?
+    {
+        let tags = annotation.tag_list();
+        events.push(TimelineEvent {
+            segment_id: annotation.segment_id,
+            title: annotation.body,
+            detail: (!tags.is_empty()).then(|| tags.join(", ")),
+            ..event(
+                "annotation",
+                annotation.id,
+                annotation.audio_file_id,
+                annotation.start_time,
+                annotation.end_time,
+            )
+        });
+    }
+
+    sort_timeline(&mut events);
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{
+        NewAlertWord, NewAudioFile, NewBookmark, NewCase, NewRedaction, NewTranscriptSegment,
+    };
+    use crate::schema::{alert_words, cases};
+    use crate::services::database::MIGRATIONS;
+    use diesel_migrations::MigrationHarness;
+
+    fn time(raw: &str) -> chrono::NaiveDateTime {
+        chrono::NaiveDateTime::parse_from_str(raw, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    fn timeline_event(
+        audio_file_id: i32,
+        start_time: f32,
+        absolute: Option<&str>,
+    ) -> TimelineEvent {
+        TimelineEvent {
+            kind: "bookmark".to_string(),
+            source_id: 1,
+            audio_file_id,
+            file_name: String::new(),
+            segment_id: None,
+            start_time,
+            end_time: start_time,
+            absolute_time: absolute.map(time),
+            title: String::new(),
+            detail: None,
+            severity: None,
+            category: None,
+        }
+    }
+
+    #[test]
+    fn test_sort_timeline() {
+        let mut events = vec![
+            timeline_event(2, 5.0, None),
+            timeline_event(1, 30.0, Some("2026-01-03 14:22:40")),
+            timeline_event(1, 9.0, None),
+            timeline_event(3, 0.0, Some("2026-01-03 09:00:00")),
+            timeline_event(1, 2.0, None),
+        ];
+        sort_timeline(&mut events);
+
+        let order: Vec<(i32, f32)> = events
+            .iter()
+            .map(|e| (e.audio_file_id, e.start_time))
+            .collect();
+        assert_eq!(
+            order,
+            vec![(3, 0.0), (1, 30.0), (1, 2.0), (1, 9.0), (2, 5.0)]
+        );
+    }
+
+    #[test]
+    fn test_build_case_timeline() {
+        let mut conn = SqliteConnection::establish(":memory:").unwrap();
+        conn.run_pending_migrations(MIGRATIONS).unwrap();
+
+        diesel::insert_into(cases::table)
+            .values(&NewCase {
+                code: "VA-01".to_string(),
+                title: "Timeline".to_string(),
+                description: None,
+                status: "open".to_string(),
+                priority: "normal".to_string(),
+                tags: "[]".to_string(),
+                due_date: None,
+            })
+            .execute(&mut conn)
+            .unwrap();
+        let case_id = cases::table
+            .select(cases::id)
+            .first::<i32>(&mut conn)
+            .unwrap();
+
+        diesel::insert_into(audio_files::table)
+            .values(&NewAudioFile {
+                case_id,
+                file_name: "call.wav".to_string(),
+                file_path: "/evidence/call.wav".to_string(),
+                duration: 60.0,
+                status: "transcribed".to_string(),
+                channels: 1,
+                sha256: None,
+            })
+            .execute(&mut conn)
+            .unwrap();
+        let audio_file_id = audio_files::table
+            .select(audio_files::id)
+            .first::<i32>(&mut conn)
+            .unwrap();
+        diesel::update(audio_files::table.find(audio_file_id))
+            .set(audio_files::recorded_at.eq(time("2026-01-03 14:22:10")))
+            .execute(&mut conn)
+            .unwrap();
+
+        diesel::insert_into(transcript_segments::table)
+            .values(&NewTranscriptSegment {
+                audio_file_id,
+                speaker_id: None,
+                start_time: 20.0,
+                end_time: 24.0,
+                text: "giao hàng ở bến xe".to_string(),
+                words: None,
+                channel: None,
+            })
+            .execute(&mut conn)
+            .unwrap();
+        diesel::insert_into(alert_words::table)
+            .values(&NewAlertWord {
+                keyword: "giao hàng".to_string(),
+                category: "drugs".to_string(),
+                description: None,
+            })
+            .execute(&mut conn)
+            .unwrap();
+        diesel::insert_into(redactions::table)
+            .values(&NewRedaction {
+                audio_file_id,
+                start_time: 22.0,
+                end_time: 23.0,
+                mode: "silence".to_string(),
+                reason: "address".to_string(),
+                created_by: None,
+            })
+            .execute(&mut conn)
+            .unwrap();
+        diesel::insert_into(bookmarks::table)
+            .values(&NewBookmark {
+                audio_file_id,
+                start_time: 5.0,
+                end_time: 6.0,
+                title: "greeting".to_string(),
+                description: None,
+                severity: "low".to_string(),
+                created_by: None,
+            })
+            .execute(&mut conn)
+            .unwrap();
+
+        let events = build_case_timeline(&mut conn, case_id).unwrap();
+        let kinds: Vec<&str> = events.iter().map(|e| e.kind.as_str()).collect();
+        assert_eq!(kinds, vec!["bookmark", "alert_hit"]);
+
+        assert_eq!(events[0].absolute_time, Some(time("2026-01-03 14:22:15")));
+        let hit = &events[1];
+        assert_eq!(hit.title, "giao hàng");
+        assert_eq!(hit.detail.as_deref(), Some("[REDACTED]"));
+        assert_eq!(hit.absolute_time, Some(time("2026-01-03 14:22:30")));
+    }
+}
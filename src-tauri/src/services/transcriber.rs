@@ -0,0 +1,89 @@
+//! Transcriber service: runs a bundled whisper.cpp CLI on CPU
+//!
+//! The binary is located like ffmpeg (`SPEXOR_WHISPER`, next to the
+//! executable, or PATH); the model with `SPEXOR_WHISPER_MODEL` or under
+//! `models/` in the app data directory.
+use crate::services::chunking_service::ChunkSegment;
+use crate::services::ffmpeg::tool_binary;
+use crate::services::storage::app_dir;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const DEFAULT_MODEL: &str = "ggml-large-v3.bin";
+
+#[derive(Deserialize)]
+struct WhisperOutput {
+    transcription: Vec<WhisperSegment>,
+}
+
+#[derive(Deserialize)]
+struct WhisperSegment {
+    offsets: WhisperOffsets,
+    text: String,
+}
+
+/// Milliseconds from the start of the input file
+#[derive(Deserialize)]
+struct WhisperOffsets {
+    from: i64,
+    to: i64,
+}
+
+fn model_path() -> PathBuf {
+    std::env::var("SPEXOR_WHISPER_MODEL")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| app_dir().join("models").join(DEFAULT_MODEL))
+}
+
+/// Transcribe a 16 kHz mono WAV file. Segment times are relative to the file.
+pub fn transcribe_wav(path: &Path, threads: usize) -> Result<Vec<ChunkSegment>, String> {
+    let output_base = path.with_extension("");
+    let output = Command::new(tool_binary("whisper"))
+        .arg("-m")
+        .arg(model_path())
+        .arg("-f")
+        .arg(path)
+        .args([
+            "-t",
+            &threads.max(1).to_string(),
+            "-l",
+            "auto",
+            "-np",
+            "-oj",
+        ])
+        .arg("-of")
+        .arg(&output_base)
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                "Transcriber not found (set SPEXOR_WHISPER)".to_string()
+            }
+            _ => format!("Transcriber I/O error: {}", e),
+        })?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Transcriber failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let json_path = output_base.with_extension("json");
+    let raw = std::fs::read_to_string(&json_path)
+        .map_err(|e| format!("Failed to read transcriber output: {}", e))?;
+    let _ = std::fs::remove_file(&json_path);
+
+    let parsed: WhisperOutput =
+        serde_json::from_str(&raw).map_err(|e| format!("Invalid transcriber output: {}", e))?;
+
+    Ok(parsed
+        .transcription
+        .into_iter()
+        .map(|s| ChunkSegment {
+            start: s.offsets.from as f32 / 1000.0,
+            end: s.offsets.to as f32 / 1000.0,
+            text: s.text.trim().to_string(),
+        })
+        .collect())
+}
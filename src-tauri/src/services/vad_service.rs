@@ -0,0 +1,269 @@
+//! Voice activity detection service (energy based, CPU only)
+use crate::models::TimeRange;
+use serde::{Deserialize, Serialize};
+
+/// Sample rate audio is decoded at for analysis
+pub const VAD_SAMPLE_RATE: u32 = 16000;
+
+/// Tunable VAD parameters (stored with each analysis for reproducibility)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VadParams {
+    /// Analysis frame length in milliseconds
+    pub frame_ms: u32,
+    /// Fixed speech threshold in dBFS; estimated from the noise floor when absent
+    pub threshold_db: Option<f32>,
+    /// Margin above the estimated noise floor, in dB
+    pub margin_db: f32,
+    /// Speech shorter than this (seconds) is dropped
+    pub min_speech: f32,
+    /// Pauses shorter than this (seconds) are merged into the surrounding speech
+    pub min_silence: f32,
+    /// Silences at least this long (seconds) are reported as long gaps
+    pub long_gap: f32,
+}
+
+impl Default for VadParams {
+    fn default() -> Self {
+        Self {
+            frame_ms: 30,
+            threshold_db: None,
+            margin_db: 10.0,
+            min_speech: 0.25,
+            min_silence: 0.4,
+            long_gap: 5.0,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct VadOutput {
+    pub duration: f32,
+    pub speech_duration: f32,
+    pub regions: Vec<TimeRange>,
+    pub long_gaps: Vec<TimeRange>,
+}
+
+/// Streaming speech detector: fed decoded samples block by block, so only one
+/// energy value per frame is kept instead of the whole recording
+pub struct SpeechDetector {
+    params: VadParams,
+    sample_rate: u32,
+    frame_len: usize,
+    /// Power summed over the frame being filled, and its sample count
+    frame_power: f32,
+    frame_filled: usize,
+    energies: Vec<f32>,
+    total_samples: u64,
+}
+
+impl SpeechDetector {
+    pub fn new(sample_rate: u32, params: &VadParams) -> Self {
+        let frame_len =
+            ((u64::from(sample_rate) * u64::from(params.frame_ms.max(1))) / 1000).max(1) as usize;
+        Self {
+            params: params.clone(),
+            sample_rate,
+            frame_len,
+            frame_power: 0.0,
+            frame_filled: 0,
+            energies: Vec::new(),
+            total_samples: 0,
+        }
+    }
+
+    pub fn push(&mut self, samples: &[f32]) {
+        self.total_samples += samples.len() as u64;
+        for sample in samples {
+            self.frame_power += sample * sample;
+            self.frame_filled += 1;
+            if self.frame_filled == self.frame_len {
+                self.close_frame();
+            }
+        }
+    }
+
+    fn close_frame(&mut self) {
+        let power = self.frame_power / self.frame_filled as f32;
+        self.energies.push(10.0 * (power + 1e-10).log10());
+        self.frame_power = 0.0;
+        self.frame_filled = 0;
+    }
+
+    pub fn finish(mut self) -> VadOutput {
+        if self.frame_filled > 0 {
+            self.close_frame();
+        }
+        let duration = self.total_samples as f32 / self.sample_rate as f32;
+        let frame_secs = self.frame_len as f32 / self.sample_rate as f32;
+        detect_regions(&self.energies, frame_secs, duration, &self.params)
+    }
+}
+
+/// Detect speech regions in mono samples
+pub fn detect_speech(samples: &[f32], sample_rate: u32, params: &VadParams) -> VadOutput {
+    let mut detector = SpeechDetector::new(sample_rate, params);
+    detector.push(samples);
+    detector.finish()
+}
+
+/// Turn per-frame energies (dBFS) into speech regions and long gaps
+fn detect_regions(
+    energies: &[f32],
+    frame_secs: f32,
+    duration: f32,
+    params: &VadParams,
+) -> VadOutput {
+    let threshold = params.threshold_db.unwrap_or_else(|| {
+        let mut sorted = energies.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let noise_floor = sorted.get(sorted.len() / 10).copied().unwrap_or(-100.0);
+        (noise_floor + params.margin_db).max(-55.0)
+    });
+
+    // Runs of frames above the threshold
+    let mut raw: Vec<TimeRange> = Vec::new();
+    let mut run_start: Option<usize> = None;
+    for (i, energy) in energies.iter().enumerate() {
+        match (*energy > threshold, run_start) {
+            (true, None) => run_start = Some(i),
+            (false, Some(start)) => {
+                raw.push(TimeRange {
+                    start: start as f32 * frame_secs,
+                    end: i as f32 * frame_secs,
+                });
+                run_start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(start) = run_start {
+        raw.push(TimeRange {
+            start: start as f32 * frame_secs,
+            end: duration,
+        });
+    }
+
+    // Bridge short pauses, then drop blips
+    let mut merged: Vec<TimeRange> = Vec::new();
+    for range in raw {
+        match merged.last_mut() {
+            Some(last) if range.start - last.end < params.min_silence => last.end = range.end,
+            _ => merged.push(range),
+        }
+    }
+    let regions: Vec<TimeRange> = merged
+        .into_iter()
+        .filter(|r| r.length() >= params.min_speech)
+        .collect();
+
+    let mut long_gaps = Vec::new();
+    let mut cursor = 0.0;
+    for region in regions.iter().chain(std::iter::once(&TimeRange {
+        start: duration,
+        end: duration,
+    })) {
+        if region.start - cursor >= params.long_gap {
+            long_gaps.push(TimeRange {
+                start: cursor,
+                end: region.start,
+            });
+        }
+        cursor = region.end;
+    }
+
+    VadOutput {
+        duration,
+        speech_duration: regions.iter().map(|r| r.length()).sum(),
+        regions,
+        long_gaps,
+    }
+}
+
+/// Turn speech regions into ranges for the transcriber: padded, merged when
+/// they touch, and split so no range exceeds `max_length` seconds
+pub fn transcription_ranges(
+    regions: &[TimeRange],
+    duration: f32,
+    padding: f32,
+    max_length: f32,
+) -> Vec<TimeRange> {
+    let mut ranges: Vec<TimeRange> = Vec::new();
+    for region in regions {
+        let padded = TimeRange {
+            start: (region.start - padding).max(0.0),
+            end: (region.end + padding).min(duration),
+        };
+        match ranges.last_mut() {
+            Some(last) if padded.start <= last.end => last.end = padded.end,
+            _ => ranges.push(padded),
+        }
+    }
+
+    ranges
+        .into_iter()
+        .flat_map(|range| {
+            let pieces = (range.length() / max_length).ceil().max(1.0) as usize;
+            let step = range.length() / pieces as f32;
+            (0..pieces).map(move |i| TimeRange {
+                start: range.start + step * i as f32,
+                end: if i + 1 == pieces {
+                    range.end
+                } else {
+                    range.start + step * (i + 1) as f32
+                },
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_speech_in_silence() {
+        let rate = VAD_SAMPLE_RATE as usize;
+        // 2s near-silence, 3s tone, 8s near-silence
+        let mut samples = vec![0.0005f32; rate * 2];
+        samples.extend((0..rate * 3).map(|i| 0.3 * (i as f32 * 0.2).sin()));
+        samples.extend(vec![0.0005f32; rate * 8]);
+
+        let output = detect_speech(&samples, VAD_SAMPLE_RATE, &VadParams::default());
+
+        assert_eq!(output.regions.len(), 1);
+        assert!((output.regions[0].start - 2.0).abs() < 0.05);
+        assert!((output.regions[0].end - 5.0).abs() < 0.05);
+        assert_eq!(output.long_gaps.len(), 1);
+        assert!((output.long_gaps[0].length() - 8.0).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_streaming_matches_whole_buffer() {
+        let rate = VAD_SAMPLE_RATE as usize;
+        let mut samples = vec![0.0005f32; rate];
+        samples.extend((0..rate * 2).map(|i| 0.3 * (i as f32 * 0.2).sin()));
+        samples.extend(vec![0.0005f32; rate + 123]);
+        let params = VadParams::default();
+
+        let whole = detect_speech(&samples, VAD_SAMPLE_RATE, &params);
+        let mut detector = SpeechDetector::new(VAD_SAMPLE_RATE, &params);
+        for block in samples.chunks(1000) {
+            detector.push(block);
+        }
+        let streamed = detector.finish();
+
+        assert_eq!(whole.regions, streamed.regions);
+        assert_eq!(whole.duration, streamed.duration);
+
+        // Frame lengths beyond u32 range must not overflow
+        let huge = VadParams {
+            frame_ms: u32::MAX,
+            ..VadParams::default()
+        };
+        assert_eq!(
+            SpeechDetector::new(VAD_SAMPLE_RATE, &huge).frame_len,
+            (u64::from(VAD_SAMPLE_RATE) * u64::from(u32::MAX) / 1000) as usize
+        );
+    }
+}
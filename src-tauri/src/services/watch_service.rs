@@ -0,0 +1,347 @@
+//! Watch folder service: polls drop folders and ingests new audio files once they are stable
+use crate::models::WatchFolder;
+use crate::schema::{audio_files, watch_folder_files, watch_folders};
+use crate::services::activity_service::log_activity;
+use crate::services::get_pool;
+use crate::services::import_service::{
+    import_audio_file, is_supported_audio, scan_files, DuplicatePolicy, ImportReport,
+};
+use crate::services::notification_service::notify;
+use diesel::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+/// What happens to a file after it was ingested
+pub const WATCH_POST_ACTIONS: [&str; 2] = ["leave", "move"];
+
+/// Subfolder ingested files are moved to when no destination is configured
+pub const DEFAULT_MOVE_DIR: &str = "imported";
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A file is ingested once its size and modification time stopped changing for this long
+const SETTLE_TIME: Duration = Duration::from_secs(10);
+
+/// Size and modification time (unix milliseconds) of a file, as last observed
+type Snapshot = (i64, Option<i64>);
+
+#[derive(Default)]
+struct WatchState {
+    /// Files seen but not yet stable, with the time their snapshot last changed
+    pending: HashMap<PathBuf, (Snapshot, Instant)>,
+}
+
+fn snapshot(metadata: &std::fs::Metadata) -> Snapshot {
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|elapsed| elapsed.as_millis() as i64);
+    (metadata.len() as i64, modified)
+}
+
+/// Whether a file name matches one of the ';'-separated wildcard patterns
+/// (`*` and `?`, case-insensitive). An empty pattern matches everything.
+pub fn matches_pattern(pattern: &str, file_name: &str) -> bool {
+    let patterns: Vec<&str> = pattern
+        .split(';')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect();
+
+    patterns.is_empty()
+        || patterns
+            .iter()
+            .any(|p| wildcard_match(&p.to_lowercase(), &file_name.to_lowercase()))
+}
+
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // Let the last '*' swallow one more character
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, t));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Start the background thread polling all active watch folders (called once at startup)
+pub fn start_watch_folders() {
+    std::thread::Builder::new()
+        .name("watch-folders".to_string())
+        .spawn(|| {
+            let mut state = WatchState::default();
+            loop {
+                if let Err(e) = poll(&mut state) {
+                    eprintln!("Watch folder scan failed: {}", e);
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            }
+        })
+        .expect("Failed to start watch folder thread");
+}
+
+fn poll(state: &mut WatchState) -> Result<(), String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+
+    let folders = watch_folders::table
+        .filter(watch_folders::is_active.eq(1))
+        .load::<WatchFolder>(&mut conn)
+        .map_err(|e| e.to_string())?;
+
+    let mut seen = HashSet::new();
+    for folder in &folders {
+        // One broken folder must not stop the others from being ingested
+        if let Err(e) = poll_folder(&mut conn, folder, state, &mut seen) {
+            eprintln!("Failed to process watch folder {}: {}", folder.path, e);
+        }
+    }
+
+    // Forget files that disappeared before they settled
+    state.pending.retain(|path, _| seen.contains(path));
+    Ok(())
+}
+
+fn poll_folder(
+    conn: &mut SqliteConnection,
+    folder: &WatchFolder,
+    state: &mut WatchState,
+    seen: &mut HashSet<PathBuf>,
+) -> Result<(), String> {
+    let files = scan_files(Path::new(&folder.path), false)
+        .map_err(|e| format!("Failed to scan folder: {}", e))?
+        .files;
+
+    // Files already handled (only relevant for folders that leave files in place)
+    let handled: HashMap<String, Snapshot> = watch_folder_files::table
+        .filter(watch_folder_files::watch_folder_id.eq(folder.id))
+        .select((
+            watch_folder_files::file_path,
+            watch_folder_files::file_size,
+            watch_folder_files::modified_ms,
+        ))
+        .load::<(String, i64, Option<i64>)>(conn)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|(path, size, modified)| (path, (size, modified)))
+        .collect();
+
+    let mut ready = Vec::new();
+    for path in files {
+        let matches = path
+            .file_name()
+            .map(|name| matches_pattern(&folder.file_pattern, &name.to_string_lossy()))
+            .unwrap_or(false);
+        if !matches || !is_supported_audio(&path) {
+            continue;
+        }
+        let Ok(metadata) = std::fs::metadata(&path) else {
+            continue;
+        };
+        let snapshot = snapshot(&metadata);
+        if handled.get(path.to_string_lossy().as_ref()) == Some(&snapshot) {
+            continue;
+        }
+        seen.insert(path.clone());
+
+        match state.pending.get(&path) {
+            Some((previous, since)) if *previous == snapshot => {
+                if since.elapsed() >= SETTLE_TIME {
+                    state.pending.remove(&path);
+                    ready.push((path, snapshot));
+                }
+            }
+            _ => {
+                state.pending.insert(path, (snapshot, Instant::now()));
+            }
+        }
+    }
+
+    if !ready.is_empty() {
+        ingest(conn, folder, ready)?;
+    }
+
+    diesel::update(watch_folders::table.find(folder.id))
+        .set(watch_folders::last_scan_at.eq(chrono::Utc::now().naive_utc()))
+        .execute(conn)
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Import one batch of stable files from a watch folder, then notify
+fn ingest(
+    conn: &mut SqliteConnection,
+    folder: &WatchFolder,
+    files: Vec<(PathBuf, Snapshot)>,
+) -> Result<(), String> {
+    let mut results = Vec::with_capacity(files.len());
+
+    for (path, snapshot) in files {
+        if folder.post_action == "move" {
+            let destination = folder
+                .move_to
+                .as_ref()
+                .map(PathBuf::from)
+                .unwrap_or_else(|| Path::new(&folder.path).join(DEFAULT_MOVE_DIR));
+
+            match move_file(&path, &destination) {
+                Ok(moved) => results.push(import_audio_file(
+                    conn,
+                    folder.case_id,
+                    &moved,
+                    DuplicatePolicy::Skip,
+                )),
+                Err(e) => eprintln!("Failed to move {}: {}", path.display(), e),
+            }
+            continue;
+        }
+
+        let file_path = path.to_string_lossy().to_string();
+        let (file_size, modified_ms) = snapshot;
+        diesel::insert_into(watch_folder_files::table)
+            .values((
+                watch_folder_files::watch_folder_id.eq(folder.id),
+                watch_folder_files::file_path.eq(&file_path),
+                watch_folder_files::file_size.eq(file_size),
+                watch_folder_files::modified_ms.eq(modified_ms),
+            ))
+            .on_conflict((
+                watch_folder_files::watch_folder_id,
+                watch_folder_files::file_path,
+            ))
+            .do_update()
+            .set((
+                watch_folder_files::file_size.eq(file_size),
+                watch_folder_files::modified_ms.eq(modified_ms),
+                watch_folder_files::handled_at.eq(chrono::Utc::now().naive_utc()),
+            ))
+            .execute(conn)
+            .map_err(|e| e.to_string())?;
+
+        // Already registered from this folder (e.g. handled before this was tracked)
+        let known = audio_files::table
+            .filter(audio_files::case_id.eq(folder.case_id))
+            .filter(audio_files::file_path.eq(&file_path))
+            .count()
+            .get_result::<i64>(conn)
+            .map_err(|e| e.to_string())?;
+        if known == 0 {
+            results.push(import_audio_file(
+                conn,
+                folder.case_id,
+                &path,
+                DuplicatePolicy::Skip,
+            ));
+        }
+    }
+
+    if results.is_empty() {
+        return Ok(());
+    }
+
+    let report = ImportReport::from_results(results);
+    let summary = format!(
+        "{} imported, {} duplicates, {} failed",
+        report.imported + report.linked,
+        report.duplicates,
+        report.failed
+    );
+
+    log_activity(
+        conn,
+        folder.created_by,
+        "import",
+        "case",
+        Some(folder.case_id),
+        Some(
+            serde_json::json!({
+                "operation": "watch_folder",
+                "watch_folder_id": folder.id,
+                "folder": folder.path,
+                "total": report.total,
+                "imported": report.imported,
+                "linked": report.linked,
+                "duplicates": report.duplicates,
+                "failed": report.failed,
+            })
+            .to_string(),
+        ),
+    )
+    .map_err(|e| e.to_string())?;
+
+    notify(
+        conn,
+        None,
+        if report.failed > 0 {
+            "system"
+        } else {
+            "success"
+        },
+        "import",
+        format!("Watch folder import: {} file(s)", report.total),
+        Some(format!("{} ({})", summary, folder.path)),
+        Some(("case", folder.case_id)),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Move a file into a directory without overwriting, falling back to
+/// copy + delete across file systems. Returns the new path.
+fn move_file(path: &Path, dir: &Path) -> std::io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+
+    let mut destination = dir.join(format!("{}{}", stem, extension));
+    let mut counter = 1;
+    while destination.exists() {
+        destination = dir.join(format!("{}_{}{}", stem, counter, extension));
+        counter += 1;
+    }
+
+    if std::fs::rename(path, &destination).is_err() {
+        std::fs::copy(path, &destination)?;
+        std::fs::remove_file(path)?;
+    }
+    Ok(destination)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern("*", "call.wav"));
+        assert!(matches_pattern("", "call.wav"));
+        assert!(matches_pattern("*.wav; *.AMR", "20260103_142210.amr"));
+        assert!(matches_pattern("call_??.mp3", "CALL_01.mp3"));
+        assert!(matches_pattern("*_*_*.wav", "a_b_c.wav"));
+        assert!(!matches_pattern("*.wav", "call.wav.part"));
+        assert!(!matches_pattern("call_??.mp3", "call_1.mp3"));
+    }
+}
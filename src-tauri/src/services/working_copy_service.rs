@@ -0,0 +1,330 @@
+//! Working copy service: canonical derivatives of evidence files
+//!
+//! Handset formats (AMR, WMA, 3GP) are slow to decode and often unplayable in
+//! the webview. Each recording gets a 16 kHz mono WAV "working" copy used by
+//! analysis features and, when needed, an MP3 "playback" copy. The original
+//! file is never modified.
+use crate::models::{AudioDerivative, AudioFile, NewAudioDerivative};
+use crate::schema::{audio_derivatives, audio_files};
+use crate::services::encryption_service::{read_range, seal_stored_file, ByteRange};
+use crate::services::ffmpeg::{run_ffmpeg, OutputFormat};
+use crate::services::get_pool;
+use crate::services::storage::{sha256_file, storage_dir};
+use diesel::prelude::*;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::sync::OnceLock;
+
+/// Sample rate of the analysis working copy
+pub const WORKING_SAMPLE_RATE: u32 = 16000;
+
+/// Extensions the webview plays natively (lowercase)
+pub const WEB_PLAYABLE_EXTENSIONS: [&str; 10] = [
+    "wav", "mp3", "m4a", "aac", "ogg", "oga", "opus", "flac", "webm", "mp4",
+];
+
+/// Whether the original needs a transcoded copy to be played in the webview
+pub fn needs_playback_copy(path: &Path) -> bool {
+    !path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| WEB_PLAYABLE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Latest derivative of a kind whose file still exists on disk
+fn find_copy(
+    conn: &mut SqliteConnection,
+    audio_file_id: i32,
+    kind: &str,
+) -> QueryResult<Option<AudioDerivative>> {
+    let copies = audio_derivatives::table
+        .filter(audio_derivatives::audio_file_id.eq(audio_file_id))
+        .filter(audio_derivatives::kind.eq(kind))
+        .order(audio_derivatives::id.desc())
+        .load::<AudioDerivative>(conn)?;
+
+    Ok(copies
+        .into_iter()
+        .find(|copy| Path::new(&copy.file_path).exists()))
+}
+
+/// Copy of the same content made for another audio file (same SHA-256), if any
+fn find_shared_copy(
+    conn: &mut SqliteConnection,
+    audio: &AudioFile,
+    kind: &str,
+) -> QueryResult<Option<AudioDerivative>> {
+    let Some(sha256) = &audio.sha256 else {
+        return Ok(None);
+    };
+
+    let siblings = audio_files::table
+        .filter(audio_files::sha256.eq(sha256))
+        .filter(audio_files::id.ne(audio.id))
+        .select(audio_files::id)
+        .load::<i32>(conn)?;
+
+    for sibling in siblings {
+        if let Some(copy) = find_copy(conn, sibling, kind)? {
+            return Ok(Some(copy));
+        }
+    }
+    Ok(None)
+}
+
+/// Create the working copy (and playback copy if the format needs one) of an
+/// audio file, reusing copies that already exist
+pub fn ensure_working_copies(
+    conn: &mut SqliteConnection,
+    audio: &AudioFile,
+    user_id: Option<i32>,
+) -> Result<Vec<AudioDerivative>, String> {
+    let original = Path::new(&audio.file_path);
+    let mut kinds = vec![("working", OutputFormat::Wav)];
+    if needs_playback_copy(original) {
+        kinds.push(("playback", OutputFormat::Mp3));
+    }
+
+    let mut copies = Vec::new();
+    for (kind, format) in kinds {
+        if let Some(copy) = find_copy(conn, audio.id, kind).map_err(|e| e.to_string())? {
+            copies.push(copy);
+            continue;
+        }
+
+        let new_copy = match find_shared_copy(conn, audio, kind).map_err(|e| e.to_string())? {
+            Some(shared) => NewAudioDerivative {
+                audio_file_id: audio.id,
+                kind: kind.to_string(),
+                file_path: shared.file_path,
+                sha256: shared.sha256,
+                params: shared.params,
+                created_by: user_id,
+            },
+            None => render_copy(audio, kind, format, user_id)?,
+        };
+
+        diesel::insert_into(audio_derivatives::table)
+            .values(&new_copy)
+            .execute(conn)
+            .map_err(|e| format!("Failed to save {} copy: {}", kind, e))?;
+
+        copies.push(
+            audio_derivatives::table
+                .order(audio_derivatives::id.desc())
+                .first::<AudioDerivative>(conn)
+                .map_err(|e| e.to_string())?,
+        );
+    }
+
+    Ok(copies)
+}
+
+/// Background queue of audio files still waiting for their working copies
+static COPY_QUEUE: OnceLock<Sender<(i32, Option<i32>)>> = OnceLock::new();
+
+/// Queue working copy generation for a newly registered audio file. Copies are
+/// rendered one file at a time on a background thread; analysis falls back to
+/// the original until they exist, so failures are logged rather than returned.
+pub fn queue_working_copies(audio_file_id: i32, user_id: Option<i32>) {
+    let queue = COPY_QUEUE.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel::<(i32, Option<i32>)>();
+        std::thread::Builder::new()
+            .name("working-copies".to_string())
+            .spawn(move || {
+                for (audio_file_id, user_id) in receiver {
+                    if let Err(e) = make_working_copies(audio_file_id, user_id) {
+                        eprintln!(
+                            "Failed to create working copies for audio file {}: {}",
+                            audio_file_id, e
+                        );
+                    }
+                }
+            })
+            .expect("Failed to start working copy thread");
+        sender
+    });
+
+    if queue.send((audio_file_id, user_id)).is_err() {
+        eprintln!(
+            "Working copy queue stopped; audio file {} has no working copy",
+            audio_file_id
+        );
+    }
+}
+
+fn make_working_copies(audio_file_id: i32, user_id: Option<i32>) -> Result<(), String> {
+    let mut conn = get_pool()?.get().map_err(|e| e.to_string())?;
+    let audio = audio_files::table
+        .find(audio_file_id)
+        .first::<AudioFile>(&mut conn)
+        .map_err(|e| format!("Audio file not found: {}", e))?;
+
+    ensure_working_copies(&mut conn, &audio, user_id).map(|_| ())
+}
+
+/// Split a multi-channel recording into one mono track per channel (0-based, in
+/// channel order), reusing tracks that were already generated
+pub fn ensure_channel_tracks(
+    conn: &mut SqliteConnection,
+    audio: &AudioFile,
+    user_id: Option<i32>,
+) -> Result<Vec<AudioDerivative>, String> {
+    if audio.channels < 2 {
+        return Err("Audio file has a single channel".to_string());
+    }
+
+    let existing = audio_derivatives::table
+        .filter(audio_derivatives::audio_file_id.eq(audio.id))
+        .filter(audio_derivatives::kind.eq("channel"))
+        .load::<AudioDerivative>(conn)
+        .map_err(|e| e.to_string())?;
+
+    let format = OutputFormat::Wav;
+    let mut tracks = Vec::new();
+
+    for channel in 0..audio.channels {
+        let params = serde_json::json!({ "channel": channel }).to_string();
+
+        if let Some(track) = existing.iter().find(|d| {
+            d.params.as_deref() == Some(params.as_str()) && Path::new(&d.file_path).exists()
+        }) {
+            tracks.push(track.clone());
+            continue;
+        }
+
+        let output_path = storage_dir("channels")
+            .map_err(|e| e.to_string())?
+            .join(format!(
+                "audio{}_ch{}.{}",
+                audio.id,
+                channel,
+                format.extension()
+            ));
+
+        let mut args = vec![
+            "-i".to_string(),
+            audio.file_path.clone(),
+            "-af".to_string(),
+            format!("pan=mono|c0=c{}", channel),
+        ];
+        args.extend(format.codec_args());
+        args.push(output_path.to_string_lossy().to_string());
+
+        run_ffmpeg(&args).map_err(|e| e.to_string())?;
+        seal_stored_file(&output_path)?;
+
+        let new_derivative = NewAudioDerivative {
+            audio_file_id: audio.id,
+            kind: "channel".to_string(),
+            file_path: output_path.to_string_lossy().to_string(),
+            sha256: sha256_file(&output_path).map_err(|e| e.to_string())?,
+            params: Some(params),
+            created_by: user_id,
+        };
+
+        diesel::insert_into(audio_derivatives::table)
+            .values(&new_derivative)
+            .execute(conn)
+            .map_err(|e| format!("Failed to save channel track: {}", e))?;
+
+        tracks.push(
+            audio_derivatives::table
+                .order(audio_derivatives::id.desc())
+                .first::<AudioDerivative>(conn)
+                .map_err(|e| e.to_string())?,
+        );
+    }
+
+    Ok(tracks)
+}
+
+fn render_copy(
+    audio: &AudioFile,
+    kind: &str,
+    format: OutputFormat,
+    user_id: Option<i32>,
+) -> Result<NewAudioDerivative, String> {
+    let output_path = storage_dir("working")
+        .map_err(|e| e.to_string())?
+        .join(format!("audio{}_{}.{}", audio.id, kind, format.extension()));
+
+    let mut args = vec!["-i".to_string(), audio.file_path.clone(), "-vn".to_string()];
+    let params = if kind == "working" {
+        args.extend([
+            "-ac".to_string(),
+            "1".to_string(),
+            "-ar".to_string(),
+            WORKING_SAMPLE_RATE.to_string(),
+        ]);
+        serde_json::json!({ "format": format, "sample_rate": WORKING_SAMPLE_RATE, "channels": 1 })
+    } else {
+        serde_json::json!({ "format": format })
+    };
+    args.extend(format.codec_args());
+    args.push(output_path.to_string_lossy().to_string());
+
+    run_ffmpeg(&args).map_err(|e| e.to_string())?;
+    seal_stored_file(&output_path)?;
+
+    Ok(NewAudioDerivative {
+        audio_file_id: audio.id,
+        kind: kind.to_string(),
+        file_path: output_path.to_string_lossy().to_string(),
+        sha256: sha256_file(&output_path).map_err(|e| e.to_string())?,
+        params: Some(params.to_string()),
+        created_by: user_id,
+    })
+}
+
+/// File analysis features should decode: the working copy, or the original
+/// if no working copy was made yet
+pub fn analysis_path(conn: &mut SqliteConnection, audio: &AudioFile) -> PathBuf {
+    find_copy(conn, audio.id, "working")
+        .ok()
+        .flatten()
+        .map(|copy| PathBuf::from(copy.file_path))
+        .unwrap_or_else(|| PathBuf::from(&audio.file_path))
+}
+
+/// File the webview should play: the playback copy, or the original
+pub fn playback_path(conn: &mut SqliteConnection, audio: &AudioFile) -> PathBuf {
+    find_copy(conn, audio.id, "playback")
+        .ok()
+        .flatten()
+        .map(|copy| PathBuf::from(copy.file_path))
+        .unwrap_or_else(|| PathBuf::from(&audio.file_path))
+}
+
+/// MIME type of an audio file, for serving it to the webview
+pub fn audio_content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "wav" => "audio/wav",
+        "mp3" => "audio/mpeg",
+        "m4a" | "aac" | "mp4" => "audio/mp4",
+        "ogg" | "oga" | "opus" => "audio/ogg",
+        "flac" => "audio/flac",
+        "webm" => "audio/webm",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Part of the file the webview should play, decrypted if it is sealed, and its MIME type
+pub fn read_playback_range(
+    conn: &mut SqliteConnection,
+    audio_file_id: i32,
+    range: Option<&str>,
+) -> Result<(ByteRange, &'static str), String> {
+    let audio = audio_files::table
+        .find(audio_file_id)
+        .first::<AudioFile>(conn)
+        .map_err(|e| format!("Audio file not found: {}", e))?;
+    let path = playback_path(conn, &audio);
+
+    Ok((read_range(&path, range)?, audio_content_type(&path)))
+}
@@ -15,11 +15,14 @@ export function AudioFilesPanel({ files }: AudioFilesPanelProps) {
 
   const getStatusIcon = (status: string) => {
     switch (status) {
-      case 'completed':
+      case 'transcribed':
+      case 'reviewed':
+      case 'approved':
         return <span className="audio-status completed" title="Hoàn thành">✓</span>;
+      case 'queued':
       case 'processing':
         return <span className="audio-status processing" title="Đang xử lý">⏳</span>;
-      case 'error':
+      case 'failed':
         return <span className="audio-status error" title="Lỗi">❌</span>;
       default:
         return <span className="audio-status pending" title="Chờ xử lý">○</span>;
@@ -1,10 +1,17 @@
 import { useRef, useEffect } from 'react';
-import { useAudioStore } from '../../../stores';
+import { convertFileSrc } from '@tauri-apps/api/core';
+import { useAudioStore, useAuthStore } from '../../../stores';
 import { Waveform } from './waveform';
 
+/** Playback stream of an audio file; the backend checks the user's access to its case */
+const evidenceUrl = (audioId: number, userId?: number) =>
+  `${convertFileSrc(String(audioId), 'evidence')}?user_id=${userId ?? ''}`;
+
 export function AudioPlayer() {
   const audioRef = useRef<HTMLAudioElement>(null);
+  const userId = useAuthStore((state) => state.user?.id);
   const {
+    currentAudioId,
     isPlaying,
     currentTime,
     duration,
@@ -15,26 +22,44 @@ export function AudioPlayer() {
     setPlaybackSpeed
   } = useAudioStore();
 
-  // Demo mode - simulate playback
+  // Load the selected recording
+  useEffect(() => {
+    const audio = audioRef.current;
+    if (!audio) return;
+    if (currentAudioId === null) {
+      audio.removeAttribute('src');
+    } else {
+      audio.src = evidenceUrl(currentAudioId, userId);
+    }
+    audio.load();
+  }, [currentAudioId, userId]);
+
   useEffect(() => {
-    let interval: ReturnType<typeof setInterval>;
-    if (isPlaying && duration > 0) {
-      interval = setInterval(() => {
-        setCurrentTime(Math.min(currentTime + 0.1, duration));
-        if (currentTime >= duration) {
-          setIsPlaying(false);
-        }
-      }, 100 / playbackSpeed);
+    const audio = audioRef.current;
+    if (!audio) return;
+    if (isPlaying) {
+      audio.play().catch((error) => {
+        console.error('Failed to play audio:', error);
+        setIsPlaying(false);
+      });
+    } else {
+      audio.pause();
     }
-    return () => clearInterval(interval);
-  }, [isPlaying, currentTime, duration, playbackSpeed, setCurrentTime, setIsPlaying]);
+  }, [isPlaying, currentAudioId, setIsPlaying]);
 
-  // Set initial demo duration
   useEffect(() => {
-    if (duration === 0) {
-      setDuration(332); // 5:32 demo
+    if (audioRef.current) {
+      audioRef.current.playbackRate = playbackSpeed;
     }
-  }, [duration, setDuration]);
+  }, [playbackSpeed]);
+
+  // Seeks made elsewhere (transcript, waveform); small gaps are the player's own updates
+  useEffect(() => {
+    const audio = audioRef.current;
+    if (audio && Math.abs(audio.currentTime - currentTime) > 0.5) {
+      audio.currentTime = currentTime;
+    }
+  }, [currentTime]);
 
   const formatTime = (seconds: number) => {
     const mins = Math.floor(seconds / 60);
@@ -58,7 +83,17 @@ export function AudioPlayer() {
 
   return (
     <div className="audio-player-section">
-      <audio ref={audioRef} />
+      <audio
+        ref={audioRef}
+        onTimeUpdate={(e) => setCurrentTime(e.currentTarget.currentTime)}
+        onLoadedMetadata={(e) => {
+          e.currentTarget.playbackRate = playbackSpeed;
+          if (Number.isFinite(e.currentTarget.duration)) {
+            setDuration(e.currentTarget.duration);
+          }
+        }}
+        onEnded={() => setIsPlaying(false)}
+      />
 
       {/* Waveform */}
       <Waveform
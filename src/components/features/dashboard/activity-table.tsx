@@ -1,6 +1,6 @@
 import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import type { ActivityLog } from '../../../types';
+import type { ActivityLog, PagedResult } from '../../../types';
 
 export function ActivityTable() {
   const [activities, setActivities] = useState<ActivityLog[]>([]);
@@ -9,8 +9,10 @@ export function ActivityTable() {
   useEffect(() => {
     const fetchActivities = async () => {
       try {
-        const logs = await invoke<ActivityLog[]>('get_activity_logs');
-        setActivities(logs.slice(0, 5)); // Only show last 5
+        const logs = await invoke<PagedResult<ActivityLog>>('get_activity_logs', {
+          query: { pageSize: 5 }, // Only show last 5
+        });
+        setActivities(logs.items);
       } catch (error) {
         console.error('Failed to fetch activities:', error);
       } finally {
@@ -1,7 +1,7 @@
 import { useEffect, useState } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { useUIStore } from '../../../stores';
-import type { Page } from '../../../types';
+import { useAuthStore, useUIStore } from '../../../stores';
+import type { DashboardStats, Page } from '../../../types';
 
 interface StatData {
   icon: string;
@@ -24,42 +24,38 @@ export function StatsGrid() {
   useEffect(() => {
     const fetchStats = async () => {
       try {
-        // Fetch real data from Tauri commands
-        const [cases, speakers, alertWords, users] = await Promise.allSettled([
-          invoke<any[]>('get_cases'),
-          invoke<any[]>('get_speakers'),
-          invoke<any[]>('get_alert_words'),
-          invoke<any[]>('get_users'),
-        ]);
+        const { totals } = await invoke<DashboardStats>('get_dashboard_stats', {
+          userId: useAuthStore.getState().user?.id,
+        });
 
         setStats([
           {
             icon: '📁',
-            value: cases.status === 'fulfilled' ? cases.value.length : 0,
+            value: totals.cases,
             label: 'Tổng vụ việc',
             page: 'cases'
           },
           {
             icon: '🎵',
-            value: 0, // Audio files need case context
+            value: totals.audioFiles,
             label: 'Tổng file ghi âm',
             page: 'workspace'
           },
           {
             icon: '⚠️',
-            value: alertWords.status === 'fulfilled' ? alertWords.value.length : 0,
+            value: totals.alertWords,
             label: 'Từ ngữ cảnh báo',
             page: 'alert-words'
           },
           {
             icon: '🎤',
-            value: speakers.status === 'fulfilled' ? speakers.value.length : 0,
+            value: totals.speakers,
             label: 'Tổng số người nói',
             page: 'speakers'
           },
           {
             icon: '👥',
-            value: users.status === 'fulfilled' ? users.value.length : 0,
+            value: totals.users,
             label: 'Tổng số người dùng',
             page: 'users'
           },
@@ -0,0 +1,29 @@
+/**
+ * Helpers for paged list commands
+ */
+import { invoke } from '@tauri-apps/api/core';
+import type { PagedResult } from '../types';
+
+/** Largest page the backend returns (MAX_PAGE_SIZE) */
+const MAX_PAGE_SIZE = 500;
+
+/**
+ * Load every item of a paged list command by following `nextCursor`
+ */
+export async function fetchAllPages<T>(
+  command: string,
+  args: Record<string, unknown> = {},
+  query: Record<string, unknown> = {}
+): Promise<T[]> {
+  const items: T[] = [];
+  let cursor: string | null = null;
+  do {
+    const page: PagedResult<T> = await invoke<PagedResult<T>>(command, {
+      ...args,
+      query: { ...query, pageSize: MAX_PAGE_SIZE, cursor },
+    });
+    items.push(...page.items);
+    cursor = page.nextCursor;
+  } while (cursor);
+  return items;
+}
@@ -1,6 +1,7 @@
 import { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/core';
 import { useAuthStore } from '../stores/authStore';
+import type { EncryptionStatus } from '../types';
 
 // localStorage key for saved credentials
 const SAVED_CREDENTIALS_KEY = 'spexor_saved_credentials';
@@ -40,6 +41,8 @@ export function LoginPage() {
   const [rememberMe, setRememberMe] = useState(false);
   const [error, setError] = useState('');
   const [loading, setLoading] = useState(false);
+  const [locked, setLocked] = useState(false);
+  const [masterPassphrase, setMasterPassphrase] = useState('');
   const setUser = useAuthStore((s) => s.setUser);
 
   // Load saved credentials on mount
@@ -52,11 +55,33 @@ export function LoginPage() {
     }
   }, []);
 
+  // The encrypted store must be unlocked before the database can be used
+  useEffect(() => {
+    invoke<EncryptionStatus>('get_encryption_status')
+      .then((status) => setLocked(status.enabled && !status.unlocked))
+      .catch(() => setLocked(false));
+  }, []);
+
   const handleLogin = async (e: React.FormEvent) => {
     e.preventDefault();
     setLoading(true);
     setError('');
 
+    if (locked) {
+      try {
+        await invoke<EncryptionStatus>('unlock_encryption', {
+          passphrase: masterPassphrase,
+        });
+        setLocked(false);
+        setMasterPassphrase('');
+      } catch (err) {
+        // A wrong passphrase, a damaged keyring or a database that fails to open
+        setError(String(err));
+        setLoading(false);
+        return;
+      }
+    }
+
     try {
       const user = await invoke<{
         id: number;
@@ -133,6 +158,20 @@ export function LoginPage() {
 
           {/* Login Form */}
           <form onSubmit={handleLogin} className="login-form">
+            {/* Master passphrase for the encrypted store */}
+            {locked && (
+              <div className="form-group">
+                <label>Mật khẩu mã hóa</label>
+                <input
+                  type="password"
+                  value={masterPassphrase}
+                  onChange={(e) => setMasterPassphrase(e.target.value)}
+                  placeholder="Nhập mật khẩu mã hóa dữ liệu"
+                  autoComplete="off"
+                />
+              </div>
+            )}
+
             {/* Username Input */}
             <div className="form-group">
               <label>Tài khoản</label>
@@ -182,7 +221,7 @@ export function LoginPage() {
             <button
               type="submit"
               className="btn btn-primary login-btn"
-              disabled={loading || !username || !password}
+              disabled={loading || !username || !password || (locked && !masterPassphrase)}
             >
               {loading ? '⏳ Đang xử lý...' : '🔐 Đăng nhập'}
             </button>
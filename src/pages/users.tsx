@@ -1,7 +1,7 @@
 import { useState, useEffect } from 'react';
-import { invoke } from '@tauri-apps/api/core';
 import { useUIStore } from '../stores';
 import type { User } from '../types';
+import { fetchAllPages } from '../lib/paging';
 
 const roleLabels: Record<string, string> = {
   admin: 'Admin',
@@ -28,8 +28,7 @@ export function UsersPage() {
   const loadUsers = async () => {
     setLoading(true);
     try {
-      const data = await invoke<User[]>('get_users');
-      setUsers(data);
+      setUsers(await fetchAllPages<User>('get_users'));
     } catch (error) {
       console.error(error);
       // Use mock data for now
@@ -1,6 +1,7 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import type { TranscriptSegment, AudioFile } from '../types';
+import { useAuthStore } from './authStore';
 
 interface AudioState {
   // Current audio
@@ -107,7 +108,10 @@ export const useAudioStore = create<AudioState & AudioActions>((set, get) => ({
   fetchSegments: async (audioFileId) => {
     set({ loading: true });
     try {
-      const segments = await invoke<TranscriptSegment[]>('get_transcript_segments', { audioFileId });
+      const segments = await invoke<TranscriptSegment[]>('get_transcript_segments', {
+        audioFileId,
+        userId: useAuthStore.getState().user?.id,
+      });
       set({ segments, loading: false });
     } catch (error) {
       console.error('Failed to fetch segments:', error);
@@ -125,7 +129,10 @@ export const useAudioStore = create<AudioState & AudioActions>((set, get) => ({
 
   updateSegmentText: async (id, text) => {
     try {
-      await invoke('update_transcript_segment', { id, input: { text } });
+      await invoke('update_transcript_segment', {
+        id,
+        input: { text, user_id: useAuthStore.getState().user?.id },
+      });
       set({
         segments: get().segments.map(s =>
           s.id === id ? { ...s, text } : s
@@ -139,7 +146,10 @@ export const useAudioStore = create<AudioState & AudioActions>((set, get) => ({
 
   assignSpeaker: async (segmentId, speakerId) => {
     try {
-      await invoke('update_transcript_segment', { id: segmentId, input: { speakerId } });
+      await invoke('update_transcript_segment', {
+        id: segmentId,
+        input: { speaker_id: speakerId, user_id: useAuthStore.getState().user?.id },
+      });
       set({
         segments: get().segments.map(s =>
           s.id === segmentId ? { ...s, speakerId } : s
@@ -1,6 +1,11 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import type { Case, CreateCaseInput, UpdateCaseInput, AudioFile } from '../types';
+import { fetchAllPages } from '../lib/paging';
+import { useAuthStore } from './authStore';
+
+// Case-scoped commands check the caller's access to each case
+const currentUserId = () => useAuthStore.getState().user?.id;
 
 interface CaseState {
   cases: Case[];
@@ -30,7 +35,7 @@ export const useCaseStore = create<CaseState & CaseActions>((set, get) => ({
   fetchCases: async () => {
     set({ loading: true, error: null });
     try {
-      const cases = await invoke<Case[]>('get_cases');
+      const cases = await fetchAllPages<Case>('get_cases', { userId: currentUserId() });
       set({ cases, loading: false });
     } catch (error) {
       set({ error: String(error), loading: false });
@@ -40,7 +45,7 @@ export const useCaseStore = create<CaseState & CaseActions>((set, get) => ({
   createCase: async (input) => {
     set({ loading: true, error: null });
     try {
-      const newCase = await invoke<Case>('create_case', { input });
+      const newCase = await invoke<Case>('create_case', { input: { ...input, userId: currentUserId() } });
       set({ cases: [...get().cases, newCase], loading: false });
       return newCase;
     } catch (error) {
@@ -52,7 +57,7 @@ export const useCaseStore = create<CaseState & CaseActions>((set, get) => ({
   updateCase: async (id, input) => {
     set({ loading: true, error: null });
     try {
-      const updated = await invoke<Case>('update_case', { id, input });
+      const updated = await invoke<Case>('update_case', { id, input: { ...input, userId: currentUserId() } });
       set({
         cases: get().cases.map(c => c.id === id ? updated : c),
         currentCase: get().currentCase?.id === id ? updated : get().currentCase,
@@ -67,7 +72,7 @@ export const useCaseStore = create<CaseState & CaseActions>((set, get) => ({
   deleteCase: async (id) => {
     set({ loading: true, error: null });
     try {
-      await invoke('delete_case', { id });
+      await invoke('delete_case', { id, userId: currentUserId() });
       set({
         cases: get().cases.filter(c => c.id !== id),
         currentCase: get().currentCase?.id === id ? null : get().currentCase,
@@ -88,7 +93,7 @@ export const useCaseStore = create<CaseState & CaseActions>((set, get) => ({
 
   fetchAudioFiles: async (caseId) => {
     try {
-      const audioFiles = await invoke<AudioFile[]>('get_audio_files', { caseId });
+      const audioFiles = await invoke<AudioFile[]>('get_audio_files', { caseId, userId: currentUserId() });
       set({ audioFiles });
     } catch (error) {
       console.error('Failed to fetch audio files:', error);
@@ -1,6 +1,8 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import type { Notification } from '../types';
+import { fetchAllPages } from '../lib/paging';
+import { useAuthStore } from './authStore';
 
 interface NotificationState {
   notifications: Notification[];
@@ -32,8 +34,11 @@ export const useNotificationStore = create<NotificationState & NotificationActio
   fetchNotifications: async (_filters) => {
     set({ loading: true, error: null });
     try {
-      const notifications = await invoke<Notification[]>('get_notifications');
-      const unreadCount = notifications.filter(n => !n.isRead).length;
+      const userId = useAuthStore.getState().user?.id;
+      const [notifications, unreadCount] = await Promise.all([
+        fetchAllPages<Notification>('get_notifications', { userId }),
+        invoke<number>('get_unread_count', { userId }),
+      ]);
       set({ notifications, unreadCount, loading: false });
     } catch (error) {
       console.error('Failed to fetch notifications:', error);
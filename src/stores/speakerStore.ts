@@ -1,6 +1,7 @@
 import { create } from 'zustand';
 import { invoke } from '@tauri-apps/api/core';
 import type { Speaker, CreateSpeakerInput, VoiceSample } from '../types';
+import { fetchAllPages } from '../lib/paging';
 
 interface SpeakerState {
   speakers: Speaker[];
@@ -29,7 +30,7 @@ export const useSpeakerStore = create<SpeakerState & SpeakerActions>((set, get)
   fetchSpeakers: async () => {
     set({ loading: true, error: null });
     try {
-      const speakers = await invoke<Speaker[]>('get_speakers');
+      const speakers = await fetchAllPages<Speaker>('get_speakers');
       set({ speakers, loading: false });
     } catch (error) {
       console.error('Failed to fetch speakers:', error);
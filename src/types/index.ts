@@ -4,6 +4,10 @@
 // ============================================
 // Case types
 // ============================================
+export type CaseStatus = 'open' | 'in-progress' | 'closed' | 'archived';
+
+export type CasePriority = 'low' | 'medium' | 'high' | 'urgent';
+
 export interface Case {
   id: number;
   code: string;
@@ -11,24 +15,46 @@ export interface Case {
   description: string | null;
   createdAt: string;
   updatedAt: string;
+  status: CaseStatus;
+  priority: CasePriority;
+  tags: string[];
+  dueDate: string | null;
 }
 
 export interface CreateCaseInput {
   code: string;
   title: string;
   description?: string;
+  status?: CaseStatus;
+  priority?: CasePriority;
+  tags?: string[];
+  dueDate?: string;
+  assigneeIds?: number[];
 }
 
 export interface UpdateCaseInput {
   code?: string;
   title?: string;
   description?: string;
+  status?: CaseStatus;
+  priority?: CasePriority;
+  tags?: string[];
+  dueDate?: string;
+  clearDueDate?: boolean;
 }
 
 // ============================================
 // Audio types
 // ============================================
-export type AudioStatus = 'pending' | 'processing' | 'completed' | 'error';
+export type AudioStatus =
+  | 'uploaded'
+  | 'queued'
+  | 'processing'
+  | 'transcribed'
+  | 'reviewed'
+  | 'approved'
+  | 'failed'
+  | 'archived';
 
 export interface AudioFile {
   id: number;
@@ -185,6 +211,68 @@ export interface ActivityLog {
   createdAt: string;
 }
 
+// ============================================
+// List query types
+// ============================================
+export interface ListQuery {
+  page?: number;
+  cursor?: string;
+  pageSize?: number;
+  sortBy?: string;
+  sortDirection?: 'asc' | 'desc';
+  search?: string;
+  dateFrom?: string;
+  dateTo?: string;
+}
+
+export interface PagedResult<T> {
+  items: T[];
+  total: number;
+  page: number;
+  pageSize: number;
+  nextCursor: string | null;
+}
+
+// ============================================
+// Dashboard types
+// ============================================
+
+export interface DashboardStats {
+  dateFrom: string | null;
+  dateTo: string | null;
+  totals: {
+    cases: number;
+    audioFiles: number;
+    audioHours: number;
+    speakers: number;
+    alertWords: number;
+    users: number;
+  };
+  casesByStatus: Record<CaseStatus, number>;
+  audioPerWeek: { week: string; files: number; hours: number }[];
+  transcriptionBacklog: {
+    files: number;
+    hours: number;
+    byStatus: Record<string, number>;
+    failed: number;
+  };
+  alertHitsPerWeek: { week: string; category: string; hits: number }[];
+  topSpeakers: { speakerId: number; name: string; segments: number; seconds: number }[];
+  recentActivity: ActivityLog[];
+}
+
+// ============================================
+// Encryption types
+// ============================================
+
+export interface EncryptionStatus {
+  enabled: boolean;
+  unlocked: boolean;
+  databaseEncrypted: boolean;
+  databaseRekeyPending: boolean;
+  audioKeyId: number | null;
+}
+
 // ============================================
 // UI types
 // ============================================